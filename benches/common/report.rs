@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+/* Criterion keeps its measurements in target/criterion/<group>/<backend>/<shape>/new/{benchmark,estimates}.json.
+    After the run these are collected into one table, together with the proof sizes recorded by the benches */
+
+// (operation, backend, shape) -> proof size in bytes
+static PROOF_SIZES: Mutex<BTreeMap<(String, String, String), usize>> = Mutex::new(BTreeMap::new());
+
+pub fn record_proof_size(operation: &str, backend: &str, shape: &str, bytes: usize) {
+    println!("{}/{}/{}: proof size {} bytes", operation, backend, shape, bytes);
+    PROOF_SIZES
+        .lock()
+        .unwrap()
+        .insert((operation.to_string(), backend.to_string(), shape.to_string()), bytes);
+}
+
+fn criterion_dir() -> PathBuf {
+    match std::env::var_os("CRITERION_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let target = std::env::var_os("CARGO_TARGET_DIR").unwrap_or_else(|| "target".into());
+            PathBuf::from(target).join("criterion")
+        }
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+// Every directory below `dir` that holds a finished measurement
+fn measurements(dir: &Path, found: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if path.file_name().is_some_and(|name| name == "new") {
+            if path.join("benchmark.json").exists() && path.join("estimates.json").exists() {
+                found.push(path);
+            }
+        }
+        else {
+            measurements(&path, found);
+        }
+    }
+}
+
+// Writes verkle-results.csv and verkle-results.json next to the criterion reports
+pub fn export(operations: &[&str]) -> io::Result<()> {
+    let dir = criterion_dir();
+    let mut found: Vec<PathBuf> = Vec::new();
+    measurements(&dir, &mut found);
+    let sizes = PROOF_SIZES.lock().unwrap();
+
+    let mut rows: Vec<Value> = Vec::new();
+    for path in found {
+        let (benchmark, estimates) = match (read_json(&path.join("benchmark.json")), read_json(&path.join("estimates.json"))) {
+            (Some(benchmark), Some(estimates)) => (benchmark, estimates),
+            _ => continue,
+        };
+        let operation = benchmark["group_id"].as_str().unwrap_or_default().to_string();
+        if !operations.contains(&operation.as_str()) {
+            continue;
+        }
+        let backend = benchmark["function_id"].as_str().unwrap_or_default().to_string();
+        let shape = benchmark["value_str"].as_str().unwrap_or_default().to_string();
+        let proof_bytes = sizes.get(&(operation.clone(), backend.clone(), shape.clone())).cloned();
+        rows.push(json!({
+            "operation": operation,
+            "backend": backend,
+            "shape": shape,
+            "mean_ns": estimates["mean"]["point_estimate"].as_f64(),
+            "std_dev_ns": estimates["std_dev"]["point_estimate"].as_f64(),
+            "proof_bytes": proof_bytes,
+        }));
+    }
+    rows.sort_by_key(|row| (row["operation"].to_string(), row["backend"].to_string(), row["shape"].to_string()));
+
+    let mut csv = String::from("operation,backend,shape,mean_ns,std_dev_ns,proof_bytes\n");
+    for row in rows.iter() {
+        let field = |key: &str| match &row[key] {
+            Value::Null => String::new(),
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            field("operation"),
+            field("backend"),
+            field("shape"),
+            field("mean_ns"),
+            field("std_dev_ns"),
+            field("proof_bytes")
+        ));
+    }
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("verkle-results.csv"), csv)?;
+    fs::write(dir.join("verkle-results.json"), serde_json::to_string_pretty(&rows)?)?;
+    println!("results written to {}", dir.join("verkle-results.{csv,json}").display());
+    Ok(())
+}
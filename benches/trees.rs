@@ -0,0 +1,207 @@
+/* Benchmarks of the Verkle tree backends against a SHA-256 Merkle tree of the same width:
+    build, single and multi index proofs, verification and updates.
+
+    The tree sizes go up to 2^VERKLE_BENCH_MAX_LOG (default 12, at most 20), large trees take a long time to build.
+    Proof sizes are printed next to the times, and all results are exported as CSV and JSON,
+    see common/report.rs */
+
+use std::time::Duration;
+
+use ark_bls12_381::Fr as F;
+use criterion::{criterion_group, BatchSize, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::SeedableRng;
+use sha2::Sha256;
+
+use verkle_tree::file_io::{values_to_field, ProofBody, ProofFile};
+use verkle_tree::{paramgen_from_seed, MerkleTree, ProverParams, VerifierParams, VerkleTree as VerkleTree_kzg, VerkleTree_point};
+
+mod common;
+use common::report;
+
+const WIDTHS: [usize; 4] = [2, 4, 16, 256];
+const LOG_SIZES: [u32; 4] = [8, 12, 16, 20];
+const MULTI_PROOF_INDICES: usize = 16;
+const OPERATIONS: [&str; 5] = ["build", "prove_single", "prove_multi", "verify", "update"];
+
+fn max_log_size() -> u32 {
+    std::env::var("VERKLE_BENCH_MAX_LOG")
+        .ok()
+        .and_then(|log| log.parse().ok())
+        .unwrap_or(12)
+        .min(20)
+}
+
+// All (width, number of leaves) pairs where the number of leaves is a power of the width
+fn shapes() -> Vec<(usize, usize)> {
+    let max_log = max_log_size();
+    let mut shapes: Vec<(usize, usize)> = Vec::new();
+    for width in WIDTHS {
+        let log_width = width.trailing_zeros();
+        for log_size in LOG_SIZES {
+            if log_size <= max_log && log_size % log_width == 0 {
+                shapes.push((width, 1 << log_size));
+            }
+        }
+    }
+    shapes
+}
+
+fn shape_id(width: usize, size: usize) -> String {
+    format!("w{}_n{}", width, size)
+}
+
+fn messages(size: usize) -> Vec<Vec<u8>> {
+    (0..size).map(|i| format!("this is message number {}", i).into_bytes()).collect()
+}
+
+fn params(width: usize) -> (ProverParams, VerifierParams) {
+    paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap()
+}
+
+fn random_indices(size: usize, amount: usize) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(size as u64);
+    sample(&mut rng, size, amount.min(size)).into_vec()
+}
+
+fn proof_size(width: usize, depth: usize, indices: &[usize], body: ProofBody) -> usize {
+    let proof = ProofFile {
+        width,
+        depth,
+        indices: indices.to_vec(),
+        body,
+    };
+    proof.to_bytes().unwrap().len()
+}
+
+fn bench_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build");
+    group.sample_size(10);
+    for (width, size) in shapes() {
+        let id = shape_id(width, size);
+        let data = messages(size);
+        let (prover_params, _) = params(width);
+        group.bench_with_input(BenchmarkId::new("pointproofs", &id), &data, |b, data| {
+            b.iter(|| VerkleTree_point::new(data, width, prover_params.clone()).unwrap())
+        });
+        let field = values_to_field(&data);
+        group.bench_with_input(BenchmarkId::new("kzg", &id), &field, |b, field| {
+            b.iter(|| VerkleTree_kzg::new(field, width).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("merkle", &id), &data, |b, data| {
+            b.iter(|| MerkleTree::<Sha256>::new(data, width).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_pointproofs(c: &mut Criterion, width: usize, size: usize, single: &[usize], multi: &[usize]) {
+    let id = shape_id(width, size);
+    let data = messages(size);
+    let (prover_params, verifier_params) = params(width);
+    let mut tree = VerkleTree_point::new(&data, width, prover_params).unwrap();
+    let root = tree.root_commitment().unwrap();
+    let depth = tree.depth();
+
+    for (operation, indices) in [("prove_single", single), ("prove_multi", multi)] {
+        let proof = tree.open(indices).unwrap();
+        report::record_proof_size(operation, "pointproofs", &id, proof_size(width, depth, indices, ProofBody::Pointproofs(proof)));
+        c.benchmark_group(operation)
+            .sample_size(10)
+            .bench_function(BenchmarkId::new("pointproofs", &id), |b| b.iter(|| tree.open(indices).unwrap()));
+    }
+
+    let proof = tree.open(multi).unwrap();
+    let values: Vec<Vec<u8>> = multi.iter().map(|i| data[*i].clone()).collect();
+    c.benchmark_group("verify").sample_size(10).bench_function(BenchmarkId::new("pointproofs", &id), |b| {
+        b.iter(|| assert!(VerkleTree_point::verify_proof(&root, &proof, multi, &values, width, depth, &verifier_params)))
+    });
+
+    let mut counter = 0u64;
+    c.benchmark_group("update").sample_size(10).bench_function(BenchmarkId::new("pointproofs", &id), |b| {
+        b.iter(|| {
+            counter += 1;
+            let mut batch = tree.batch();
+            batch.insert(single[0], counter.to_le_bytes().to_vec()).unwrap();
+            batch.commit().unwrap()
+        })
+    });
+}
+
+fn bench_kzg(c: &mut Criterion, width: usize, size: usize, single: &[usize], multi: &[usize]) {
+    let id = shape_id(width, size);
+    let data: Vec<F> = values_to_field(&messages(size));
+    let tree = VerkleTree_kzg::new(&data, width).unwrap();
+    let root = tree.root_commitment().unwrap();
+    let depth = tree.depth();
+
+    for (operation, indices) in [("prove_single", single), ("prove_multi", multi)] {
+        let proof = tree.proof(indices.to_vec(), &data);
+        report::record_proof_size(operation, "kzg", &id, proof_size(width, depth, indices, ProofBody::Kzg(proof)));
+        c.benchmark_group(operation)
+            .sample_size(10)
+            .bench_function(BenchmarkId::new("kzg", &id), |b| b.iter(|| tree.proof(indices.to_vec(), &data)));
+    }
+
+    let proof = tree.proof(multi.to_vec(), &data);
+    let values: Vec<F> = multi.iter().map(|i| data[*i]).collect();
+    c.benchmark_group("verify").sample_size(10).bench_function(BenchmarkId::new("kzg", &id), |b| {
+        b.iter_batched(
+            || (proof.clone(), multi.to_vec(), values.clone()),
+            |(proof, indices, values)| assert!(VerkleTree_kzg::verify(root, proof, width, indices, depth, values, tree.setup())),
+            BatchSize::SmallInput,
+        )
+    });
+    // The KZG tree has no incremental update, there is nothing to measure besides a rebuild
+}
+
+fn bench_merkle(c: &mut Criterion, width: usize, size: usize, single: &[usize], multi: &[usize]) {
+    let id = shape_id(width, size);
+    let data = messages(size);
+    let mut tree: MerkleTree = MerkleTree::new(&data, width).unwrap();
+    let root = tree.root_commitment().unwrap();
+    let depth = tree.depth();
+
+    for (operation, indices) in [("prove_single", single), ("prove_multi", multi)] {
+        report::record_proof_size(operation, "merkle", &id, tree.proof(indices).unwrap().size_in_bytes());
+        c.benchmark_group(operation)
+            .bench_function(BenchmarkId::new("merkle", &id), |b| b.iter(|| tree.proof(indices).unwrap()));
+    }
+
+    let proof = tree.proof(multi).unwrap();
+    let values: Vec<Vec<u8>> = multi.iter().map(|i| data[*i].clone()).collect();
+    c.benchmark_group("verify").bench_function(BenchmarkId::new("merkle", &id), |b| {
+        b.iter(|| assert!(<MerkleTree>::verify(&root, &proof, multi, &values, width, depth)))
+    });
+
+    let mut counter = 0u64;
+    c.benchmark_group("update").bench_function(BenchmarkId::new("merkle", &id), |b| {
+        b.iter(|| {
+            counter += 1;
+            tree.update(single[0], &counter.to_le_bytes()).unwrap()
+        })
+    });
+}
+
+fn bench_proofs(c: &mut Criterion) {
+    for (width, size) in shapes() {
+        let single = random_indices(size, 1);
+        let multi = random_indices(size, MULTI_PROOF_INDICES);
+        bench_pointproofs(c, width, size, &single, &multi);
+        bench_kzg(c, width, size, &single, &multi);
+        bench_merkle(c, width, size, &single, &multi);
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().warm_up_time(Duration::from_millis(500)).measurement_time(Duration::from_secs(3));
+    targets = bench_build, bench_proofs
+}
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    report::export(&OPERATIONS).expect("failed to export the results");
+}
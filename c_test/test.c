@@ -0,0 +1,107 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <stdint.h>
+#include <string.h>
+#include <assert.h>
+#include "verkle_c.h"
+
+#define WIDTH 4
+#define N 64
+
+// build a tree, prove a few indices, and verify the proof before and after a serialization round trip
+int test_basic() {
+  // values of the tree
+  verkle_value values[N];
+  char buffers[N][64];
+  for (int i = 0; i < N; i++) {
+    sprintf(buffers[i], "This is value %d of the verkle tree!", i);
+    values[i].data = (const uint8_t*) buffers[i];
+    values[i].len = strlen(buffers[i]);
+  }
+
+  // generate parameters
+  char seed[] = "this is a very long seed for verkle tree tests";
+  verkle_params params;
+  assert(verkle_paramgen((const uint8_t*) seed, sizeof(seed), 0, WIDTH, &params) == VERKLE_OK);
+
+  verkle_tree tree;
+  assert(verkle_tree_new(&params, values, N, WIDTH, &tree) == VERKLE_OK);
+
+  verkle_bytes root;
+  size_t depth;
+  assert(verkle_tree_root(&tree, &root) == VERKLE_OK);
+  assert(verkle_tree_depth(&tree, &depth) == VERKLE_OK);
+  assert(depth == 3);
+
+  // prove
+  size_t indices[3] = {1, 17, 63};
+  verkle_value proven[3] = {values[1], values[17], values[63]};
+  verkle_proof proof;
+  assert(verkle_tree_prove(&tree, indices, 3, &proof) == VERKLE_OK);
+  assert(verkle_verify(&params, root.data, root.len, &proof, indices, proven, 3, WIDTH, depth) == 1);
+
+  // serialization round trip
+  verkle_bytes proof_bytes;
+  verkle_proof proof_recover;
+  assert(verkle_proof_serial(&proof, &proof_bytes) == VERKLE_OK);
+  assert(verkle_proof_deserial(proof_bytes.data, proof_bytes.len, &proof_recover) == VERKLE_OK);
+  assert(verkle_verify(&params, root.data, root.len, &proof_recover, indices, proven, 3, WIDTH, depth) == 1);
+
+  // wrong values and wrong indices are rejected
+  verkle_value wrong[3] = {values[1], values[18], values[63]};
+  size_t wrong_indices[3] = {1, 18, 63};
+  assert(verkle_verify(&params, root.data, root.len, &proof, indices, wrong, 3, WIDTH, depth) == 0);
+  assert(verkle_verify(&params, root.data, root.len, &proof, wrong_indices, proven, 3, WIDTH, depth) == 0);
+
+  verkle_free_bytes(proof_bytes);
+  verkle_free_proof(proof_recover);
+  verkle_free_proof(proof);
+  verkle_free_bytes(root);
+  verkle_free_tree(tree);
+  verkle_free_params(params);
+
+  printf("basic test: success\n");
+  return 0;
+}
+
+// errors are reported with codes instead of crashing
+int test_errors() {
+  char seed[] = "this is a very long seed for verkle tree tests";
+  verkle_params params;
+  assert(verkle_paramgen((const uint8_t*) seed, sizeof(seed), 0, WIDTH, &params) == VERKLE_OK);
+
+  verkle_value values[N - 1];
+  uint8_t data[1] = {42};
+  for (int i = 0; i < N - 1; i++) {
+    values[i].data = data;
+    values[i].len = 1;
+  }
+  verkle_tree tree;
+  // the number of values is not a power of the width
+  assert(verkle_tree_new(&params, values, N - 1, WIDTH, &tree) == VERKLE_ERR_INVALID_ARGUMENT);
+  assert(verkle_tree_new(&params, NULL, N - 1, WIDTH, &tree) == VERKLE_ERR_NULL_POINTER);
+  assert(verkle_tree_new(&params, values, 0, WIDTH, &tree) == VERKLE_ERR_INVALID_ARGUMENT);
+  assert(verkle_tree_new(&params, values, N, 1, &tree) == VERKLE_ERR_INVALID_ARGUMENT);
+  assert(verkle_tree_new(&params, values, N, 0, &tree) == VERKLE_ERR_INVALID_ARGUMENT);
+
+  // an index outside of the tree
+  assert(verkle_tree_new(&params, values, WIDTH, WIDTH, &tree) == VERKLE_OK);
+  size_t index = WIDTH;
+  verkle_proof proof;
+  assert(verkle_tree_prove(&tree, &index, 1, &proof) == VERKLE_ERR_PROOF);
+
+  // garbage proof bytes
+  uint8_t garbage[16] = {0};
+  assert(verkle_proof_deserial(garbage, sizeof(garbage), &proof) == VERKLE_ERR_SERIALIZATION);
+
+  verkle_free_tree(tree);
+  verkle_free_params(params);
+
+  printf("error test: success\n");
+  return 0;
+}
+
+int main() {
+  test_basic();
+  test_errors();
+}
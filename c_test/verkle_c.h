@@ -0,0 +1,170 @@
+/* C API of the Verkle tree (pointproofs backend) */
+
+/* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Success.
+ */
+#define VERKLE_OK 0
+
+/**
+ * A pointer argument is null.
+ */
+#define VERKLE_ERR_NULL_POINTER -1
+
+/**
+ * An argument is out of range, e.g. an index outside of the tree.
+ */
+#define VERKLE_ERR_INVALID_ARGUMENT -2
+
+/**
+ * The parameters could not be generated or the tree could not be built.
+ */
+#define VERKLE_ERR_BUILD -3
+
+/**
+ * The proof could not be generated.
+ */
+#define VERKLE_ERR_PROOF -4
+
+/**
+ * Bytes could not be (de)serialized.
+ */
+#define VERKLE_ERR_SERIALIZATION -5
+
+/**
+ * The library panicked, this is a bug.
+ */
+#define VERKLE_ERR_PANIC -6
+
+/**
+ * bytes allocated by the library, free them with verkle_free_bytes
+ */
+typedef struct verkle_bytes {
+  uint8_t *data;
+  size_t len;
+} verkle_bytes;
+
+/**
+ * prover and verifier parameters
+ */
+typedef struct verkle_params {
+  void *prover;
+  void *verifier;
+} verkle_params;
+
+/**
+ * a proof for several indices of a tree
+ */
+typedef struct verkle_proof {
+  void *data;
+} verkle_proof;
+
+/**
+ * a tree, keeps its own copy of the prover parameters
+ */
+typedef struct verkle_tree {
+  void *data;
+} verkle_tree;
+
+/**
+ * a value of the tree
+ */
+typedef struct verkle_value {
+  const uint8_t *data;
+  size_t len;
+} verkle_value;
+
+/**
+ * Free bytes returned by the library
+ */
+void verkle_free_bytes(verkle_bytes buf);
+
+/**
+ * Free parameters
+ */
+void verkle_free_params(verkle_params params);
+
+/**
+ * Free a proof
+ */
+void verkle_free_proof(verkle_proof proof);
+
+/**
+ * Free a tree
+ */
+void verkle_free_tree(verkle_tree tree);
+
+/**
+ * Generating parameters for trees of width n from a seed
+ */
+int32_t verkle_paramgen(const uint8_t *seedbuf,
+                        size_t seedlen,
+                        uint8_t ciphersuite,
+                        size_t n,
+                        verkle_params *params);
+
+/**
+ * Deserializing parameters written by pointproofs_pp_serial and pointproofs_vp_serial
+ */
+int32_t verkle_params_deserial(const uint8_t *prover_bytes,
+                               size_t prover_len,
+                               const uint8_t *verifier_bytes,
+                               size_t verifier_len,
+                               verkle_params *params);
+
+/**
+ * Deserializing bytes into a proof
+ */
+int32_t verkle_proof_deserial(const uint8_t *data, size_t len, verkle_proof *proof);
+
+/**
+ * Serializing a proof into bytes (the proof file format of the command line tool)
+ */
+int32_t verkle_proof_serial(const verkle_proof *proof, verkle_bytes *bytes);
+
+/**
+ * The number of layers of the tree, needed to verify its proofs
+ */
+int32_t verkle_tree_depth(const verkle_tree *tree, size_t *depth);
+
+/**
+ * Building a tree of the given width from n values, n has to be a power of the width
+ */
+int32_t verkle_tree_new(const verkle_params *params,
+                        const verkle_value *values,
+                        size_t n,
+                        size_t width,
+                        verkle_tree *tree);
+
+/**
+ * Proving the values at n indices, free the proof with verkle_free_proof
+ */
+int32_t verkle_tree_prove(const verkle_tree *tree,
+                          const size_t *indices,
+                          size_t n,
+                          verkle_proof *proof);
+
+/**
+ * The serialized root commitment, free it with verkle_free_bytes
+ */
+int32_t verkle_tree_root(const verkle_tree *tree, verkle_bytes *root);
+
+/**
+ * Verifying that values[i] is the value at indices[i] of the tree with the given root.
+ * Returns 1 for a valid proof, 0 for an invalid one and a negative error code otherwise.
+ */
+int32_t verkle_verify(const verkle_params *params,
+                      const uint8_t *root,
+                      size_t root_len,
+                      const verkle_proof *proof,
+                      const size_t *indices,
+                      const verkle_value *values,
+                      size_t n,
+                      size_t width,
+                      size_t depth);
@@ -0,0 +1,60 @@
+// this file is part of the pointproofs.
+// it benches the cost of generating all n proofs of a commitment,
+// via FFT (all_proofs) vs n independent sums of products (batch_new)
+
+#[macro_use]
+extern crate criterion;
+extern crate pointproofs;
+
+use criterion::Benchmark;
+use criterion::Criterion;
+use pointproofs::pairings::*;
+use std::time::Duration;
+
+criterion_group!(all_proofs, bench_all_proofs);
+criterion_main!(all_proofs);
+
+fn bench_all_proofs(c: &mut Criterion) {
+    let n_array = [32, 256, 1024];
+    let mut bench: Option<Benchmark> = None;
+    for e in n_array.iter() {
+        let n = *e;
+
+        let mut values: Vec<String> = Vec::with_capacity(n);
+        for i in 0..n {
+            values.push(format!("this is message number {}", i));
+        }
+        let indices: Vec<usize> = (0..n).collect();
+        // generate parameter for dimension n
+        let (pp, _vp) = param::paramgen_from_seed(
+            "This is a very very long seed for vector commitment benchmarking",
+            0,
+            n,
+        )
+        .unwrap();
+
+        // all proofs via FFT
+        let pp_clone = pp.clone();
+        let values_clone = values.clone();
+        let bench_str = format!("n_{}_all_proofs_fft", n);
+        let closure = move |b: &mut criterion::Bencher| {
+            b.iter(|| Proof::all_proofs(&pp_clone, &values_clone).unwrap());
+        };
+        bench = Some(match bench {
+            None => Benchmark::new(bench_str, closure),
+            Some(bench) => bench.with_function(bench_str, closure),
+        });
+
+        // all proofs one by one
+        let bench_str = format!("n_{}_all_proofs_batch_new", n);
+        let closure = move |b: &mut criterion::Bencher| {
+            b.iter(|| Proof::batch_new(&pp, &values, &indices).unwrap());
+        };
+        bench = bench.map(|bench| bench.with_function(bench_str, closure));
+    }
+
+    let bench = bench.unwrap().warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("all_proofs", bench);
+}
@@ -21,6 +21,7 @@ criterion_group!(
     randomized_batch_new_proof,
     commit_update,
     single_commit,
+    large_commit,
     aggregate,
 );
 criterion_main!(basic);
@@ -273,6 +274,30 @@ fn single_commit(c: &mut Criterion) {
         });
     }
 
+    // verification, without and with the prepared lines of the Miller loop
+    let vp_clone = vp.clone();
+    let proof = proofs[0].clone();
+    let com_clone = com.clone();
+    let value = values[0].clone();
+    let bench_str = format!("single_commit_n_{}_verify", n);
+    let bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| {
+            proof.verify(&vp_clone, &com_clone, &value, 0);
+        });
+    });
+
+    let mut vp_prepared = vp.clone();
+    vp_prepared.prepare();
+    let proof = proofs[0].clone();
+    let com_clone = com.clone();
+    let value = values[0].clone();
+    let bench_str = format!("single_commit_n_{}_verify_prepared", n);
+    let bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| {
+            proof.verify(&vp_prepared, &com_clone, &value, 0);
+        });
+    });
+
     // Verify 8 aggregated proofs
     // verification with des
     let proof = proofs[0].clone();
@@ -316,6 +341,42 @@ fn single_commit(c: &mut Criterion) {
     c.bench("basic", bench);
 }
 
+// commit and prove for large n, where they use the multi-threaded multi-exponentiation
+// run with RAYON_NUM_THREADS=1 for the single threaded numbers
+fn large_commit(c: &mut Criterion) {
+    for n in [4096usize, 16384].iter() {
+        let n = *n;
+        let mut values: Vec<String> = Vec::with_capacity(n);
+        for i in 0..n {
+            values.push(format!("this is message number {}", i));
+        }
+        // generate parameter for dimension n
+        let (pp, _vp) = param::paramgen_from_seed(
+            "This is a very very long seed for vector commitment benchmarking",
+            0,
+            n,
+        )
+        .unwrap();
+
+        let pp_clone = pp.clone();
+        let values_clone = values.clone();
+        let bench_str = format!("large_commit_n_{}_commit_new", n);
+        let bench = Benchmark::new(bench_str, move |b| {
+            b.iter(|| Commitment::new(&pp_clone, &values_clone).unwrap());
+        });
+
+        let bench_str = format!("large_commit_n_{}_proof_new", n);
+        let bench = bench.with_function(bench_str, move |b| {
+            b.iter(|| Proof::new(&pp, &values, 0).unwrap());
+        });
+
+        let bench = bench.warm_up_time(Duration::from_millis(1000));
+        let bench = bench.measurement_time(Duration::from_millis(5000));
+        let bench = bench.sample_size(10);
+        c.bench("basic", bench);
+    }
+}
+
 fn commit_update(c: &mut Criterion) {
     let n = 1024;
 
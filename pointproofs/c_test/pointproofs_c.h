@@ -37,6 +37,101 @@
 #define POINTPROOFSG2_LEN 48
 #endif
 
+/**
+ * Error code: invalid contribution to the ceremony.
+ */
+#define POINTPROOFS_ERR_CEREMONY -15
+
+/**
+ * Error code: invalid ciphersuite ID, or objects of different ciphersuites.
+ */
+#define POINTPROOFS_ERR_CIPHERSUITE -4
+
+/**
+ * Error code: only support compress=true mode.
+ */
+#define POINTPROOFS_ERR_COMPRESS -5
+
+/**
+ * Error code: invalid domain separation tag.
+ */
+#define POINTPROOFS_ERR_DST -18
+
+/**
+ * Error code: duplicated index.
+ */
+#define POINTPROOFS_ERR_DUPLICATED_INDEX -8
+
+/**
+ * Error code: length of index and proof sets do not match.
+ */
+#define POINTPROOFS_ERR_INDEX_PROOF_NOT_MATCH -9
+
+/**
+ * Error code: length of index and value sets do not match.
+ */
+#define POINTPROOFS_ERR_INDEX_VALUE_NOT_MATCH -13
+
+/**
+ * Error code: invalid index.
+ */
+#define POINTPROOFS_ERR_INVALID_INDEX -7
+
+/**
+ * Error code: invalid number of values.
+ */
+#define POINTPROOFS_ERR_INVALID_VALUE -6
+
+/**
+ * Error code: an I/O error.
+ */
+#define POINTPROOFS_ERR_IO -2
+
+/**
+ * Error code: n is too large.
+ */
+#define POINTPROOFS_ERR_MAX_N -11
+
+/**
+ * Error code: the input parameter is not correct.
+ */
+#define POINTPROOFS_ERR_PARAM -12
+
+/**
+ * Error code: the group placement of the blob does not match.
+ */
+#define POINTPROOFS_ERR_PLACEMENT -19
+
+/**
+ * Error code: the parameters cannot be restricted to this dimension.
+ */
+#define POINTPROOFS_ERR_RESTRICT -14
+
+/**
+ * Error code: the seed length is too short.
+ */
+#define POINTPROOFS_ERR_SEED_TOO_SHORT -3
+
+/**
+ * Error code: an object cannot be serialized or deserialized.
+ */
+#define POINTPROOFS_ERR_SERIALIZATION -1
+
+/**
+ * Error code: the checksum of the parameter store does not match.
+ */
+#define POINTPROOFS_ERR_STORE_CHECKSUM -17
+
+/**
+ * Error code: invalid parameter store.
+ */
+#define POINTPROOFS_ERR_STORE_FORMAT -16
+
+/**
+ * Error code: invalid sizes for commit, proof, or values for cross commit.
+ */
+#define POINTPROOFS_ERR_X_COM_SIZE -10
+
 #if !defined(GROUP_SWITCHED)
 /**
  * Size for serialized proof.
@@ -201,6 +296,26 @@ int32_t pointproofs_pp_deserial(pointproofs_pp_bytes pprover, pointproofs_pp *pr
  */
 int32_t pointproofs_pp_serial(pointproofs_pp pprover, pointproofs_pp_bytes *bytes);
 
+/**
+ * Caches the prepared Miller-loop lines of the verifier parameters in place,
+ * the later verifications with these parameters are faster.
+ * With the `group_switched` feature it changes nothing, see `VerifierParams::prepare`.
+ * The parameters shall not be used by another thread meanwhile.
+ */
+void pointproofs_prepare_verifier_params(pointproofs_vp verifier);
+
+/**
+ * update an existing proof with a list of changed values
+ */
+int32_t pointproofs_proof_batch_update(pointproofs_pp prover,
+                                       pointproofs_proof proof,
+                                       size_t idx,
+                                       const size_t *changed_idx,
+                                       const pointproofs_value *val_old,
+                                       const pointproofs_value *val_new,
+                                       size_t nvalues,
+                                       pointproofs_proof *new_proof);
+
 /**
  * Deserializeing bytes into proofs
  */
@@ -252,6 +367,19 @@ bool pointproofs_same_commit_batch_verify(pointproofs_vp verifier,
                                           const pointproofs_value *values,
                                           size_t nvalues);
 
+/**
+ * update a list of existing proofs with a same list of changed values
+ */
+int32_t pointproofs_update_many_proofs(pointproofs_pp prover,
+                                       const pointproofs_proof *proofs,
+                                       const size_t *idx,
+                                       size_t nproofs,
+                                       const size_t *changed_idx,
+                                       const pointproofs_value *val_old,
+                                       const pointproofs_value *val_new,
+                                       size_t nvalues,
+                                       pointproofs_proof *new_proofs);
+
 /**
  * verify the proof against the value and commitment
  */
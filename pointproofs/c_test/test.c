@@ -179,6 +179,8 @@ int test_same_commit_aggregation() {
   assert(pointproofs_paramgen((const uint8_t*) seed, sizeof(seed), ciphersuite, n, &pointproofs_param) == 0);
   pointproofs_pp pp = pointproofs_param.prover;
   pointproofs_vp vp = pointproofs_param.verifier;
+  // the verifications below use the prepared lines
+  pointproofs_prepare_verifier_params(vp);
 
   pointproofs_commitment commit;
   pointproofs_proof proof[32];
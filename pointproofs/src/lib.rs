@@ -4,7 +4,14 @@ extern crate bigint;
 extern crate ff_zeroize as ff;
 extern crate pairing_plus as pairing;
 extern crate pointproofs_paramgen;
+extern crate rayon;
 extern crate sha2;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate ciborium;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 pub mod pairings;
 
 #[cfg(test)]
@@ -7,6 +7,91 @@ use pairings::*;
 use std::ffi;
 use std::slice;
 
+// The C functions return 0 on success, and one of the following codes otherwise.
+// The codes are stable: a new error gets a new code, and codes are never reused.
+
+/// Error code: an object cannot be serialized or deserialized.
+pub const POINTPROOFS_ERR_SERIALIZATION: i32 = -1;
+
+/// Error code: an I/O error.
+pub const POINTPROOFS_ERR_IO: i32 = -2;
+
+/// Error code: the seed length is too short.
+pub const POINTPROOFS_ERR_SEED_TOO_SHORT: i32 = -3;
+
+/// Error code: invalid ciphersuite ID, or objects of different ciphersuites.
+pub const POINTPROOFS_ERR_CIPHERSUITE: i32 = -4;
+
+/// Error code: only support compress=true mode.
+pub const POINTPROOFS_ERR_COMPRESS: i32 = -5;
+
+/// Error code: invalid number of values.
+pub const POINTPROOFS_ERR_INVALID_VALUE: i32 = -6;
+
+/// Error code: invalid index.
+pub const POINTPROOFS_ERR_INVALID_INDEX: i32 = -7;
+
+/// Error code: duplicated index.
+pub const POINTPROOFS_ERR_DUPLICATED_INDEX: i32 = -8;
+
+/// Error code: length of index and proof sets do not match.
+pub const POINTPROOFS_ERR_INDEX_PROOF_NOT_MATCH: i32 = -9;
+
+/// Error code: invalid sizes for commit, proof, or values for cross commit.
+pub const POINTPROOFS_ERR_X_COM_SIZE: i32 = -10;
+
+/// Error code: n is too large.
+pub const POINTPROOFS_ERR_MAX_N: i32 = -11;
+
+/// Error code: the input parameter is not correct.
+pub const POINTPROOFS_ERR_PARAM: i32 = -12;
+
+/// Error code: length of index and value sets do not match.
+pub const POINTPROOFS_ERR_INDEX_VALUE_NOT_MATCH: i32 = -13;
+
+/// Error code: the parameters cannot be restricted to this dimension.
+pub const POINTPROOFS_ERR_RESTRICT: i32 = -14;
+
+/// Error code: invalid contribution to the ceremony.
+pub const POINTPROOFS_ERR_CEREMONY: i32 = -15;
+
+/// Error code: invalid parameter store.
+pub const POINTPROOFS_ERR_STORE_FORMAT: i32 = -16;
+
+/// Error code: the checksum of the parameter store does not match.
+pub const POINTPROOFS_ERR_STORE_CHECKSUM: i32 = -17;
+
+/// Error code: invalid domain separation tag.
+pub const POINTPROOFS_ERR_DST: i32 = -18;
+
+/// Error code: the group placement of the blob does not match.
+pub const POINTPROOFS_ERR_PLACEMENT: i32 = -19;
+
+/// Maps an error to its code.
+pub(crate) fn pointproofs_error_code(e: &PointproofsError) -> i32 {
+    match e {
+        PointproofsError::Serialization(_) => POINTPROOFS_ERR_SERIALIZATION,
+        PointproofsError::Io(_) => POINTPROOFS_ERR_IO,
+        PointproofsError::SeedTooShort => POINTPROOFS_ERR_SEED_TOO_SHORT,
+        PointproofsError::InvalidCiphersuite => POINTPROOFS_ERR_CIPHERSUITE,
+        PointproofsError::Compress => POINTPROOFS_ERR_COMPRESS,
+        PointproofsError::InvalidValue => POINTPROOFS_ERR_INVALID_VALUE,
+        PointproofsError::InvalidIndex => POINTPROOFS_ERR_INVALID_INDEX,
+        PointproofsError::DuplicatedIndex => POINTPROOFS_ERR_DUPLICATED_INDEX,
+        PointproofsError::IndexProofNotMatch => POINTPROOFS_ERR_INDEX_PROOF_NOT_MATCH,
+        PointproofsError::XComSize => POINTPROOFS_ERR_X_COM_SIZE,
+        PointproofsError::MaxN => POINTPROOFS_ERR_MAX_N,
+        PointproofsError::InvalidParam => POINTPROOFS_ERR_PARAM,
+        PointproofsError::IndexValueNotMatch => POINTPROOFS_ERR_INDEX_VALUE_NOT_MATCH,
+        PointproofsError::Restrict => POINTPROOFS_ERR_RESTRICT,
+        PointproofsError::Ceremony => POINTPROOFS_ERR_CEREMONY,
+        PointproofsError::StoreFormat => POINTPROOFS_ERR_STORE_FORMAT,
+        PointproofsError::StoreChecksum => POINTPROOFS_ERR_STORE_CHECKSUM,
+        PointproofsError::Dst => POINTPROOFS_ERR_DST,
+        PointproofsError::Placement => POINTPROOFS_ERR_PLACEMENT,
+    }
+}
+
 /// non-serialized
 #[repr(C)]
 #[derive(Default)]
@@ -92,10 +177,7 @@ pub unsafe extern "C" fn pointproofs_pp_serial(
 
     match pprover.serialize(&mut buf, true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, prover parameter serialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
 
     buf.shrink_to_fit();
@@ -121,10 +203,7 @@ pub unsafe extern "C" fn pointproofs_pp_deserial(
     let s: &mut [u8] = std::slice::from_raw_parts_mut(pprover.data as *mut u8, pprover.len);
     let pp = match ProverParams::deserialize(&mut &s[..], true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, prover parameter deserialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
     let buf_box = Box::new(pp);
     let pp_ptr = Box::into_raw(buf_box) as *mut ffi::c_void;
@@ -143,10 +222,7 @@ pub unsafe extern "C" fn pointproofs_vp_serial(
 
     match pverifier.serialize(&mut buf, true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, verifier parameter serialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
 
     buf.shrink_to_fit();
@@ -172,10 +248,7 @@ pub unsafe extern "C" fn pointproofs_vp_deserial(
     let s: &mut [u8] = std::slice::from_raw_parts_mut(pverifier.data as *mut u8, pverifier.len);
     let pp = match VerifierParams::deserialize(&mut &s[..], true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, verifier parameter deserialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
     let buf_box = Box::new(pp);
     let vp_ptr = Box::into_raw(buf_box) as *mut ffi::c_void;
@@ -193,10 +266,7 @@ pub unsafe extern "C" fn pointproofs_commit_serial(
     let mut buf: Vec<u8> = vec![];
     match com.serialize(&mut buf, true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, commitment serialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
     buf.shrink_to_fit();
     let mut boxed_buf = buf.into_boxed_slice();
@@ -222,10 +292,7 @@ pub unsafe extern "C" fn pointproofs_commit_deserial(
     let s: &mut [u8] = std::slice::from_raw_parts_mut(commit_bytes.data as *mut u8, commit_bytes.len);
     let com = match Commitment::deserialize(&mut &s[..], true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, commitment deserialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
     let buf_box = Box::new(com);
 
@@ -245,10 +312,7 @@ pub unsafe extern "C" fn pointproofs_proof_serial(
     let mut buf: Vec<u8> = vec![];
     match proof.serialize(&mut buf, true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, proof serialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
     buf.shrink_to_fit();
     let mut boxed_buf = buf.into_boxed_slice();
@@ -274,10 +338,7 @@ pub unsafe extern "C" fn pointproofs_proof_deserial(
     let s: &mut [u8] = std::slice::from_raw_parts_mut(proof_bytes.data as *mut u8, proof_bytes.len);
     let pr = match Proof::deserialize(&mut &s[..], true) {
         Ok(p) => p,
-        Err(_e) => {
-            println!("C wrapper, proof deserialization failed");
-            return -1;
-        }
+        Err(_e) => return POINTPROOFS_ERR_SERIALIZATION,
     };
     let buf_box = Box::new(pr);
 
@@ -297,7 +358,10 @@ pub unsafe extern "C" fn pointproofs_paramgen(
     param: *mut pointproofs_params,
 ) -> i32 {
     let seed = slice::from_raw_parts(seedbuf, seedlen);
-    let (pp, vp) = param::paramgen_from_seed(seed, ciphersuite, n).unwrap();
+    let (pp, vp) = match param::paramgen_from_seed(seed, ciphersuite, n) {
+        Ok(p) => p,
+        Err(e) => return pointproofs_error_code(&e),
+    };
 
     let buf_box = Box::new(pp);
     let pp_ptr = Box::into_raw(buf_box) as *mut ffi::c_void;
@@ -323,6 +387,16 @@ pub unsafe extern "C" fn pointproofs_free_verifier_params(vp: pointproofs_vp) {
     Box::from_raw(vp.data);
 }
 
+/// Caches the prepared Miller-loop lines of the verifier parameters in place,
+/// the later verifications with these parameters are faster.
+/// With the `group_switched` feature it changes nothing, see `VerifierParams::prepare`.
+/// The parameters shall not be used by another thread meanwhile.
+#[no_mangle]
+pub unsafe extern "C" fn pointproofs_prepare_verifier_params(verifier: pointproofs_vp) {
+    let pverifier = &mut *(verifier.data as *mut VerifierParams);
+    pverifier.prepare();
+}
+
 /// Free commitment
 #[no_mangle]
 pub unsafe extern "C" fn pointproofs_free_commit(commit: pointproofs_commitment) {
@@ -354,7 +428,10 @@ pub unsafe extern "C" fn pointproofs_commit(
         vvalues.push(pointproofs_value_slice(&e).to_vec());
     }
 
-    let com = Commitment::new(pprover, &vvalues).unwrap();
+    let com = match Commitment::new(pprover, &vvalues) {
+        Ok(p) => p,
+        Err(e) => return pointproofs_error_code(&e),
+    };
     let buf_box = Box::new(com);
 
     *commit = pointproofs_commitment {
@@ -379,7 +456,10 @@ pub unsafe extern "C" fn pointproofs_prove(
         vvalues.push(pointproofs_value_slice(&e).to_vec());
     }
 
-    let pr = Proof::new(pprover, &vvalues, idx).unwrap();
+    let pr = match Proof::new(pprover, &vvalues, idx) {
+        Ok(p) => p,
+        Err(e) => return pointproofs_error_code(&e),
+    };
     let buf_box = Box::new(pr);
 
     *proof = pointproofs_proof {
@@ -405,7 +485,10 @@ pub unsafe extern "C" fn pointproofs_prove_batch_aggregated(
         vvalues.push(pointproofs_value_slice(&e).to_vec());
     }
     let pcom = &*(commit.data as *const Commitment);
-    let pr = Proof::batch_new_aggregated(pprover, pcom, &vvalues, idx).unwrap();
+    let pr = match Proof::batch_new_aggregated(pprover, pcom, &vvalues, idx) {
+        Ok(p) => p,
+        Err(e) => return pointproofs_error_code(&e),
+    };
     let buf_box = Box::new(pr);
 
     *proof = pointproofs_proof {
@@ -432,9 +515,45 @@ pub unsafe extern "C" fn pointproofs_proof_update(
 
     let mut new_pr = pproof.clone();
 
-    new_pr
-        .update(pprover, idx, changed_idx, value_before, value_after)
-        .unwrap();
+    if let Err(e) = new_pr.update(pprover, idx, changed_idx, value_before, value_after) {
+        return pointproofs_error_code(&e);
+    }
+    let buf_box = Box::new(new_pr);
+    *new_proof = pointproofs_proof {
+        data: Box::into_raw(buf_box) as *mut ffi::c_void,
+    };
+    0
+}
+
+/// update an existing proof with a list of changed values
+#[no_mangle]
+#[cfg_attr(feature = "cargo-clippy", allow(clippy::too_many_arguments))]
+pub unsafe extern "C" fn pointproofs_proof_batch_update(
+    prover: pointproofs_pp,
+    proof: pointproofs_proof,
+    idx: libc::size_t,
+    changed_idx: *const libc::size_t,
+    val_old: *const pointproofs_value,
+    val_new: *const pointproofs_value,
+    nvalues: libc::size_t,
+    new_proof: *mut pointproofs_proof,
+) -> i32 {
+    let pprover = &*(prover.data as *const ProverParams);
+    let pproof = &*(proof.data as *const Proof);
+    let changed_index = slice::from_raw_parts::<libc::size_t>(changed_idx, nvalues);
+    let value_before: Vec<&[u8]> = slice::from_raw_parts::<pointproofs_value>(val_old, nvalues)
+        .iter()
+        .map(pointproofs_value_slice)
+        .collect();
+    let value_after: Vec<&[u8]> = slice::from_raw_parts::<pointproofs_value>(val_new, nvalues)
+        .iter()
+        .map(pointproofs_value_slice)
+        .collect();
+
+    let mut new_pr = pproof.clone();
+    if let Err(e) = new_pr.batch_update(pprover, idx, changed_index, &value_before, &value_after) {
+        return pointproofs_error_code(&e);
+    }
     let buf_box = Box::new(new_pr);
     *new_proof = pointproofs_proof {
         data: Box::into_raw(buf_box) as *mut ffi::c_void,
@@ -442,6 +561,56 @@ pub unsafe extern "C" fn pointproofs_proof_update(
     0
 }
 
+/// update a list of existing proofs with a same list of changed values
+#[no_mangle]
+#[cfg_attr(feature = "cargo-clippy", allow(clippy::too_many_arguments))]
+pub unsafe extern "C" fn pointproofs_update_many_proofs(
+    prover: pointproofs_pp,
+    proofs: *const pointproofs_proof,
+    idx: *const libc::size_t,
+    nproofs: libc::size_t,
+    changed_idx: *const libc::size_t,
+    val_old: *const pointproofs_value,
+    val_new: *const pointproofs_value,
+    nvalues: libc::size_t,
+    new_proofs: *mut pointproofs_proof,
+) -> i32 {
+    let pprover = &*(prover.data as *const ProverParams);
+    let mut proof_list: Vec<Proof> = slice::from_raw_parts::<pointproofs_proof>(proofs, nproofs)
+        .iter()
+        .map(|e| (*(e.data as *const Proof)).clone())
+        .collect();
+    let proof_indices = slice::from_raw_parts::<libc::size_t>(idx, nproofs);
+    let changed_index = slice::from_raw_parts::<libc::size_t>(changed_idx, nvalues);
+    let value_before: Vec<&[u8]> = slice::from_raw_parts::<pointproofs_value>(val_old, nvalues)
+        .iter()
+        .map(pointproofs_value_slice)
+        .collect();
+    let value_after: Vec<&[u8]> = slice::from_raw_parts::<pointproofs_value>(val_new, nvalues)
+        .iter()
+        .map(pointproofs_value_slice)
+        .collect();
+
+    if let Err(e) = Proof::update_many_proofs(
+        pprover,
+        &mut proof_list,
+        proof_indices,
+        changed_index,
+        &value_before,
+        &value_after,
+    ) {
+        return pointproofs_error_code(&e);
+    }
+    // the caller provides an array of nproofs proofs for the output
+    let output = slice::from_raw_parts_mut::<pointproofs_proof>(new_proofs, nproofs);
+    for (e, pr) in output.iter_mut().zip(proof_list.into_iter()) {
+        *e = pointproofs_proof {
+            data: Box::into_raw(Box::new(pr)) as *mut ffi::c_void,
+        };
+    }
+    0
+}
+
 /// update an existing commitment
 #[no_mangle]
 pub unsafe extern "C" fn pointproofs_commit_update(
@@ -457,9 +626,9 @@ pub unsafe extern "C" fn pointproofs_commit_update(
     let value_before = pointproofs_value_slice(&val_old);
     let value_after = pointproofs_value_slice(&val_new);
     let mut new_commit = pcom.clone();
-    new_commit
-        .update(pprover, changed_idx, value_before, value_after)
-        .unwrap();
+    if let Err(e) = new_commit.update(pprover, changed_idx, value_before, value_after) {
+        return pointproofs_error_code(&e);
+    }
     let buf_box = Box::new(new_commit);
     *new_com = pointproofs_commitment {
         data: Box::into_raw(buf_box) as *mut ffi::c_void,
@@ -522,10 +691,7 @@ pub unsafe extern "C" fn pointproofs_same_commit_aggregate(
     let agg_pr =
         match Proof::same_commit_aggregate(&pcom, &proof_list, &set_list, &vvalues, param_n) {
             Ok(p) => p,
-            Err(e) => {
-                println!("C wrapper, same commit aggregation failed: {}", e);
-                return -1;
-            }
+            Err(e) => return pointproofs_error_code(&e),
         };
     let buf_box = Box::new(agg_pr);
     *agg_proof = pointproofs_proof {
@@ -626,10 +792,7 @@ pub unsafe extern "C" fn pointproofs_x_commit_aggregate_full(
         param_n,
     ) {
         Ok(p) => p,
-        Err(e) => {
-            println!("C wrapper, x-commit aggregation failed: {}", e);
-            return -1;
-        }
+        Err(e) => return pointproofs_error_code(&e),
     };
     let buf_box = Box::new(agg_proof);
     *x_proof = pointproofs_proof {
@@ -697,10 +860,7 @@ pub unsafe extern "C" fn pointproofs_x_commit_aggregate_partial(
         param_n,
     ) {
         Ok(p) => p,
-        Err(e) => {
-            println!("C wrapper, x-commit aggregation failed: {}", e);
-            return -1;
-        }
+        Err(e) => return pointproofs_error_code(&e),
     };
     let buf_box = Box::new(agg_proof);
     *x_proof = pointproofs_proof {
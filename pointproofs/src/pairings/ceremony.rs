@@ -0,0 +1,255 @@
+//! this file is part of the pointproofs.
+//! It implements a sequential multi-party ceremony for the parameters.
+//!
+//! The ceremony starts from the parameters for alpha = 1 (`init`).
+//! Each participant in turn raises the current parameters to the powers of a secret delta
+//! (`contribute`), so that alpha becomes alpha * delta, and publishes the new parameters
+//! together with an `UpdateProof`. The secret delta is erased once the contribution is made.
+//! Anyone can check the whole transcript (`verify_transcript`); the final alpha is unknown
+//! to everyone as long as one of the participants was honest.
+
+use ff::Field;
+use pairing::{bls12_381::*, CurveAffine, CurveProjective};
+use pairings::err::*;
+use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
+use pairings::param::*;
+use pairings::*;
+use rayon::prelude::*;
+use std::sync::atomic;
+
+/// A secret scalar, erased when dropped.
+struct Secret(Fr);
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        // a volatile write followed by a fence, as the zeroize crate does,
+        // so that the compiler cannot remove the erasure
+        unsafe { std::ptr::write_volatile(&mut self.0, Fr::zero()) };
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+}
+
+/// Erases a buffer of bytes.
+fn erase(buf: &mut [u8]) {
+    for e in buf.iter_mut() {
+        unsafe { std::ptr::write_volatile(e, 0) };
+    }
+    atomic::compiler_fence(atomic::Ordering::SeqCst);
+}
+
+/// The Fiat-Shamir challenge of the proof of knowledge:
+/// it binds the proof to the parameters being updated.
+fn challenge<G: CurveAffine>(digest: &[u8], delta_g1: &G, nonce_commit: &G) -> Fr {
+    let mut buf: Vec<u8> = digest.to_vec();
+    buf.extend_from_slice(delta_g1.into_compressed().as_ref());
+    buf.extend_from_slice(nonce_commit.into_compressed().as_ref());
+    hash_to_field_pointproofs(buf)
+}
+
+/// The parameters the ceremony starts with, which are the parameters for alpha = 1.
+///     * input: ciphersuite id
+///     * input: the dimension n
+///     * output: the parameters to be updated by the first participant
+///     * error: invalid ciphersuite, or n is 0 or too large
+pub fn init(
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    init_in(ciphersuite, n)
+}
+
+/// The same as `init`, for a given group placement.
+pub fn init_in<P: GroupPlacement>(
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+    if n > 65536 || n == 0 {
+        return Err(PointproofsError::MaxN);
+    }
+    Ok(paramgen_from_alpha(&Fr::one(), ciphersuite, n))
+}
+
+/// Contributes to the ceremony.
+///     * input: the current prover parameters
+///     * input: the current verifier parameters
+///     * input: the entropy of the participant
+///     * output: the updated parameters and the proof of the update
+///     * error: the entropy is too short, or invalid parameters
+/// The entropy shall be secret and random (e.g., from the OS random generator),
+/// and shall be erased by the caller afterwards.
+/// The secret delta is derived from the entropy and the current parameters, and is erased
+/// once the update is computed. The pre-computation and the prepared lines, if any,
+/// are not carried over.
+pub fn contribute<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
+    verifier_params: &VerifierParamsIn<P>,
+    entropy: &[u8],
+) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>, UpdateProofIn<P>), PointproofsError> {
+    if entropy.len() < 32 {
+        return Err(PointproofsError::SeedTooShort);
+    }
+    let n = prover_params.n;
+    if prover_params.generators.len() != 2 * n || verifier_params.generators.len() != n || n == 0 {
+        return Err(PointproofsError::InvalidParam);
+    }
+    let digest = params_digest(prover_params, verifier_params)?;
+
+    // step 1. derive the secret delta and the nonce of the proof of knowledge
+    let mut buf: Vec<u8> = [entropy, &digest[..], &b"delta"[..]].concat();
+    let delta = Secret(hash_to_field_pointproofs(&buf));
+    erase(&mut buf);
+    let mut buf: Vec<u8> = [entropy, &digest[..], &b"nonce"[..]].concat();
+    let nonce = Secret(hash_to_field_pointproofs(&buf));
+    erase(&mut buf);
+
+    // step 2. the powers delta^1, ..., delta^{2n}
+    let mut powers: Vec<Secret> = Vec::with_capacity(2 * n);
+    let mut delta_power = Secret(Fr::one());
+    for _ in 0..2 * n {
+        delta_power.0.mul_assign(&delta.0);
+        powers.push(Secret(delta_power.0));
+    }
+
+    // step 3. g1[i] = g1[i]^{delta^{i+1}}, g2[j] = g2[j]^{delta^{j+1}};
+    // the placeholder at index n stays 0
+    let mut new_prover_params = prover_params.clone();
+    new_prover_params.generators = prover_params
+        .generators
+        .par_iter()
+        .zip(powers.par_iter())
+        .map(|(g, power)| g.mul(power.0).into_affine())
+        .collect();
+    new_prover_params.pp_len = 0;
+    new_prover_params.precomp = vec![];
+    let mut new_verifier_params = verifier_params.clone();
+    new_verifier_params.generators = verifier_params
+        .generators
+        .par_iter()
+        .zip(powers.par_iter())
+        .map(|(g, power)| g.mul(power.0).into_affine())
+        .collect();
+    new_verifier_params.pp_len = 0;
+    new_verifier_params.precomp = vec![];
+    new_verifier_params.prepared = vec![];
+    new_verifier_params.gt_elt = P::pairing(
+        new_prover_params.generators[0],
+        new_verifier_params.generators[n - 1],
+    );
+
+    // step 4. Schnorr proof of knowledge of delta
+    let delta_g1 = P::G1Affine::one().mul(delta.0).into_affine();
+    let delta_g2 = P::G2Affine::one().mul(delta.0).into_affine();
+    let nonce_commit = P::G1Affine::one().mul(nonce.0).into_affine();
+    let mut response = Secret(challenge(&digest, &delta_g1, &nonce_commit));
+    response.0.mul_assign(&delta.0);
+    response.0.add_assign(&nonce.0);
+
+    let proof = UpdateProofIn {
+        ciphersuite: prover_params.ciphersuite,
+        delta_g1,
+        delta_g2,
+        nonce_commit,
+        response: response.0,
+    };
+    Ok((new_prover_params, new_verifier_params, proof))
+}
+
+/// Verifies a contribution to the ceremony.
+///     * input: the parameters before the contribution
+///     * input: the parameters after the contribution
+///     * input: the proof of the contribution
+///     * output: Ok if the new parameters are the old ones raised to the powers of delta,
+///     and the participant knows delta
+///     * error: otherwise
+/// The new parameters are checked with `validate`.
+pub fn verify_contribution<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
+    verifier_params: &VerifierParamsIn<P>,
+    new_prover_params: &ProverParamsIn<P>,
+    new_verifier_params: &VerifierParamsIn<P>,
+    proof: &UpdateProofIn<P>,
+) -> Result<(), PointproofsError> {
+    if proof.ciphersuite != prover_params.ciphersuite
+        || proof.ciphersuite != new_prover_params.ciphersuite
+        || proof.ciphersuite != verifier_params.ciphersuite
+    {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+    if prover_params.n != new_prover_params.n || prover_params.generators.is_empty() {
+        return Err(PointproofsError::InvalidParam);
+    }
+
+    // step 1. the new parameters are powers of a same alpha'
+    validate(new_prover_params, new_verifier_params)?;
+
+    // step 2. proof of knowledge of delta: g1^response = nonce_commit * (g1^delta)^challenge
+    if proof.delta_g1.is_zero() {
+        return Err(PointproofsError::Ceremony);
+    }
+    let digest = params_digest(prover_params, verifier_params)?;
+    let mut rhs = proof
+        .delta_g1
+        .mul(challenge(&digest, &proof.delta_g1, &proof.nonce_commit));
+    rhs.add_assign_mixed(&proof.nonce_commit);
+    if P::G1Affine::one().mul(proof.response) != rhs {
+        return Err(PointproofsError::Ceremony);
+    }
+
+    // step 3. g2^delta matches g1^delta, and alpha' = alpha * delta:
+    //  e(g1^delta, generator_of_g2) = e(generator_of_g1, g2^delta)
+    //  e(g1'[0], generator_of_g2) = e(g1[0], g2^delta)
+    let mut minus_generator = P::G1::one();
+    minus_generator.negate();
+    let mut minus_old = prover_params.generators[0].into_projective();
+    minus_old.negate();
+    if P::pairing_product(
+        proof.delta_g1,
+        P::G2Affine::one(),
+        minus_generator.into_affine(),
+        proof.delta_g2,
+    ) != Fq12::one()
+        || P::pairing_product(
+            new_prover_params.generators[0],
+            P::G2Affine::one(),
+            minus_old.into_affine(),
+            proof.delta_g2,
+        ) != Fq12::one()
+    {
+        return Err(PointproofsError::Ceremony);
+    }
+    Ok(())
+}
+
+/// Verifies the transcript of a ceremony.
+///     * input: the parameters after each step, starting with the ones from `init`
+///     * input: the proofs of the contributions
+///     * output: Ok if every contribution is valid
+///     * error: otherwise, or if there is no contribution
+pub fn verify_transcript<P: GroupPlacement>(
+    params: &[(ProverParamsIn<P>, VerifierParamsIn<P>)],
+    proofs: &[UpdateProofIn<P>],
+) -> Result<(), PointproofsError> {
+    if proofs.is_empty() || params.len() != proofs.len() + 1 {
+        return Err(PointproofsError::Ceremony);
+    }
+    let (initial_prover_params, initial_verifier_params) =
+        init_in::<P>(params[0].0.ciphersuite, params[0].0.n)?;
+    if params[0].0.generators != initial_prover_params.generators
+        || params[0].1 != initial_verifier_params
+    {
+        return Err(PointproofsError::Ceremony);
+    }
+    for (i, proof) in proofs.iter().enumerate() {
+        verify_contribution(
+            &params[i].0,
+            &params[i].1,
+            &params[i + 1].0,
+            &params[i + 1].1,
+            proof,
+        )?;
+    }
+    Ok(())
+}
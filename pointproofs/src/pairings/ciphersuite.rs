@@ -0,0 +1,141 @@
+//! this file is part of the pointproofs.
+//! It defines the registry of ciphersuites.
+//! A ciphersuite defines how values are hashed into scalars,
+//! and how the scalars for aggregation and batch verification are derived.
+//!
+//! * ciphersuite 0 uses SHA-512 and a reduction, see SPEC.md;
+//! * ciphersuite 1 uses the IETF `hash_to_field` (RFC 9380), with `expand_message_xmd` and SHA-256,
+//!   and the domain separation tags `CS01_VALUE_DST` and `CS01_AGGREGATION_DST`;
+//! * other IDs can be registered with `register_ciphersuite`, e.g., for other domain separation tags.
+
+use ff::PrimeField;
+use pairing::bls12_381::*;
+use pairings::err::*;
+use pairings::hash_to_field_pointproofs::*;
+use pairings::param::Ciphersuite;
+use pairings::pointproofs_groups::PLACEMENT_TAG_MASK;
+use sha2::{Digest, Sha256, Sha512};
+use std::sync::RwLock;
+
+/// The domain separation tag for the values, in ciphersuite 1.
+pub const CS01_VALUE_DST: &[u8] = b"POINTPROOFS-V01-CS01-VALUE_XMD:SHA-256_";
+
+/// The domain separation tag for the aggregation scalars, in ciphersuite 1.
+pub const CS01_AGGREGATION_DST: &[u8] = b"POINTPROOFS-V01-CS01-AGGREGATION_XMD:SHA-256_";
+
+/// A hash from bytes into non-zero field elements.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HashToField {
+    /// SHA-512 and a reduction modulo r, as in ciphersuite 0
+    Sha512,
+    /// the IETF `hash_to_field` with `expand_message_xmd` and SHA-256, with a domain separation tag
+    XmdSha256(Vec<u8>),
+}
+
+impl HashToField {
+    /// Hashes a blob into a non-zero field element.
+    pub(crate) fn hash_to_field_repr(&self, input: &[u8]) -> FrRepr {
+        match self {
+            HashToField::Sha512 => hash_to_field_repr_pointproofs(input),
+            HashToField::XmdSha256(dst) => hash_to_field_repr_xmd_sha256(input, dst),
+        }
+    }
+
+    /// Compresses the inputs of the aggregation scalars, with the same hash function.
+    pub(crate) fn digest(&self, input: &[u8]) -> Vec<u8> {
+        match self {
+            HashToField::Sha512 => {
+                let mut hasher = Sha512::new();
+                hasher.input(input);
+                hasher.result().to_vec()
+            }
+            HashToField::XmdSha256(_) => {
+                let mut hasher = Sha256::new();
+                hasher.input(input);
+                hasher.result().to_vec()
+            }
+        }
+    }
+}
+
+/// The definition of a ciphersuite.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CiphersuiteDef {
+    /// the hash of the values into scalars
+    pub value_hash: HashToField,
+    /// the hash into the scalars t_i and t_j for aggregation and batch verification
+    pub aggregation_hash: HashToField,
+}
+
+impl CiphersuiteDef {
+    /// Hashes a value into a non-zero scalar.
+    pub(crate) fn hash_value_repr(&self, value: &[u8]) -> FrRepr {
+        self.value_hash.hash_to_field_repr(value)
+    }
+
+    /// A wrapper of `hash_value_repr` that outputs `Fr`s instead of `FrRepr`s.
+    pub(crate) fn hash_value(&self, value: &[u8]) -> Fr {
+        // the hash should already produce a valid Fr element
+        // so it is safe to unwrap here
+        Fr::from_repr(self.hash_value_repr(value)).unwrap()
+    }
+}
+
+// the ciphersuites registered at run time; 0 and 1 are built in
+static REGISTRY: RwLock<Vec<(Ciphersuite, CiphersuiteDef)>> = RwLock::new(Vec::new());
+
+/// Returns the definition of a ciphersuite, if it is supported.
+pub fn get_ciphersuite(csid: Ciphersuite) -> Option<CiphersuiteDef> {
+    match csid {
+        0 => Some(CiphersuiteDef {
+            value_hash: HashToField::Sha512,
+            aggregation_hash: HashToField::Sha512,
+        }),
+        1 => Some(CiphersuiteDef {
+            value_hash: HashToField::XmdSha256(CS01_VALUE_DST.to_vec()),
+            aggregation_hash: HashToField::XmdSha256(CS01_AGGREGATION_DST.to_vec()),
+        }),
+        _ => match REGISTRY.read() {
+            Ok(registry) => registry
+                .iter()
+                .find(|(id, _)| *id == csid)
+                .map(|(_, def)| def.clone()),
+            Err(_) => None,
+        },
+    }
+}
+
+/// Like `get_ciphersuite`, with an error for unsupported ciphersuites.
+pub(crate) fn ciphersuite_def(csid: Ciphersuite) -> Result<CiphersuiteDef, PointproofsError> {
+    get_ciphersuite(csid).ok_or_else(|| PointproofsError::InvalidCiphersuite)
+}
+
+/// Registers a new ciphersuite.
+///     * input: the ciphersuite ID
+///     * input: its definition
+///     * error: the ID is already used or not below 128, or a domain separation tag is empty
+///     or longer than 255 bytes
+/// The high bit of a serialized ciphersuite ID holds the group placement, so the IDs are below 128.
+/// A ciphersuite cannot be changed or removed once it is registered,
+/// so that objects tagged with its ID always mean the same.
+pub fn register_ciphersuite(
+    csid: Ciphersuite,
+    def: CiphersuiteDef,
+) -> Result<(), PointproofsError> {
+    for hash in [&def.value_hash, &def.aggregation_hash].iter() {
+        if let HashToField::XmdSha256(dst) = hash {
+            if dst.is_empty() || dst.len() > 255 {
+                return Err(PointproofsError::Dst);
+            }
+        }
+    }
+    let mut registry = match REGISTRY.write() {
+        Ok(registry) => registry,
+        Err(_) => return Err(PointproofsError::InvalidCiphersuite),
+    };
+    if csid <= 1 || csid & PLACEMENT_TAG_MASK != 0 || registry.iter().any(|(id, _)| *id == csid) {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+    registry.push((csid, def));
+    Ok(())
+}
@@ -2,14 +2,14 @@
 //! It defines APIs for constructing and updating commitments.
 
 use ff::{Field, PrimeField};
-use pairing::{bls12_381::*, CurveAffine, CurveProjective};
+use pairing::{bls12_381::*, CurveProjective};
+use pairings::ciphersuite::*;
 use pairings::err::*;
-use pairings::hash_to_field_pointproofs::*;
 use pairings::misc::*;
 use pairings::param::*;
 use pairings::*;
 
-impl Commitment {
+impl<P: GroupPlacement> CommitmentIn<P> {
     /// generate a new commitment.
     ///     * input: prover parameter set
     ///     * input: a list of n values
@@ -22,22 +22,20 @@ impl Commitment {
     /// In this scenario, the caller should define the right
     /// format for padding.
     pub fn new<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
+        prover_params: &ProverParamsIn<P>,
         values: &[Blob],
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        };
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
 
         if prover_params.n != values.len() {
-            return Err(ERR_INVALID_VALUE.to_owned());
+            return Err(PointproofsError::InvalidValue);
         };
 
         // hash the values into scalars
         let scalars_fr_repr: Vec<FrRepr> = values
             .iter()
-            .map(|s| hash_to_field_repr_pointproofs(s.as_ref()))
+            .map(|s| suite.hash_value_repr(s.as_ref()))
             .collect();
         let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
 
@@ -50,6 +48,39 @@ impl Commitment {
         })
     }
 
+    /// generate a new commitment to a vector of scalars.
+    ///     * input: prover parameter set
+    ///     * input: a list of n scalars
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters
+    /// unlike `new`, the scalars are committed as they are, without hashing.
+    /// This is for callers that already hold field elements and define their
+    /// own encoding; the proofs are verified with `Proof::verify_scalars`.
+    pub fn new_from_scalars(
+        prover_params: &ProverParamsIn<P>,
+        scalars: &[Fr],
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        if prover_params.n != scalars.len() {
+            return Err(PointproofsError::InvalidValue);
+        };
+
+        let scalars_fr_repr: Vec<FrRepr> = scalars.iter().map(|s| s.into_repr()).collect();
+        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+        // commit = \prod pp[i]^scalar[i]
+        let commit = pp_sum_of_prod_helper(&prover_params, &scalars_u64, 0, prover_params.n);
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            commit,
+        })
+    }
+
     /// upated an existing commitment
     ///     * input: commitment
     ///     * input: prover parameter set
@@ -60,26 +91,24 @@ impl Commitment {
     ///     * error: invalid ciphersuite, parameters
     pub fn update<Blob: AsRef<[u8]>>(
         &mut self,
-        prover_params: &ProverParams,
+        prover_params: &ProverParamsIn<P>,
         changed_index: usize,
         value_before: Blob,
         value_after: Blob,
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
         if prover_params.n <= changed_index {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         };
 
         // multiplier = hash(new_value) - hash(old_value)
-        let mut multiplier = hash_to_field_pointproofs(&value_before);
+        let mut multiplier = suite.hash_value(value_before.as_ref());
         multiplier.negate();
-        multiplier.add_assign(&hash_to_field_pointproofs(&value_after));
+        multiplier.add_assign(&suite.hash_value(value_after.as_ref()));
 
         // new_commit = old_commit * g[index]^multiplier
         let res = pp_single_exp_helper(&prover_params, multiplier, changed_index);
@@ -103,73 +132,46 @@ impl Commitment {
     /// n is invalid -- in this case the caller should call Commitment::new
     pub fn batch_update<Blob: AsRef<[u8]>>(
         &mut self,
-        prover_params: &ProverParams,
+        prover_params: &ProverParamsIn<P>,
         changed_index: &[usize],
         value_before: &[Blob],
         value_after: &[Blob],
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
         // check the parameters are valid
         for index in changed_index {
             if prover_params.n <= *index {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex);
             };
         }
         if changed_index.len() >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         }
         if changed_index.len() != value_before.len() || changed_index.len() != value_after.len() {
-            return Err(ERR_INDEX_VALUE_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexValueNotMatch);
         }
         if !misc::has_unique_elements(changed_index) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
 
         // get the scalars from the hashes
         let mut multiplier_set: Vec<FrRepr> = Vec::with_capacity(value_before.len());
         for i in 0..value_before.len() {
             // multiplier = hash(new_value) - hash(old_value)
-            let mut multiplier = hash_to_field_pointproofs(&value_before[i]);
+            let mut multiplier = suite.hash_value(value_before[i].as_ref());
             multiplier.negate();
-            multiplier.add_assign(&hash_to_field_pointproofs(&value_after[i]));
+            multiplier.add_assign(&suite.hash_value(value_after[i].as_ref()));
             multiplier_set.push(multiplier.into_repr());
         }
         let scalars_u64: Vec<&[u64; 4]> = multiplier_set.iter().map(|s| &s.0).collect();
 
-        // form the basis for `sum_of_products`
-        let basis = changed_index
-            .iter()
-            .map(|i| prover_params.generators[*i])
-            .collect::<Vec<PointproofsG1Affine>>();
-
         // compute delta = \prod g[index]^multiplier
-        let delta = {
-            // to use sum_of_products with pre_computation,
-            // we need to form the right basis
-            if prover_params.precomp.len() == 256 * prover_params.generators.len() {
-                let mut pre: Vec<PointproofsG1Affine> =
-                    Vec::with_capacity(changed_index.len() * 256);
-                for e in changed_index.iter() {
-                    pre = [
-                        pre,
-                        prover_params.precomp
-                            [changed_index[*e] * 256..(changed_index[*e] + 1) * 256]
-                            .to_vec(),
-                    ]
-                    .concat();
-                }
-                PointproofsG1Affine::sum_of_products_precomp_256(&basis, &scalars_u64, &pre)
-            } else {
-                // without pre_computation
-                PointproofsG1Affine::sum_of_products(&basis[..], &scalars_u64)
-            }
-        };
+        // the helper gathers the basis and, if any, the pre-computation of each changed index
+        let delta = pp_sum_of_prod_indexed_helper(&prover_params, &scalars_u64, changed_index);
         // new_commit = old_commit * \prod g[index]^multiplier
         self.commit.add_assign(&delta);
         Ok(())
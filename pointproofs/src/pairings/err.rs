@@ -1,15 +1,89 @@
 //! this file is part of the pointproofs.
-//! It defines a list of error messages.
-
-pub(crate) const ERR_SEED_TOO_SHORT: &str = "The seed length is too short";
-pub(crate) const ERR_CIPHERSUITE: &str = "Invalid ciphersuite ID";
-pub(crate) const ERR_COMPRESS: &str = "Only support compress=true mode";
-pub(crate) const ERR_INVALID_VALUE: &str = "Invalid number of values";
-pub(crate) const ERR_INVALID_INDEX: &str = "Invalid index";
-pub(crate) const ERR_DUPLICATED_INDEX: &str = "Duplicated index";
-pub(crate) const ERR_INDEX_PROOF_NOT_MATCH: &str = "Length of index and proof sets do not match";
-pub(crate) const ERR_X_COM_SIZE: &str =
-    "Invalid sizes for commit, proof, or values for cross commit";
-pub(crate) const ERR_MAX_N: &str = "N is too large";
-pub(crate) const ERR_PARAM: &str = "The input parameter is not correct";
-pub(crate) const ERR_INDEX_VALUE_NOT_MATCH: &str = "Length of index and value sets do not match";
+//! It defines the errors of the pointproofs APIs.
+
+use std::fmt;
+
+/// The errors returned by the pointproofs APIs.
+/// `c_api.rs` maps each of them to a stable numeric code.
+#[derive(Debug)]
+pub enum PointproofsError {
+    /// The seed length is too short
+    SeedTooShort,
+    /// Invalid ciphersuite ID, or objects of different ciphersuites
+    InvalidCiphersuite,
+    /// Only support compress=true mode
+    Compress,
+    /// Invalid number of values
+    InvalidValue,
+    /// Invalid index
+    InvalidIndex,
+    /// Duplicated index
+    DuplicatedIndex,
+    /// Length of index and proof sets do not match
+    IndexProofNotMatch,
+    /// Invalid sizes for commit, proof, or values for cross commit
+    XComSize,
+    /// N is too large
+    MaxN,
+    /// The input parameter is not correct
+    InvalidParam,
+    /// Length of index and value sets do not match
+    IndexValueNotMatch,
+    /// The parameters cannot be restricted to this dimension
+    Restrict,
+    /// Invalid contribution to the ceremony
+    Ceremony,
+    /// Invalid parameter store
+    StoreFormat,
+    /// The checksum of the parameter store does not match
+    StoreChecksum,
+    /// Invalid domain separation tag
+    Dst,
+    /// The group placement of the blob does not match
+    Placement,
+    /// An object cannot be serialized or deserialized
+    Serialization(String),
+    /// An I/O error, e.g., when reading or writing a file
+    Io(std::io::Error),
+}
+
+impl fmt::Display for PointproofsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PointproofsError::SeedTooShort => write!(f, "The seed length is too short"),
+            PointproofsError::InvalidCiphersuite => write!(f, "Invalid ciphersuite ID"),
+            PointproofsError::Compress => write!(f, "Only support compress=true mode"),
+            PointproofsError::InvalidValue => write!(f, "Invalid number of values"),
+            PointproofsError::InvalidIndex => write!(f, "Invalid index"),
+            PointproofsError::DuplicatedIndex => write!(f, "Duplicated index"),
+            PointproofsError::IndexProofNotMatch => {
+                write!(f, "Length of index and proof sets do not match")
+            }
+            PointproofsError::XComSize => write!(
+                f,
+                "Invalid sizes for commit, proof, or values for cross commit"
+            ),
+            PointproofsError::MaxN => write!(f, "N is too large"),
+            PointproofsError::InvalidParam => write!(f, "The input parameter is not correct"),
+            PointproofsError::IndexValueNotMatch => {
+                write!(f, "Length of index and value sets do not match")
+            }
+            PointproofsError::Restrict => {
+                write!(f, "The parameters cannot be restricted to this dimension")
+            }
+            PointproofsError::Ceremony => write!(f, "Invalid contribution to the ceremony"),
+            PointproofsError::StoreFormat => write!(f, "Invalid parameter store"),
+            PointproofsError::StoreChecksum => {
+                write!(f, "The checksum of the parameter store does not match")
+            }
+            PointproofsError::Dst => write!(f, "Invalid domain separation tag"),
+            PointproofsError::Placement => {
+                write!(f, "The group placement of the blob does not match")
+            }
+            PointproofsError::Serialization(e) => write!(f, "Serialization failed: {}", e),
+            PointproofsError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PointproofsError {}
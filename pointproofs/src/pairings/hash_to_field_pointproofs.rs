@@ -2,14 +2,17 @@
 //! It defines the hash_to_field functions that are more efficient than `bls::hash_to_field`
 //! The algorithms are described here:
 //! https://github.com/algorand/pointproofs/blob/master/SPEC.md#hashes
+//! It also implements the IETF hash_to_field with expand_message_xmd, for ciphersuite 1:
+//! https://www.rfc-editor.org/rfc/rfc9380.html#section-5
 use bigint::U512;
 use ff::PrimeField;
 use pairing::bls12_381::*;
 use pairing::serdes::SerDes;
+use pairings::ciphersuite::*;
 use pairings::err::*;
-use pairings::param::*;
-use pairings::Commitment;
-use sha2::{Digest, Sha512};
+use pairings::param::Ciphersuite;
+use pairings::{CommitmentIn, GroupPlacement, ProofIn};
+use sha2::{Digest, Sha256};
 use std::ops::Rem;
 
 // A wrapper of `hash_to_tj_repr` that outputs `Fr`s instead of `FrRepr`s.
@@ -17,12 +20,12 @@ use std::ops::Rem;
 /// * input: a list of k * x indices, for which we need to generate t_j
 /// * input: Value: a list of k * x messages that is commited to
 /// * output: a list of k field elements
-pub(crate) fn hash_to_tj_fr<Blob: AsRef<[u8]>>(
-    commits: &[Commitment],
+pub(crate) fn hash_to_tj_fr<P: GroupPlacement, Blob: AsRef<[u8]>>(
+    commits: &[CommitmentIn<P>],
     set: &[Vec<usize>],
     value_sub_vector: &[Vec<Blob>],
     n: usize,
-) -> Result<Vec<Fr>, String> {
+) -> Result<Vec<Fr>, PointproofsError> {
     Ok(hash_to_tj_repr(commits, set, value_sub_vector, n)?
         .iter()
         // the hash_to_ti_repr should already produce valid Fr elements
@@ -37,21 +40,26 @@ pub(crate) fn hash_to_tj_fr<Blob: AsRef<[u8]>>(
 /// * input: a list of k * x indices, for which we need to generate t_j
 /// * input: Value: a list of k * x messages that is commited to
 /// * output: a list of k field elements
-pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
-    commits: &[Commitment],
+pub(crate) fn hash_to_tj_repr<P: GroupPlacement, Blob: AsRef<[u8]>>(
+    commits: &[CommitmentIn<P>],
     set: &[Vec<usize>],
     value_sub_vector: &[Vec<Blob>],
     n: usize,
-) -> Result<Vec<FrRepr>, String> {
+) -> Result<Vec<FrRepr>, PointproofsError> {
     // check the length are correct
     if commits.len() != set.len() || commits.len() != value_sub_vector.len() {
-        return Err(ERR_X_COM_SIZE.to_owned());
+        return Err(PointproofsError::XComSize);
     };
 
-    // check the ciphersuite is supported
+    // check the ciphersuite is supported, and is the same for all commitments
+    let csid = match commits.first() {
+        Some(e) => e.ciphersuite,
+        None => return Ok(vec![]),
+    };
+    let suite = ciphersuite_def(csid)?;
     for e in commits {
-        if !check_ciphersuite(e.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+        if e.ciphersuite != csid {
+            return Err(PointproofsError::InvalidCiphersuite);
         }
     }
 
@@ -67,7 +75,7 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
         // serialize commitment
         match commits[i].serialize(&mut tmp, true) {
             Ok(_p) => _p,
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(PointproofsError::Serialization(e.to_string())),
         };
         // add the set to tmp
         for j in 0..set[i].len() {
@@ -77,29 +85,29 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
 
         // if the set leng does not mathc values, return an error
         if set[i].len() != value_sub_vector[i].len() {
-            return Err(ERR_INDEX_PROOF_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexProofNotMatch);
         }
 
         // add values to set; returns an error if index is out of range
         for j in 0..set[i].len() {
             if set[i][j] >= n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex);
             }
             let t = value_sub_vector[i][j].as_ref();
             tmp.append(&mut t.to_vec());
         }
     }
 
-    let mut hasher = Sha512::new();
-    hasher.input(tmp);
-    let digest = hasher.result();
+    let digest = suite.aggregation_hash.digest(&tmp);
 
     // formulate the output
     Ok((0..commits.len())
         .map(|i| {
             // each field element t_i is generated as
             // t_i = hash_to_field (i | C | S | m[S])
-            hash_to_field_repr_pointproofs([&i.to_be_bytes()[..], digest.as_ref()].concat())
+            suite
+                .aggregation_hash
+                .hash_to_field_repr(&[&i.to_be_bytes()[..], digest.as_ref()].concat())
         })
         .collect::<Vec<FrRepr>>())
 }
@@ -109,12 +117,12 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
 // * input: a list of indices, for which we need to generate t_i
 // * input: Value: the messages that is commited to
 // * output: a list of field elements
-pub(crate) fn hash_to_ti_fr<Blob: AsRef<[u8]>>(
-    commit: &Commitment,
+pub(crate) fn hash_to_ti_fr<P: GroupPlacement, Blob: AsRef<[u8]>>(
+    commit: &CommitmentIn<P>,
     set: &[usize],
     value_sub_vector: &[Blob],
     n: usize,
-) -> Result<Vec<Fr>, String> {
+) -> Result<Vec<Fr>, PointproofsError> {
     Ok(hash_to_ti_repr(commit, set, value_sub_vector, n)?
         .iter()
         // the hash_to_ti_repr should already produce valid Fr elements
@@ -129,18 +137,16 @@ pub(crate) fn hash_to_ti_fr<Blob: AsRef<[u8]>>(
 /// * input: a list of indices, for which we need to generate t_i
 /// * input: Value: the messages that is commited to
 /// * output: a list of field elements
-pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
-    commit: &Commitment,
+pub(crate) fn hash_to_ti_repr<P: GroupPlacement, Blob: AsRef<[u8]>>(
+    commit: &CommitmentIn<P>,
     set: &[usize],
     value_sub_vector: &[Blob],
     n: usize,
-) -> Result<Vec<FrRepr>, String> {
-    if !check_ciphersuite(commit.ciphersuite) {
-        return Err(ERR_CIPHERSUITE.to_owned());
-    }
+) -> Result<Vec<FrRepr>, PointproofsError> {
+    let suite = ciphersuite_def(commit.ciphersuite)?;
     // if the set leng does not mathc values, return an error
     if set.len() != value_sub_vector.len() {
-        return Err(ERR_INVALID_INDEX.to_owned());
+        return Err(PointproofsError::InvalidIndex);
     }
 
     // handle the case where there is only one input
@@ -152,7 +158,7 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
     // add values to set; returns an error if index is out of range
     for e in set {
         if *e >= n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         }
     }
 
@@ -161,7 +167,7 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
     // serialize commitment
     match commit.serialize(&mut tmp, true) {
         Ok(_p) => _p,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(PointproofsError::Serialization(e.to_string())),
     };
     // add the set to tmp
     for index in set {
@@ -174,15 +180,60 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
         tmp.append(&mut t.to_vec());
     }
 
-    let mut hasher = Sha512::new();
-    hasher.input(tmp);
-    let digest = hasher.result();
+    let digest = suite.aggregation_hash.digest(&tmp);
 
     // formulate the output
     Ok(set
         .iter()
         .map(|index| {
-            hash_to_field_repr_pointproofs([&index.to_be_bytes()[..], digest.as_ref()].concat())
+            suite
+                .aggregation_hash
+                .hash_to_field_repr(&[&index.to_be_bytes()[..], digest.as_ref()].concat())
+        })
+        .collect())
+}
+
+/// Hash the inputs of a batch verification into non-zero scalars r_k, one per proof.
+/// An internal function for `Proof::batch_verify_many`.
+/// * input: the ciphersuite of the commitments and proofs
+/// * input: a list of (commitment, proof, index, value)
+/// * output: a list of field elements
+pub(crate) fn hash_to_rk_fr<P: GroupPlacement>(
+    csid: Ciphersuite,
+    items: &[(&CommitmentIn<P>, &ProofIn<P>, usize, &[u8])],
+) -> Result<Vec<Fr>, PointproofsError> {
+    let suite = ciphersuite_def(csid)?;
+
+    // tmp = {C | proof | index | len(m) | m} for each item
+    let mut tmp: Vec<u8> = vec![];
+    for (commit, proof, index, value) in items {
+        match commit.serialize(&mut tmp, true) {
+            Ok(_p) => _p,
+            Err(e) => return Err(PointproofsError::Serialization(e.to_string())),
+        };
+        match proof.serialize(&mut tmp, true) {
+            Ok(_p) => _p,
+            Err(e) => return Err(PointproofsError::Serialization(e.to_string())),
+        };
+        tmp.extend_from_slice(&index.to_be_bytes());
+        // the values have different lengths, so their lengths are hashed as well
+        tmp.extend_from_slice(&value.len().to_be_bytes());
+        tmp.extend_from_slice(value);
+    }
+
+    let digest = suite.aggregation_hash.digest(&tmp);
+
+    // formulate the output
+    Ok((0..items.len())
+        .map(|k| {
+            // each field element r_k is generated as
+            // r_k = hash_to_field (k | digest)
+            let repr = suite
+                .aggregation_hash
+                .hash_to_field_repr(&[&k.to_be_bytes()[..], digest.as_ref()].concat());
+            // the hash should already produce a valid Fr element
+            // so it is safe to unwrap here
+            Fr::from_repr(repr).unwrap()
         })
         .collect())
 }
@@ -198,7 +249,7 @@ pub(crate) fn hash_to_field_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> Fr {
 /// Hashes a blob into a non-zero field element.
 /// hash_to_field_pointproofs use SHA 512 to hash a blob into a non-zero field element.
 pub(crate) fn hash_to_field_repr_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> FrRepr {
-    let mut hasher = Sha512::new();
+    let mut hasher = sha2::Sha512::new();
     hasher.input(input);
     let hash_output = hasher.result();
     let mut t = os2ip_mod_p(&hash_output);
@@ -211,6 +262,55 @@ pub(crate) fn hash_to_field_repr_pointproofs<Blob: AsRef<[u8]>>(input: Blob) ->
     t
 }
 
+/// expand_message_xmd with SHA-256, see
+/// https://www.rfc-editor.org/rfc/rfc9380.html#section-5.3.1
+/// It assumes that the dst is at most 255 bytes, and len_in_bytes at most 8160,
+/// which `register_ciphersuite` and the callers ensure.
+pub(crate) fn expand_message_xmd_sha256(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    // b_in_bytes = 32, s_in_bytes = 64
+    let ell = (len_in_bytes + 31) / 32;
+    let dst_prime = [dst, &[dst.len() as u8][..]].concat();
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let mut hasher = Sha256::new();
+    hasher.input(&[0u8; 64][..]);
+    hasher.input(msg);
+    hasher.input(&(len_in_bytes as u16).to_be_bytes()[..]);
+    hasher.input(&[0u8][..]);
+    hasher.input(&dst_prime);
+    let b_0 = hasher.result();
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+    // b_i = H(strxor(b_0, b_(i - 1)) || I2OSP(i, 1) || DST_prime)
+    let mut uniform_bytes: Vec<u8> = Vec::with_capacity(ell * 32);
+    let mut b_i = vec![0u8; 32];
+    for i in 1..=ell {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        let mut hasher = Sha256::new();
+        hasher.input(&xored);
+        hasher.input(&[i as u8][..]);
+        hasher.input(&dst_prime);
+        b_i = hasher.result().to_vec();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+/// Hashes a blob into a non-zero field element with the IETF hash_to_field,
+/// with expand_message_xmd and SHA-256, see
+/// https://www.rfc-editor.org/rfc/rfc9380.html#section-5.2
+/// L = ceil((ceil(log2(r)) + k) / 8) = 48 for r of 255 bits and k = 128.
+/// As in `hash_to_field_repr_pointproofs`, 0 is mapped to 1, so that the scalars are invertible.
+pub(crate) fn hash_to_field_repr_xmd_sha256(input: &[u8], dst: &[u8]) -> FrRepr {
+    let uniform_bytes = expand_message_xmd_sha256(input, dst, 48);
+    let mut t = os2ip_mod_p(&uniform_bytes);
+    if t == FrRepr([0, 0, 0, 0]) {
+        t = FrRepr([1, 0, 0, 0]);
+    }
+    t
+}
+
 /// this is Pointproofs's Octect String to Integer Primitive (os2ip) function
 /// https://tools.ietf.org/html/rfc8017#section-4
 /// the input is a 64 bytes array, and the output is between 0 and p-1
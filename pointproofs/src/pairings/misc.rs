@@ -1,8 +1,9 @@
 //! this file is part of the pointproofs.
 //! It defines some misc functions.
 
-use pairing::CurveAffine;
-use pairings::err::ERR_PARAM;
+use ff::{Field, PrimeField};
+use pairing::{CurveAffine, CurveProjective};
+use pairings::msm::*;
 use pairings::*;
 use std::collections::HashSet;
 use std::hash::Hash;
@@ -20,35 +21,68 @@ where
 /// This helper computes the sum of product:
 ///     \sum_{i=start}^{end-1}
 ///         param.generator[i]^scarlar_u64[i]
-/// It tries to use pre-computed data when possible.
+/// It tries to use pre-computed data when possible,
+/// and the multi-threaded multi-exponentiation for large inputs.
 /// It assumes end - start = n; and the lengths matches.
 /// It doesnot perform any sanity checks of those conditions.
-pub(crate) fn pp_sum_of_prod_helper(
-    prover_params: &ProverParams,
+pub(crate) fn pp_sum_of_prod_helper<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
     scalars_u64: &[&[u64; 4]],
     start: usize,
     end: usize,
-) -> PointproofsG1 {
+) -> P::G1 {
     // the second condition `n <= 1024` comes from benchmarking
     // pre-computation is faster only when the #basis is <1024
     if prover_params.precomp.len() == 512 * prover_params.n && prover_params.n <= 1024 {
-        PointproofsG1Affine::sum_of_products_precomp_256(
+        P::G1Affine::sum_of_products_precomp_256(
             &prover_params.generators[start..end],
             &scalars_u64,
             &prover_params.precomp[start * 256..end * 256],
         )
+    } else if end - start >= PARALLEL_MSM_THRESHOLD {
+        parallel_sum_of_products(&prover_params.generators[start..end], &scalars_u64)
     } else {
-        PointproofsG1Affine::sum_of_products(&prover_params.generators[start..end], &scalars_u64)
+        P::G1Affine::sum_of_products(&prover_params.generators[start..end], &scalars_u64)
+    }
+}
+
+/// This helper computes the sum of product:
+///     \sum_i param.generator[indices[i]]^scarlar_u64[i]
+/// for indices that need not be contiguous, e.g., a set of updated values.
+/// As `pp_sum_of_prod_helper`, it tries to use pre-computed data when possible,
+/// and the multi-threaded multi-exponentiation for large inputs.
+/// It assumes the indices are within [0, 2n) and the lengths match;
+/// it doesnot perform any sanity checks of those conditions.
+pub(crate) fn pp_sum_of_prod_indexed_helper<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
+    scalars_u64: &[&[u64; 4]],
+    indices: &[usize],
+) -> P::G1 {
+    let basis: Vec<P::G1Affine> = indices
+        .iter()
+        .map(|i| prover_params.generators[*i])
+        .collect();
+    if prover_params.precomp.len() == 512 * prover_params.n && indices.len() <= 1024 {
+        // the pre-computation of generator[i] is precomp[i * 256..(i + 1) * 256]
+        let mut precomp: Vec<P::G1Affine> = Vec::with_capacity(256 * indices.len());
+        for i in indices {
+            precomp.extend_from_slice(&prover_params.precomp[i * 256..(i + 1) * 256]);
+        }
+        P::G1Affine::sum_of_products_precomp_256(&basis, &scalars_u64, &precomp)
+    } else if indices.len() >= PARALLEL_MSM_THRESHOLD {
+        parallel_sum_of_products(&basis, &scalars_u64)
+    } else {
+        P::G1Affine::sum_of_products(&basis, &scalars_u64)
     }
 }
 
 /// Computes prover_params.generator[index] ^ scalars
 /// Tries to use pre-computated data when possible.
-pub(crate) fn pp_single_exp_helper(
-    prover_params: &ProverParams,
+pub(crate) fn pp_single_exp_helper<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
     scalar: Fr,
     index: usize,
-) -> PointproofsG1 {
+) -> P::G1 {
     if prover_params.precomp.len() == 3 * prover_params.generators.len() {
         prover_params.generators[index]
             .mul_precomp_3(scalar, &prover_params.precomp[index * 3..(index + 1) * 3])
@@ -58,7 +92,95 @@ pub(crate) fn pp_single_exp_helper(
             &prover_params.precomp[index * 256..(index + 1) * 256],
         )
     } else {
-        assert_eq!(prover_params.precomp.len(), 0, "{}", ERR_PARAM);
+        assert_eq!(
+            prover_params.precomp.len(),
+            0,
+            "{}",
+            PointproofsError::InvalidParam
+        );
         prover_params.generators[index].mul(scalar)
     }
 }
+
+/// Returns a primitive root of unity of order 2^log_size in Fr.
+/// The caller must ensure log_size <= Fr::S.
+pub(crate) fn root_of_unity(log_size: u32) -> Fr {
+    let mut omega = Fr::root_of_unity();
+    for _ in log_size..Fr::S {
+        omega.square();
+    }
+    omega
+}
+
+/// Permutes the slice into bit-reversed order; the length must be a power of 2.
+fn bit_reverse<T>(a: &mut [T]) {
+    let log_size = a.len().trailing_zeros();
+    for i in 0..a.len() {
+        let j = i.reverse_bits() >> (usize::BITS - log_size);
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+}
+
+/// In-place radix-2 FFT over Fr:
+///     a[i] <- \sum_j a[j] * omega^{ij}
+/// The length of a must be a power of 2 and equal to the order of omega.
+pub(crate) fn fft_fr(a: &mut [Fr], omega: &Fr) {
+    let size = a.len();
+    if size <= 1 {
+        return;
+    }
+    bit_reverse(a);
+    let mut half = 1;
+    while half < size {
+        let step = omega.pow([(size / (2 * half)) as u64]);
+        for chunk in a.chunks_mut(2 * half) {
+            let mut w = Fr::one();
+            for j in 0..half {
+                let mut t = chunk[j + half];
+                t.mul_assign(&w);
+                chunk[j + half] = chunk[j];
+                chunk[j + half].sub_assign(&t);
+                chunk[j].add_assign(&t);
+                w.mul_assign(&step);
+            }
+        }
+        half *= 2;
+    }
+}
+
+/// In-place radix-2 FFT over the group of the proofs:
+///     a[i] <- \sum_j a[j]^{omega^{ij}}
+/// The length of a must be a power of 2 and equal to the order of omega.
+pub(crate) fn fft_g1<G: CurveProjective<Scalar = Fr>>(a: &mut [G], omega: &Fr) {
+    let size = a.len();
+    if size <= 1 {
+        return;
+    }
+    bit_reverse(a);
+    let mut half = 1;
+    while half < size {
+        let step = omega.pow([(size / (2 * half)) as u64]);
+        // the twiddle factors are shared by all chunks of this round
+        let mut twiddles = Vec::with_capacity(half);
+        let mut w = Fr::one();
+        for _ in 0..half {
+            twiddles.push(w);
+            w.mul_assign(&step);
+        }
+        for chunk in a.chunks_mut(2 * half) {
+            for j in 0..half {
+                let mut t = chunk[j + half];
+                // multiplying by one is free, skip it
+                if j != 0 {
+                    t.mul_assign(twiddles[j]);
+                }
+                chunk[j + half] = chunk[j];
+                chunk[j + half].sub_assign(&t);
+                chunk[j].add_assign(&t);
+            }
+        }
+        half *= 2;
+    }
+}
@@ -5,43 +5,70 @@ use self::param::Ciphersuite;
 use self::pointproofs_groups::*;
 use pairing::bls12_381::*;
 
-/// Structure for porver parameters.
+/// Structure for porver parameters, with commitments and proofs placed as in `P`.
 #[derive(Clone, Debug)]
-pub struct ProverParams {
+pub struct ProverParamsIn<P: GroupPlacement> {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) n: usize,
-    generators: Vec<PointproofsG1Affine>,
+    generators: Vec<P::G1Affine>,
     pp_len: usize,
-    precomp: Vec<PointproofsG1Affine>,
+    precomp: Vec<P::G1Affine>,
 }
 
-/// Structure for verifier parameters.
+/// Structure for verifier parameters, with commitments and proofs placed as in `P`.
 #[derive(Clone, Debug)]
-pub struct VerifierParams {
+pub struct VerifierParamsIn<P: GroupPlacement> {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) n: usize,
-    generators: Vec<PointproofsG2Affine>,
+    generators: Vec<P::G2Affine>,
     pp_len: usize,
-    precomp: Vec<PointproofsG2Affine>,
+    precomp: Vec<P::G2Affine>,
     gt_elt: Fq12,
+    // the prepared generators, followed by the prepared generator of the group; or empty
+    prepared: Vec<P::G2Prepared>,
 }
 
-/// Structure to hold a commitment.
+/// Structure to hold a commitment, placed as in `P`.
 #[derive(Clone, Debug, PartialEq)]
-pub struct Commitment {
+pub struct CommitmentIn<P: GroupPlacement> {
     pub(crate) ciphersuite: Ciphersuite,
-    pub(crate) commit: PointproofsG1,
+    pub(crate) commit: P::G1,
 }
 
-/// Structure to hold a proof.
+/// Structure to hold a proof, placed as in `P`.
 #[derive(Clone, Debug, PartialEq)]
-pub struct Proof {
+pub struct ProofIn<P: GroupPlacement> {
     pub(crate) ciphersuite: Ciphersuite,
-    pub(crate) proof: PointproofsG1,
+    pub(crate) proof: P::G1,
 }
 
+/// Structure to hold the proof of a contribution to the ceremony:
+/// the update g1^delta, g2^delta, and a Schnorr proof of knowledge of delta.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpdateProofIn<P: GroupPlacement> {
+    pub(crate) ciphersuite: Ciphersuite,
+    pub(crate) delta_g1: P::G1Affine,
+    pub(crate) delta_g2: P::G2Affine,
+    pub(crate) nonce_commit: P::G1Affine,
+    pub(crate) response: Fr,
+}
+
+/// Prover parameters in the default placement.
+pub type ProverParams = ProverParamsIn<DefaultPlacement>;
+/// Verifier parameters in the default placement.
+pub type VerifierParams = VerifierParamsIn<DefaultPlacement>;
+/// A commitment in the default placement.
+pub type Commitment = CommitmentIn<DefaultPlacement>;
+/// A proof in the default placement.
+pub type Proof = ProofIn<DefaultPlacement>;
+/// The proof of a contribution to the ceremony, in the default placement.
+pub type UpdateProof = UpdateProofIn<DefaultPlacement>;
+
+pub mod ceremony;
+pub mod ciphersuite;
 pub(crate) mod commit;
 pub mod param;
+pub mod param_store;
 pub(crate) mod prove;
 
 pub(crate) mod hash_to_field_pointproofs;
@@ -50,5 +77,9 @@ pub mod pointproofs_groups;
 //mod c_api;
 pub(crate) mod c_api;
 mod err;
+pub use self::err::PointproofsError;
 mod misc;
+pub(crate) mod msm;
 mod serdes;
+#[cfg(feature = "serde")]
+mod serde_impl;
@@ -0,0 +1,88 @@
+//! this file is part of the pointproofs.
+//! It implements a multi-threaded multi-exponentiation, using Pippenger's bucket method.
+
+use pairing::{CurveAffine, CurveProjective};
+use rayon::prelude::*;
+
+/// Below this number of bases, `pp_sum_of_prod_helper` keeps using the single-threaded
+/// `sum_of_products`: for small inputs the thread overhead outweighs the gain.
+pub(crate) const PARALLEL_MSM_THRESHOLD: usize = 1024;
+
+/// Returns the window size (in bits) for a multi-exponentiation of n bases.
+/// This is roughly ln(n) + 2, which balances the number of bucket additions
+/// (n per window) with the cost of summing the 2^c buckets.
+pub(crate) fn window_size(n: usize) -> usize {
+    if n < 32 {
+        3
+    } else {
+        let log2_n = 8 * ::std::mem::size_of::<usize>() - n.leading_zeros() as usize;
+        log2_n * 69 / 100 + 2
+    }
+}
+
+/// Returns the c bits of the scalar starting at bit offset.
+fn window_bits(scalar: &[u64; 4], offset: usize, c: usize) -> usize {
+    let limb = offset / 64;
+    let shift = offset % 64;
+    let mut bits = scalar[limb] >> shift;
+    if shift + c > 64 && limb + 1 < 4 {
+        bits |= scalar[limb + 1] << (64 - shift);
+    }
+    (bits & ((1u64 << c) - 1)) as usize
+}
+
+/// Computes \sum_i bases[i]^scalars[i] with windows of c bits.
+/// The windows are independent and are computed in parallel with rayon;
+/// they are then combined, from the most significant one, with c doublings each.
+/// It assumes the lengths match, and 1 <= c < 64.
+pub(crate) fn parallel_sum_of_products_with_window<G>(
+    bases: &[G],
+    scalars_u64: &[&[u64; 4]],
+    c: usize,
+) -> G::Projective
+where
+    G: CurveAffine + Sync,
+    G::Projective: Send,
+{
+    let offsets: Vec<usize> = (0..256).step_by(c).collect();
+    let windows: Vec<G::Projective> = offsets
+        .into_par_iter()
+        .map(|offset| {
+            // bucket j holds the bases whose window is j + 1
+            let mut buckets = vec![G::Projective::zero(); (1 << c) - 1];
+            for (base, scalar) in bases.iter().zip(scalars_u64.iter()) {
+                let bits = window_bits(scalar, offset, c);
+                if bits != 0 {
+                    buckets[bits - 1].add_assign_mixed(base);
+                }
+            }
+            // \sum_j (j + 1) * bucket[j], as a running sum from the top bucket
+            let mut running = G::Projective::zero();
+            let mut sum = G::Projective::zero();
+            for bucket in buckets.iter().rev() {
+                running.add_assign(bucket);
+                sum.add_assign(&running);
+            }
+            sum
+        })
+        .collect();
+
+    let mut res = G::Projective::zero();
+    for window in windows.iter().rev() {
+        for _ in 0..c {
+            res.double();
+        }
+        res.add_assign(window);
+    }
+    res
+}
+
+/// Computes \sum_i bases[i]^scalars[i], picking the window size from the number of bases.
+/// It assumes the lengths match.
+pub(crate) fn parallel_sum_of_products<G>(bases: &[G], scalars_u64: &[&[u64; 4]]) -> G::Projective
+where
+    G: CurveAffine + Sync,
+    G::Projective: Send,
+{
+    parallel_sum_of_products_with_window(bases, scalars_u64, window_size(bases.len()))
+}
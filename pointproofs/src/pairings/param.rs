@@ -1,23 +1,23 @@
 //! this file is part of the pointproofs.
 //! It defines system parameters and functions to generate/validate them.
 
-use ff::Field;
+use ff::{Field, PrimeField};
 use pairing::{bls12_381::*, CurveAffine, CurveProjective,serdes::SerDes};
 //use pairings::err::*;
 //use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
 use pairings::*;
-
-const VALID_CIPHERSUITE: [u8; 1] = [0u8];
+use rayon::prelude::*;
+use sha2::{Digest, Sha512};
 
 /// Ciphersuite ID is a wrapper of u8.
 pub type Ciphersuite = u8;
 
-/// Checks if csid is supported. Currently only support csid = 0.
+/// Checks if csid is supported: 0, 1, or a registered ciphersuite, see `ciphersuite.rs`.
 pub fn check_ciphersuite(csid: Ciphersuite) -> bool {
-    VALID_CIPHERSUITE.contains(&csid)
+    ciphersuite::get_ciphersuite(csid).is_some()
 }
 
-/// Generate a set of parameters from a seed and a ciphersuite ID.
+/// Generate a set of parameters from a seed and a ciphersuite ID, in the default placement.
 /// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n == 0
 /// This function shall only be used for testing purpose.
 /// In deployment you should use `pointproofs-param` crate to ensure the
@@ -26,18 +26,29 @@ pub fn paramgen_from_seed<Blob: AsRef<[u8]>>(
     seed: Blob,
     ciphersuite: Ciphersuite,
     n: usize,
-) -> Result<(ProverParams, VerifierParams), String> {
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    paramgen_from_seed_in(seed, ciphersuite, n)
+}
+
+/// Generate a set of parameters from a seed and a ciphersuite ID, in the placement P,
+/// e.g., `paramgen_from_seed_in::<G2Placement, _>(seed, 0, n)`.
+/// As `paramgen_from_seed`, it shall only be used for testing purpose.
+pub fn paramgen_from_seed_in<P: GroupPlacement, Blob: AsRef<[u8]>>(
+    seed: Blob,
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
     // check the length of the seed
     if seed.as_ref().len() < 32 {
-        return Err(err::ERR_SEED_TOO_SHORT.to_owned());
+        return Err(PointproofsError::SeedTooShort);
     }
 
     // checks the validity of the inputs
     if !check_ciphersuite(ciphersuite) {
-        return Err(err::ERR_CIPHERSUITE.to_owned());
+        return Err(PointproofsError::InvalidCiphersuite);
     }
     if n > 65536 {
-        return Err(err::ERR_MAX_N.to_owned());
+        return Err(PointproofsError::MaxN);
     }
 
     // invoke the internal parameter generation function
@@ -50,12 +61,12 @@ pub fn paramgen_from_seed<Blob: AsRef<[u8]>>(
 
 /// Internal logic for parameter generation.
 /// Will always succeed.
-/// Will not be called outside this module.
-fn paramgen_from_alpha(
+/// Also used by the ceremony, starting from alpha = 1.
+pub(crate) fn paramgen_from_alpha<P: GroupPlacement>(
     alpha: &Fr,
     ciphersuite: Ciphersuite,
     n: usize,
-) -> (ProverParams, VerifierParams) {
+) -> (ProverParamsIn<P>, VerifierParamsIn<P>) {
     //#[cfg(not(debug_assertions))]
     //println!("hey");
     let mut g1_vec = Vec::with_capacity(2 * n);
@@ -67,46 +78,47 @@ fn paramgen_from_alpha(
     let mut alpha_power = Fr::one();
     for _ in 0..n {
         alpha_power.mul_assign(&alpha); // compute alpha^i
-        g1_vec.push(PointproofsG1Affine::one().mul(alpha_power).into_affine());
-        g2_vec.push(PointproofsG2Affine::one().mul(alpha_power).into_affine());
+        g1_vec.push(P::G1Affine::one().mul(alpha_power).into_affine());
+        g2_vec.push(P::G2Affine::one().mul(alpha_power).into_affine());
     }
 
     // skip g1^{alpha^{n+1}}
     alpha_power.mul_assign(&alpha);
-    g1_vec.push(PointproofsG1::zero().into_affine()); // this 0 is important -- without it, prove will not work correctly
+    g1_vec.push(P::G1::zero().into_affine()); // this 0 is important -- without it, prove will not work correctly
 
     // Now do the rest of the prover
     for _ in n..2 * n - 1 {
         alpha_power.mul_assign(&alpha); // compute alpha^i
-        g1_vec.push(PointproofsG1Affine::one().mul(alpha_power).into_affine());
+        g1_vec.push(P::G1Affine::one().mul(alpha_power).into_affine());
     }
 
     // verifier also gets gt^{alpha^{n+1}} in the target group
-    let gt = pointproofs_pairing(g1_vec[0], g2_vec[n - 1]);
+    let gt = P::pairing(g1_vec[0], g2_vec[n - 1]);
 
     (
-        ProverParams {
+        ProverParamsIn {
             ciphersuite,
             n,
             generators: g1_vec,
             pp_len: 0,
             precomp: Vec::with_capacity(0),
         },
-        VerifierParams {
+        VerifierParamsIn {
             ciphersuite,
             n,
             generators: g2_vec,
             pp_len: 0,
             precomp: Vec::with_capacity(0),
             gt_elt: gt,
+            prepared: vec![],
         },
     )
 }
 
-impl VerifierParams {
+impl<P: GroupPlacement> VerifierParamsIn<P> {
     /// pre-process the public parameters with precomputation value set to 3
     pub fn precomp_3(&mut self) {
-        self.precomp = vec![PointproofsG2Affine::zero(); 3 * self.n];
+        self.precomp = vec![P::G2Affine::zero(); 3 * self.n];
         for i in 0..self.n {
             self.generators[i].precomp_3(&mut self.precomp[i * 3..(i + 1) * 3]);
         }
@@ -115,19 +127,115 @@ impl VerifierParams {
 
     /// pre-process the public parameters with precomputation value set to 256
     pub fn precomp_256(&mut self) {
-        self.precomp = vec![PointproofsG2Affine::zero(); 256 * self.n];
+        self.precomp = vec![P::G2Affine::zero(); 256 * self.n];
         for i in 0..self.n {
             self.generators[i].precomp_256(&mut self.precomp[i * 256..(i + 1) * 256]);
         }
         self.pp_len = self.n * 256;
     }
+
+    /// pre-process the public parameters with the lines of the Miller loop
+    /// for the generators and for the generator of the group, which are
+    /// paired with in every verification.
+    /// The prepared lines are not serialized.
+    /// Only `G1Placement` is faster with them: with `G2Placement` the generators are in
+    /// bls12-381::G1, which has no lines to cache, and the pairings are computed as before.
+    pub fn prepare(&mut self) {
+        self.prepared = self
+            .generators
+            .par_iter()
+            .chain(::rayon::iter::once(&P::G2Affine::one()))
+            .map(P::prepare_g2)
+            .collect();
+    }
+
+    /// Computes the product of the pairings
+    ///     \prod_i e(g1_vec[i], g2_vec[i]) * \prod_j e(fixed[j].0, generators[fixed[j].1])
+    /// where generators[n] stands for the generator of the group.
+    /// It uses the prepared lines of the generators if any, see `prepare`;
+    /// None means the product could not be computed, see `GroupPlacement::pairing_multi_product_prepared`.
+    pub(crate) fn pairing_product_with_generators(
+        &self,
+        g1_vec: &[P::G1Affine],
+        g2_vec: &[P::G2Affine],
+        fixed: &[(P::G1Affine, usize)],
+    ) -> Option<Fq12> {
+        let mut g1_all: Vec<P::G1Affine> = g1_vec.to_vec();
+        g1_all.extend(fixed.iter().map(|(p, _)| *p));
+        if self.prepared.len() == self.n + 1 {
+            let g2_prepared: Vec<P::G2Prepared> = g2_vec.iter().map(P::prepare_g2).collect();
+            let mut g2_all: Vec<&P::G2Prepared> = g2_prepared.iter().collect();
+            g2_all.extend(fixed.iter().map(|(_, i)| &self.prepared[*i]));
+            P::pairing_multi_product_prepared(&g1_all, &g2_all)
+        } else {
+            let mut g2_all: Vec<P::G2Affine> = g2_vec.to_vec();
+            g2_all.extend(fixed.iter().map(|(_, i)| {
+                if *i == self.n {
+                    P::G2Affine::one()
+                } else {
+                    self.generators[*i]
+                }
+            }));
+            Some(P::pairing_multi_product(&g1_all, &g2_all))
+        }
+    }
+
+    /// Restricts the parameters to vectors of length m.
+    ///     * input: the new dimension m
+    ///     * output: the verifier parameters for m
+    ///     * error: m is not valid, see `ProverParams::restrict`
+    /// The pre-computation and the prepared lines, if any, are restricted as well.
+    pub fn restrict(&self, m: usize) -> Result<Self, PointproofsError> {
+        check_restriction(self.n, m)?;
+        if m == self.n {
+            return Ok(self.clone());
+        }
+
+        // g2^{alpha^1}, ..., g2^{alpha^m} are the first m generators,
+        // and gt^{alpha^{m+1}} = e(g1, g2^{alpha^{m+1}})
+        let pp_len = self.pp_len / self.n * m;
+        let prepared = if self.prepared.is_empty() {
+            vec![]
+        } else {
+            [&self.prepared[..m], &self.prepared[self.n..]].concat()
+        };
+        Ok(Self {
+            ciphersuite: self.ciphersuite,
+            n: m,
+            generators: self.generators[..m].to_vec(),
+            pp_len,
+            precomp: self.precomp[..pp_len].to_vec(),
+            gt_elt: P::pairing(P::G1Affine::one(), self.generators[m]),
+            prepared,
+        })
+    }
+
+    /// Returns the same parameters for another ciphersuite, see `ProverParams::with_ciphersuite`.
+    pub fn with_ciphersuite(&self, ciphersuite: Ciphersuite) -> Result<Self, PointproofsError> {
+        if !check_ciphersuite(ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        let mut res = self.clone();
+        res.ciphersuite = ciphersuite;
+        Ok(res)
+    }
+}
+
+/// Checks that the parameters for dimension n can be restricted to dimension m.
+/// The prover parameters for m include g1^{alpha^{m+2}}, ..., g1^{alpha^{2m}},
+/// which shall not contain the missing g1^{alpha^{n+1}}: either m = n or 2m <= n.
+fn check_restriction(n: usize, m: usize) -> Result<(), PointproofsError> {
+    if m == 0 || m > n || (m != n && 2 * m > n) {
+        return Err(PointproofsError::Restrict);
+    }
+    Ok(())
 }
 
-impl ProverParams {
+impl<P: GroupPlacement> ProverParamsIn<P> {
     /// pre-process the public parameters with precomputation value set to 3
     pub fn precomp_3(&mut self) {
         let twice_n = self.generators.len();
-        self.precomp = vec![PointproofsG1Affine::zero(); 3 * twice_n];
+        self.precomp = vec![P::G1Affine::zero(); 3 * twice_n];
         for i in 0..twice_n {
             self.generators[i].precomp_3(&mut self.precomp[i * 3..(i + 1) * 3]);
         }
@@ -137,16 +245,71 @@ impl ProverParams {
     /// pre-process the public parameters with precomputation value set to 256
     pub fn precomp_256(&mut self) {
         let twice_n = self.generators.len();
-        self.precomp = vec![PointproofsG1Affine::zero(); 256 * twice_n];
+        self.precomp = vec![P::G1Affine::zero(); 256 * twice_n];
         for i in 0..twice_n {
             self.generators[i].precomp_256(&mut self.precomp[i * 256..(i + 1) * 256]);
         }
         self.pp_len = self.n * 512;
     }
 
+    /// Restricts the parameters to vectors of length m,
+    /// e.g., to use a single ceremony output for several vector lengths.
+    ///     * input: the new dimension m
+    ///     * output: the prover parameters for m
+    ///     * error: m is 0, or larger than n, or n/2 < m < n
+    /// The parameters for m are g1^{alpha^1}, ..., g1^{alpha^m}, and g1^{alpha^{m+2}}, ..., g1^{alpha^{2m}};
+    /// the latter are only available in the parameters for n if 2m <= n,
+    /// as g1^{alpha^{n+1}} is not part of them.
+    /// The pre-computation, if any, is restricted as well.
+    pub fn restrict(&self, m: usize) -> Result<Self, PointproofsError> {
+        check_restriction(self.n, m)?;
+        if m == self.n {
+            return Ok(self.clone());
+        }
+
+        // g1^{alpha^1}, ..., g1^{alpha^m}, then 0 in place of g1^{alpha^{m+1}}, as in `paramgen_from_alpha`,
+        // then g1^{alpha^{m+2}}, ..., g1^{alpha^{2m}}
+        let mut generators = Vec::with_capacity(2 * m);
+        generators.extend_from_slice(&self.generators[..m]);
+        generators.push(self.generators[self.n]);
+        generators.extend_from_slice(&self.generators[m + 1..2 * m]);
+
+        // the same for the pre-computation of each generator;
+        // the pre-computation for 0 is the one at index n
+        let per_generator = self.pp_len / (2 * self.n);
+        let mut precomp = Vec::with_capacity(2 * m * per_generator);
+        precomp.extend_from_slice(&self.precomp[..m * per_generator]);
+        precomp
+            .extend_from_slice(&self.precomp[self.n * per_generator..(self.n + 1) * per_generator]);
+        precomp.extend_from_slice(&self.precomp[(m + 1) * per_generator..2 * m * per_generator]);
+
+        Ok(Self {
+            ciphersuite: self.ciphersuite,
+            n: m,
+            generators,
+            pp_len: 2 * m * per_generator,
+            precomp,
+        })
+    }
+
+    /// Returns the same parameters for another ciphersuite.
+    ///     * input: the ciphersuite id
+    ///     * output: the prover parameters, tagged with the ciphersuite
+    ///     * error: the ciphersuite is not supported
+    /// The group elements do not depend on the ciphersuite, which only defines the hashes;
+    /// e.g., `read_param` always outputs parameters for ciphersuite 0.
+    pub fn with_ciphersuite(&self, ciphersuite: Ciphersuite) -> Result<Self, PointproofsError> {
+        if !check_ciphersuite(ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        let mut res = self.clone();
+        res.ciphersuite = ciphersuite;
+        Ok(res)
+    }
+
     /// check if the parameters are correct -- should not be used other than testing
     #[cfg(test)]
-    pub(crate) fn check_parameters(&self, vp: &VerifierParams) -> bool {
+    pub(crate) fn check_parameters(&self, vp: &VerifierParamsIn<P>) -> bool {
         if self.n != vp.n || self.ciphersuite != vp.ciphersuite {
             return false;
         }
@@ -163,27 +326,21 @@ impl ProverParams {
         // of the two groups, and see if they all match as appropriate.
 
         for i in 0..self.n {
-            dh_values.push(pointproofs_pairing(
-                self.generators[i],
-                PointproofsG2Affine::one(),
-            ));
+            dh_values.push(P::pairing(self.generators[i], P::G2Affine::one()));
         }
         dh_values.push(vp.gt_elt);
         for i in self.n + 1..2 * self.n {
-            dh_values.push(pointproofs_pairing(
-                self.generators[i],
-                PointproofsG2Affine::one(),
-            ));
+            dh_values.push(P::pairing(self.generators[i], P::G2Affine::one()));
         }
         for i in 0..self.n {
-            dh_values.push(pointproofs_pairing(
+            dh_values.push(P::pairing(
                 self.generators[2 * self.n - 1],
                 vp.generators[i],
             ));
         }
 
         for (i, e) in dh_values.iter().enumerate().take(self.n) {
-            if e != &pointproofs_pairing(PointproofsG1Affine::one(), vp.generators[i]) {
+            if e != &P::pairing(P::G1Affine::one(), vp.generators[i]) {
                 return false;
             };
         }
@@ -191,9 +348,7 @@ impl ProverParams {
         for i in 0..2 * self.n {
             if i != self.n {
                 for j in 0..self.n {
-                    if dh_values[i + j + 1]
-                        != pointproofs_pairing(self.generators[i], vp.generators[j])
-                    {
+                    if dh_values[i + j + 1] != P::pairing(self.generators[i], vp.generators[j]) {
                         return false;
                     };
                 }
@@ -203,7 +358,190 @@ impl ProverParams {
     }
 }
 
-impl std::cmp::PartialEq for ProverParams {
+/// A SHA-512 digest of the generators and the gt element of a pair of parameters,
+/// for Fiat-Shamir transforms. The pre-computations are not included.
+pub(crate) fn params_digest<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
+    verifier_params: &VerifierParamsIn<P>,
+) -> Result<Vec<u8>, PointproofsError> {
+    let transcript = || -> std::io::Result<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![prover_params.ciphersuite];
+        for e in prover_params.generators.iter() {
+            e.serialize(&mut buf, true)?;
+        }
+        for e in verifier_params.generators.iter() {
+            e.serialize(&mut buf, true)?;
+        }
+        verifier_params.gt_elt.serialize(&mut buf, true)?;
+        Ok(buf)
+    };
+    let transcript = match transcript() {
+        Ok(p) => p,
+        Err(_) => return Err(PointproofsError::InvalidParam),
+    };
+    let mut hasher = Sha512::new();
+    hasher.input(transcript);
+    Ok(hasher.result().to_vec())
+}
+
+/// Validates a pair of parameters, e.g., parameters deserialized from an untrusted file.
+///     * input: prover parameter set
+///     * input: verifier parameter set
+///     * output: Ok if the parameters are powers of a same alpha
+///     * error: invalid ciphersuite/parameters
+/// The prover parameters shall be g1^{alpha^1}, ..., g1^{alpha^n}, 0, g1^{alpha^{n+2}}, ..., g1^{alpha^{2n}};
+/// the verifier parameters shall be g2^{alpha^1}, ..., g2^{alpha^n} and gt^{alpha^{n+1}}.
+/// Unlike `check_parameters`, which pairs every element with every other one, consecutive powers
+/// are checked together with random linear combinations: this takes 6 pairings and
+/// multi-exponentiations of size n and 2n.
+/// The coefficients are derived from a hash of the parameters (Fiat-Shamir),
+/// so that they cannot be known when the parameters are chosen.
+/// A pre-computation, if any, is checked against a new one.
+pub fn validate<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
+    verifier_params: &VerifierParamsIn<P>,
+) -> Result<(), PointproofsError> {
+    if !check_ciphersuite(prover_params.ciphersuite)
+        || prover_params.ciphersuite != verifier_params.ciphersuite
+    {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+    let n = prover_params.n;
+    if n == 0
+        || n != verifier_params.n
+        || prover_params.generators.len() != 2 * n
+        || verifier_params.generators.len() != n
+    {
+        return Err(PointproofsError::InvalidParam);
+    }
+    let g1 = &prover_params.generators;
+    let g2 = &verifier_params.generators;
+
+    // the placeholder at index n is 0, alpha is not 0
+    if !g1[n].is_zero() || g1[0].is_zero() {
+        return Err(PointproofsError::InvalidParam);
+    }
+
+    // step 1. derive the coefficients from the parameters
+    let digest = params_digest(prover_params, verifier_params)?;
+    let coefficient = |i: usize| {
+        hash_to_field_pointproofs::hash_to_field_pointproofs(
+            [&digest[..], &(i as u32).to_le_bytes()[..]].concat(),
+        )
+    };
+
+    // step 2. the powers in G1: with g1[i] = g1^{alpha^{i+1}} and g2[0] = g2^alpha,
+    //  e(g1[i], g2[0]) = e(g1[i+1], generator_of_g2) for i in 0..n-1 and n+1..2n-1,
+    //  e(g1[n-1], g2[1]) = e(g1[n+1], generator_of_g2) across the placeholder, and
+    //  e(g1[0], generator_of_g2) = e(generator_of_g1, g2[0]).
+    // With coefficients r_i, s and t, this is
+    //  e(\sum r_i g1[i] - t generator_of_g1, g2[0])
+    //  * e(t g1[0] - \sum r_i g1[i+1] - s g1[n+1], generator_of_g2)
+    //  * e(s g1[n-1], g2[1]) ?= 1
+    let pairs: Vec<usize> = (0..n - 1).chain(n + 1..2 * n - 1).collect();
+    let mut bases_0: Vec<P::G1Affine> = Vec::with_capacity(pairs.len() + 1);
+    let mut scalars_0: Vec<Fr> = Vec::with_capacity(pairs.len() + 1);
+    let mut bases_1: Vec<P::G1Affine> = Vec::with_capacity(pairs.len() + 2);
+    let mut scalars_1: Vec<Fr> = Vec::with_capacity(pairs.len() + 2);
+    for (k, i) in pairs.iter().enumerate() {
+        let r = coefficient(k);
+        let mut minus_r = r;
+        minus_r.negate();
+        bases_0.push(g1[*i]);
+        scalars_0.push(r);
+        bases_1.push(g1[*i + 1]);
+        scalars_1.push(minus_r);
+    }
+    let t = coefficient(pairs.len());
+    let mut minus_t = t;
+    minus_t.negate();
+    bases_0.push(P::G1Affine::one());
+    scalars_0.push(minus_t);
+    bases_1.push(g1[0]);
+    scalars_1.push(t);
+    let s = coefficient(pairs.len() + 1);
+    if n > 1 {
+        let mut minus_s = s;
+        minus_s.negate();
+        bases_1.push(g1[n + 1]);
+        scalars_1.push(minus_s);
+    }
+
+    let scalars_0_repr: Vec<FrRepr> = scalars_0.iter().map(|e| e.into_repr()).collect();
+    let scalars_0_u64: Vec<&[u64; 4]> = scalars_0_repr.iter().map(|e| &e.0).collect();
+    let scalars_1_repr: Vec<FrRepr> = scalars_1.iter().map(|e| e.into_repr()).collect();
+    let scalars_1_u64: Vec<&[u64; 4]> = scalars_1_repr.iter().map(|e| &e.0).collect();
+    let mut product = P::pairing_product(
+        P::G1Affine::sum_of_products(&bases_0, &scalars_0_u64).into_affine(),
+        g2[0],
+        P::G1Affine::sum_of_products(&bases_1, &scalars_1_u64).into_affine(),
+        P::G2Affine::one(),
+    );
+    if n > 1 {
+        product.mul_assign(&P::pairing(g1[n - 1].mul(s).into_affine(), g2[1]));
+    }
+    if product != Fq12::one() {
+        return Err(PointproofsError::InvalidParam);
+    }
+
+    // step 3. the powers in G2:
+    //  e(g1[0], g2[j]) = e(generator_of_g1, g2[j+1]) for j in 0..n-1, that is
+    //  e(g1[0], \sum u_j g2[j]) * e(-generator_of_g1, \sum u_j g2[j+1]) ?= 1
+    if n > 1 {
+        let scalars_repr: Vec<FrRepr> = (0..n - 1)
+            .map(|j| coefficient(pairs.len() + 2 + j).into_repr())
+            .collect();
+        let scalars_u64: Vec<&[u64; 4]> = scalars_repr.iter().map(|e| &e.0).collect();
+        let mut minus_generator = P::G1::one();
+        minus_generator.negate();
+        if P::pairing_product(
+            g1[0],
+            P::G2Affine::sum_of_products(&g2[..n - 1], &scalars_u64).into_affine(),
+            minus_generator.into_affine(),
+            P::G2Affine::sum_of_products(&g2[1..], &scalars_u64).into_affine(),
+        ) != Fq12::one()
+        {
+            return Err(PointproofsError::InvalidParam);
+        }
+    }
+
+    // step 4. gt^{alpha^{n+1}} = e(g1^alpha, g2^{alpha^n})
+    if P::pairing(g1[0], g2[n - 1]) != verifier_params.gt_elt {
+        return Err(PointproofsError::InvalidParam);
+    }
+
+    // step 5. the pre-computations
+    if prover_params.pp_len != 0 || !prover_params.precomp.is_empty() {
+        let mut expected = prover_params.clone();
+        if prover_params.pp_len == 6 * n {
+            expected.precomp_3();
+        } else if prover_params.pp_len == 512 * n {
+            expected.precomp_256();
+        } else {
+            return Err(PointproofsError::InvalidParam);
+        }
+        if expected.precomp != prover_params.precomp {
+            return Err(PointproofsError::InvalidParam);
+        }
+    }
+    if verifier_params.pp_len != 0 || !verifier_params.precomp.is_empty() {
+        let mut expected = verifier_params.clone();
+        if verifier_params.pp_len == 3 * n {
+            expected.precomp_3();
+        } else if verifier_params.pp_len == 256 * n {
+            expected.precomp_256();
+        } else {
+            return Err(PointproofsError::InvalidParam);
+        }
+        if expected.precomp != verifier_params.precomp {
+            return Err(PointproofsError::InvalidParam);
+        }
+    }
+
+    Ok(())
+}
+
+impl<P: GroupPlacement> std::cmp::PartialEq for ProverParamsIn<P> {
     /// Convenient function to compare secret key objects
     fn eq(&self, other: &Self) -> bool {
         self.ciphersuite == other.ciphersuite
@@ -214,7 +552,7 @@ impl std::cmp::PartialEq for ProverParams {
     }
 }
 
-impl std::cmp::PartialEq for VerifierParams {
+impl<P: GroupPlacement> std::cmp::PartialEq for VerifierParamsIn<P> {
     /// Convenient function to compare secret key objects
     fn eq(&self, other: &Self) -> bool {
         self.ciphersuite == other.ciphersuite
@@ -289,63 +627,48 @@ impl std::cmp::PartialEq for VerifierParams {
 // }
 pub fn read_param<R: std::io::Read>(
     reader: &mut R,
-) -> Result<(ProverParams, VerifierParams), String> {
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    read_param_in(reader)
+}
+
+/// Reads a parameter pair, as `read_param`, in the placement P.
+/// The output of `pointproofs-paramgen` holds the powers of alpha in both groups,
+/// so that the same file gives the parameters of either placement.
+pub fn read_param_in<P: GroupPlacement, R: std::io::Read>(
+    reader: &mut R,
+) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
     let param = match pointproofs_paramgen::PointproofsParams::deserialize(reader, true) {
-        Err(e) => return Err(format!("read_param: {}", e.to_string())),
+        Err(e) => return Err(PointproofsError::Serialization(e.to_string())),
         Ok(p) => p,
     };
 
     if !pointproofs_paramgen::consistent(&param) {
-        return Err("Input params are not consistent".to_owned());
-    };
-
-    // groups are NOT switched
-    #[cfg(not(feature = "group_switched"))]
-    let pp = ProverParams {
-        ciphersuite: 0,
-        n: param.n,
-        generators: [
-            param.g1_alpha_1_to_n,
-            vec![PointproofsG1::zero().into_affine()],
-            param.g1_alpha_nplus2_to_2n,
-        ]
-        .concat(),
-        pp_len: 0,
-        precomp: vec![],
-    };
-
-    #[cfg(not(feature = "group_switched"))]
-    let vp = VerifierParams {
-        ciphersuite: 0,
-        n: param.n,
-        generators: param.g2_alpha_1_to_n,
-        pp_len: 0,
-        precomp: vec![],
-        gt_elt: param.gt_alpha_nplus1,
+        return Err(PointproofsError::InvalidParam);
     };
 
-    // groups switched
-    #[cfg(feature = "group_switched")]
-    let pp = ProverParams {
+    let n = param.n;
+    let gt_elt = param.gt_alpha_nplus1;
+    let (g1_alpha_1_to_n, g1_alpha_nplus2_to_2n, g2_alpha_1_to_n) = P::select_generators(param);
+    let pp = ProverParamsIn {
         ciphersuite: 0,
-        n: param.n,
+        n,
         generators: [
-            param.g2_alpha_1_to_n,
-            vec![PointproofsG1::zero().into_affine()],
-            param.g2_alpha_nplus2_to_2n,
+            g1_alpha_1_to_n,
+            vec![P::G1::zero().into_affine()],
+            g1_alpha_nplus2_to_2n,
         ]
         .concat(),
         pp_len: 0,
         precomp: vec![],
     };
-    #[cfg(feature = "group_switched")]
-    let vp = VerifierParams {
+    let vp = VerifierParamsIn {
         ciphersuite: 0,
-        n: param.n,
-        generators: param.g1_alpha_1_to_n,
+        n,
+        generators: g2_alpha_1_to_n,
         pp_len: 0,
         precomp: vec![],
-        gt_elt: param.gt_alpha_nplus1,
+        gt_elt,
+        prepared: vec![],
     };
 
     Ok((pp, vp))
@@ -0,0 +1,415 @@
+//! this file is part of the pointproofs.
+//! It defines a store that caches the parameters together with their pre-computation,
+//! so that the pre-computation is done once rather than at every start.
+
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, EncodedPoint};
+use pairings::err::*;
+use pairings::param::*;
+use pairings::pointproofs_groups::*;
+use pairings::*;
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+const STORE_MAGIC: &[u8; 8] = b"PPSTORE1";
+// the checksum is the last field of the header
+const CHECKSUM_OFFSET: usize = 8 + 1 + 4 + 1 + 32;
+
+/// The pre-computation kept in a `ParamStore`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrecompKind {
+    /// no pre-computation
+    NoPrecomp,
+    /// pre-computation from `ProverParams::precomp_3`
+    Precomp3,
+    /// pre-computation from `ProverParams::precomp_256`
+    Precomp256,
+}
+
+impl PrecompKind {
+    fn to_u8(self) -> u8 {
+        match self {
+            PrecompKind::NoPrecomp => 0,
+            PrecompKind::Precomp3 => 1,
+            PrecompKind::Precomp256 => 2,
+        }
+    }
+
+    fn from_u8(kind: u8) -> Option<Self> {
+        match kind {
+            0 => Some(PrecompKind::NoPrecomp),
+            1 => Some(PrecompKind::Precomp3),
+            2 => Some(PrecompKind::Precomp256),
+            _ => None,
+        }
+    }
+
+    /// the length of the pre-computation for parameters of dimension n
+    fn pp_len(self, n: usize) -> usize {
+        match self {
+            PrecompKind::NoPrecomp => 0,
+            PrecompKind::Precomp3 => n * 6,
+            PrecompKind::Precomp256 => n * 512,
+        }
+    }
+
+    fn apply<P: GroupPlacement>(self, prover_params: &mut ProverParamsIn<P>) {
+        match self {
+            PrecompKind::NoPrecomp => {
+                prover_params.precomp = vec![];
+                prover_params.pp_len = 0;
+            }
+            PrecompKind::Precomp3 => prover_params.precomp_3(),
+            PrecompKind::Precomp256 => prover_params.precomp_256(),
+        }
+    }
+}
+
+/// A file that caches the prover parameters with their pre-computation,
+/// together with the verifier parameters, in the placement P.
+/// The file is organized as
+///
+/// `| magic | ciphersuite id | P::TAG | n | precomp kind | source digest | checksum | verifier params | generators | pp_len | [pre_compute] |`
+///
+/// * the ciphersuite id and the tag of the placement share a byte, as in the serialized parameters;
+/// * the verifier parameters use `SerDes`;
+/// * the prover points are uncompressed, so that loading them does not require square roots;
+/// * the source digest is the SHA-256 of the `crs.param` file the store was built from;
+/// * the checksum is the SHA-256 of everything after the header.
+///
+/// The checksum detects truncated or corrupted files, it is not an authentication:
+/// the points of the store are not checked to be in the prime order subgroup,
+/// so the store shall be kept where the `crs.param` file itself is trusted.
+#[derive(Clone, Debug)]
+pub struct ParamStoreIn<P: GroupPlacement> {
+    path: PathBuf,
+    placement: PhantomData<P>,
+}
+
+/// A store of the parameters in the default placement.
+pub type ParamStore = ParamStoreIn<DefaultPlacement>;
+
+struct StoreHeader {
+    ciphersuite: Ciphersuite,
+    n: usize,
+    kind: PrecompKind,
+    source: [u8; 32],
+    checksum: [u8; 32],
+}
+
+/// Hashes everything that goes through it.
+struct Checksummed<T> {
+    inner: T,
+    hasher: Sha256,
+}
+
+impl<T> Checksummed<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+}
+
+impl<R: Read> Read for Checksummed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.hasher.input(&buf[..len]);
+        Ok(len)
+    }
+}
+
+impl<W: Write> Write for Checksummed<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.input(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn io_error(e: std::io::Error) -> PointproofsError {
+    PointproofsError::Io(e)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<usize, PointproofsError> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(io_error)?;
+    Ok(u32::from_le_bytes(buf) as usize)
+}
+
+fn write_point<W: Write, G: CurveAffine>(
+    writer: &mut W,
+    point: &G,
+) -> Result<(), PointproofsError> {
+    writer
+        .write_all(point.into_uncompressed().as_ref())
+        .map_err(io_error)
+}
+
+fn read_point<R: Read, G: CurveAffine>(reader: &mut R) -> Result<G, PointproofsError> {
+    let mut encoded = G::Uncompressed::empty();
+    reader.read_exact(encoded.as_mut()).map_err(io_error)?;
+    // the point is checked to be on the curve, the subgroup check is skipped
+    encoded
+        .into_affine_unchecked()
+        .map_err(|e| PointproofsError::Serialization(e.to_string()))
+}
+
+fn read_header<P: GroupPlacement, R: Read>(
+    reader: &mut R,
+) -> Result<StoreHeader, PointproofsError> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic).map_err(io_error)?;
+    if &magic != STORE_MAGIC {
+        return Err(PointproofsError::StoreFormat);
+    }
+
+    let mut csid = [0u8; 1];
+    reader.read_exact(&mut csid).map_err(io_error)?;
+    if csid[0] & PLACEMENT_TAG_MASK != P::TAG {
+        return Err(PointproofsError::Placement);
+    }
+    let ciphersuite = csid[0] & !PLACEMENT_TAG_MASK;
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+
+    let n = read_u32(reader)?;
+    if n > 65536 || n == 0 {
+        return Err(PointproofsError::MaxN);
+    }
+
+    let mut kind = [0u8; 1];
+    reader.read_exact(&mut kind).map_err(io_error)?;
+    let kind = match PrecompKind::from_u8(kind[0]) {
+        Some(kind) => kind,
+        None => return Err(PointproofsError::StoreFormat),
+    };
+
+    let mut source = [0u8; 32];
+    reader.read_exact(&mut source).map_err(io_error)?;
+    let mut checksum = [0u8; 32];
+    reader.read_exact(&mut checksum).map_err(io_error)?;
+
+    Ok(StoreHeader {
+        ciphersuite,
+        n,
+        kind,
+        source,
+        checksum,
+    })
+}
+
+/// Reads the part of the store after the header, and validates it against the header.
+fn read_body<P: GroupPlacement, R: Read>(
+    reader: &mut R,
+    header: &StoreHeader,
+) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
+    let mut reader = Checksummed::new(reader);
+
+    let verifier_params =
+        VerifierParamsIn::<P>::deserialize(&mut reader, true).map_err(io_error)?;
+    if verifier_params.n != header.n || verifier_params.ciphersuite != header.ciphersuite {
+        return Err(PointproofsError::StoreFormat);
+    }
+
+    let mut generators = Vec::with_capacity(2 * header.n);
+    for _i in 0..2 * header.n {
+        generators.push(read_point(&mut reader)?);
+    }
+
+    let pp_len = read_u32(&mut reader)?;
+    if pp_len != header.kind.pp_len(header.n) {
+        return Err(PointproofsError::StoreFormat);
+    }
+    let mut precomp = Vec::with_capacity(pp_len);
+    for _i in 0..pp_len {
+        precomp.push(read_point(&mut reader)?);
+    }
+
+    // trailing bytes are an error, like a length mismatch
+    let mut extra = [0u8; 1];
+    if reader.inner.read(&mut extra).map_err(io_error)? != 0 {
+        return Err(PointproofsError::StoreFormat);
+    }
+    if reader.hasher.result().as_slice() != &header.checksum[..] {
+        return Err(PointproofsError::StoreChecksum);
+    }
+
+    // the prover and verifier parameters shall be for the same alpha,
+    // and g1^{alpha^{n+1}} shall be replaced with 0, as in `paramgen_from_alpha`
+    if !generators[header.n].is_zero()
+        || P::pairing(generators[0], verifier_params.generators[header.n - 1])
+            != verifier_params.gt_elt
+    {
+        return Err(PointproofsError::InvalidParam);
+    }
+
+    let prover_params = ProverParamsIn {
+        ciphersuite: header.ciphersuite,
+        n: header.n,
+        generators,
+        pp_len,
+        precomp,
+    };
+    Ok((prover_params, verifier_params))
+}
+
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(hasher.result().as_slice());
+    digest
+}
+
+impl<P: GroupPlacement> ParamStoreIn<P> {
+    /// Creates a store at the given path; nothing is read or written until it is used.
+    /// A store of one placement cannot be loaded as the other.
+    pub fn new<Q: AsRef<Path>>(path: Q) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            placement: PhantomData,
+        }
+    }
+
+    /// The path of the store.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the parameters from the store. If the store does not exist, or it was
+    /// built from another `crs.param` or with another pre-computation, or it is invalid,
+    /// builds it again from the `crs.param` file.
+    ///     * input: the path to the `crs.param` file
+    ///     * input: the pre-computation to use
+    ///     * output: the prover and verifier parameters
+    ///     * error: the `crs.param` file cannot be read, or the store cannot be written
+    pub fn load_or_build<Q: AsRef<Path>>(
+        &self,
+        crs_path: Q,
+        kind: PrecompKind,
+    ) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
+        let crs = std::fs::read(crs_path).map_err(io_error)?;
+        let source = sha256(&crs);
+
+        if let Ok(file) = File::open(&self.path) {
+            let mut reader = BufReader::new(file);
+            if let Ok(header) = read_header::<P, _>(&mut reader) {
+                if header.kind == kind && header.source == source {
+                    if let Ok(params) = read_body(&mut reader, &header) {
+                        return Ok(params);
+                    }
+                }
+            }
+        }
+
+        let (mut prover_params, verifier_params) = read_param_in::<P, _>(&mut crs.as_slice())?;
+        kind.apply(&mut prover_params);
+        self.write(&prover_params, &verifier_params, kind, &source)?;
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Builds the store from a `crs.param` file, and returns the parameters.
+    ///     * input: the path to the `crs.param` file
+    ///     * input: the pre-computation to use
+    ///     * output: the prover and verifier parameters
+    ///     * error: the `crs.param` file cannot be read, or the store cannot be written
+    pub fn build<Q: AsRef<Path>>(
+        &self,
+        crs_path: Q,
+        kind: PrecompKind,
+    ) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
+        let crs = std::fs::read(crs_path).map_err(io_error)?;
+        let (mut prover_params, verifier_params) = read_param_in::<P, _>(&mut crs.as_slice())?;
+        kind.apply(&mut prover_params);
+        self.write(&prover_params, &verifier_params, kind, &sha256(&crs))?;
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Loads the parameters from the store.
+    /// The whole store is read and checked, there is no lazy or memory-mapped loading:
+    /// the parameters own their points, so every point is deserialized anyway.
+    ///     * output: the prover and verifier parameters
+    ///     * error: the store is missing, invalid, or its checksum does not match
+    pub fn load(&self) -> Result<(ProverParamsIn<P>, VerifierParamsIn<P>), PointproofsError> {
+        let file = File::open(&self.path).map_err(io_error)?;
+        let mut reader = BufReader::new(file);
+        let header = read_header::<P, _>(&mut reader)?;
+        read_body(&mut reader, &header)
+    }
+
+    /// Writes the store to a temporary file first, then moves it in place,
+    /// so that a reader never sees a partial store.
+    fn write(
+        &self,
+        prover_params: &ProverParamsIn<P>,
+        verifier_params: &VerifierParamsIn<P>,
+        kind: PrecompKind,
+        source: &[u8; 32],
+    ) -> Result<(), PointproofsError> {
+        if prover_params.pp_len != kind.pp_len(prover_params.n)
+            || prover_params.n > (u32::max_value() as usize)
+        {
+            return Err(PointproofsError::InvalidParam);
+        }
+
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)
+            .map_err(io_error)?;
+
+        {
+            let mut writer = BufWriter::new(&mut file);
+            writer.write_all(STORE_MAGIC).map_err(io_error)?;
+            writer
+                .write_all(&[prover_params.ciphersuite | P::TAG])
+                .map_err(io_error)?;
+            writer
+                .write_all(&(prover_params.n as u32).to_le_bytes())
+                .map_err(io_error)?;
+            writer.write_all(&[kind.to_u8()]).map_err(io_error)?;
+            writer.write_all(source).map_err(io_error)?;
+            // the checksum is written once the body is
+            writer.write_all(&[0u8; 32]).map_err(io_error)?;
+
+            let mut writer = Checksummed::new(writer);
+            verifier_params
+                .serialize(&mut writer, true)
+                .map_err(io_error)?;
+            for e in prover_params.generators.iter() {
+                write_point(&mut writer, e)?;
+            }
+            writer
+                .write_all(&(prover_params.pp_len as u32).to_le_bytes())
+                .map_err(io_error)?;
+            for e in prover_params.precomp.iter() {
+                write_point(&mut writer, e)?;
+            }
+            writer.flush().map_err(io_error)?;
+
+            let checksum = writer.hasher.result();
+            let mut writer = writer.inner;
+            writer
+                .seek(SeekFrom::Start(CHECKSUM_OFFSET as u64))
+                .map_err(io_error)?;
+            writer.write_all(checksum.as_slice()).map_err(io_error)?;
+            writer.flush().map_err(io_error)?;
+        }
+        file.sync_all().map_err(io_error)?;
+
+        std::fs::rename(&tmp_path, &self.path).map_err(io_error)
+    }
+}
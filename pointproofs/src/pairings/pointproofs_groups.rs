@@ -1,108 +1,206 @@
 use pairing::bls12_381::*;
-use pairing::Engine;
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pointproofs_paramgen::PointproofsParams;
+use std::fmt::Debug;
 
-// =========================
-// the groups are NOT switched
-/// A wrapper of BLS::G1. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG1 = G1;
-/// A wrapper of BLS::G2. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG2 = G2;
-/// A wrapper of BLS::G1Affine. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG1Affine = G1Affine;
-/// A wrapper of BLS::G2Affine. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG2Affine = G2Affine;
+/// The placement of the commitments and proofs in the groups of BLS12-381.
+/// The commitments, proofs and prover parameters are in `Self::G1`,
+/// the verifier parameters are in `Self::G2`.
+/// Both placements can be used in a same process: the serialized objects are tagged with
+/// `Self::TAG` in the high bit of their ciphersuite id, so that a blob of one placement
+/// cannot be read as the other.
+pub trait GroupPlacement: Clone + Copy + Debug + PartialEq + Send + Sync + 'static {
+    /// The group of the commitments, proofs and prover parameters
+    type G1: CurveProjective<Scalar = Fr, Affine = Self::G1Affine> + SerDes;
+    /// The affine form of `Self::G1`
+    type G1Affine: CurveAffine<Scalar = Fr, Projective = Self::G1> + SerDes;
+    /// The group of the verifier parameters
+    type G2: CurveProjective<Scalar = Fr, Affine = Self::G2Affine> + SerDes;
+    /// The affine form of `Self::G2`
+    type G2Affine: CurveAffine<Scalar = Fr, Projective = Self::G2> + SerDes;
+    /// The prepared form of `Self::G2Affine` for the Miller loop,
+    /// e.g., the pre-computed line coefficients of a BLS::G2 point
+    type G2Prepared: Clone + Debug + Send + Sync;
 
-#[cfg(not(feature = "group_switched"))]
-pub const POINTPROOFSG1_LEN: usize = 48;
-#[cfg(not(feature = "group_switched"))]
-pub const POINTPROOFSG2_LEN: usize = 96;
+    /// The tag of the serialized objects, in the high bit of the ciphersuite id
+    const TAG: u8;
+    /// Size for a serialized element of `Self::G1`
+    const G1_LEN: usize;
+    /// Size for a serialized element of `Self::G2`
+    const G2_LEN: usize;
+    /// Size for serialized commitment
+    const COMMIT_LEN: usize;
+    /// Size for serialized proof
+    const PROOF_LEN: usize;
 
-/// A wrapper of BLS::pairing_product. Groups are switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub(crate) fn pointproofs_pairing(p1: PointproofsG1Affine, q1: PointproofsG2Affine) -> Fq12 {
-    Bls12::pairing(p1, q1)
-}
-/// A wrapper of BLS::pairing_product. Groups are switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub(crate) fn pointproofs_pairing_product(
-    p1: PointproofsG1Affine,
-    q1: PointproofsG2Affine,
-    p2: PointproofsG1Affine,
-    q2: PointproofsG2Affine,
-) -> Fq12 {
-    Bls12::pairing_product(p1, q1, p2, q2)
-}
-/// A wrapper of BLS::pairing_multi_product. Groups are switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub(crate) fn pointproofs_pairing_multi_product(
-    g1_vec: &[PointproofsG1Affine],
-    g2_vec: &[PointproofsG2Affine],
-) -> Fq12 {
-    Bls12::pairing_multi_product(g1_vec, g2_vec)
+    /// A wrapper of BLS::pairing
+    fn pairing(p1: Self::G1Affine, q1: Self::G2Affine) -> Fq12;
+    /// A wrapper of BLS::pairing_product
+    fn pairing_product(
+        p1: Self::G1Affine,
+        q1: Self::G2Affine,
+        p2: Self::G1Affine,
+        q2: Self::G2Affine,
+    ) -> Fq12;
+    /// A wrapper of BLS::pairing_multi_product
+    fn pairing_multi_product(g1_vec: &[Self::G1Affine], g2_vec: &[Self::G2Affine]) -> Fq12;
+    /// Prepares an element of `Self::G2` for `pairing_multi_product_prepared`
+    fn prepare_g2(q: &Self::G2Affine) -> Self::G2Prepared;
+    /// The same as `pairing_multi_product`, with prepared elements of `Self::G2`.
+    /// Returns None if the final exponentiation fails, i.e., the Miller loop is zero,
+    /// which the verifications treat as an invalid proof.
+    fn pairing_multi_product_prepared(
+        g1_vec: &[Self::G1Affine],
+        g2_vec: &[&Self::G2Prepared],
+    ) -> Option<Fq12>;
+
+    /// Selects the powers of alpha of each group from the output of `pointproofs-paramgen`:
+    /// the powers 1..n and n+2..2n in `Self::G1`, and the powers 1..n in `Self::G2`.
+    fn select_generators(
+        param: PointproofsParams,
+    ) -> (
+        Vec<Self::G1Affine>,
+        Vec<Self::G1Affine>,
+        Vec<Self::G2Affine>,
+    );
 }
 
-/// Size for serialized commitment.
-#[cfg(not(feature = "group_switched"))]
-pub const COMMIT_LEN: usize = 49;
+/// The bit of the serialized ciphersuite id that holds `GroupPlacement::TAG`;
+/// the ciphersuite ids themselves are below 128.
+pub const PLACEMENT_TAG_MASK: u8 = 0x80;
 
-/// Size for serialized proof.
-#[cfg(not(feature = "group_switched"))]
-pub const PROOF_LEN: usize = 49;
+/// Groups are not switched: proof/commits are in BLS::G1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct G1Placement;
 
-// =========================
-// the groups are switched
+impl GroupPlacement for G1Placement {
+    type G1 = G1;
+    type G1Affine = G1Affine;
+    type G2 = G2;
+    type G2Affine = G2Affine;
+    type G2Prepared = G2Prepared;
 
-/// A wrapper of BLS::G2. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG1 = G2;
-/// A wrapper of BLS::G1. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG2 = G1;
-/// A wrapper of BLS::G2Affine. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG1Affine = G2Affine;
-/// A wrapper of BLS::G1Affine. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG2Affine = G1Affine;
+    const TAG: u8 = 0;
+    const G1_LEN: usize = 48;
+    const G2_LEN: usize = 96;
+    const COMMIT_LEN: usize = 49;
+    const PROOF_LEN: usize = 49;
 
-#[cfg(feature = "group_switched")]
-pub const POINTPROOFSG1_LEN: usize = 96;
-#[cfg(feature = "group_switched")]
-pub const POINTPROOFSG2_LEN: usize = 48;
+    fn pairing(p1: G1Affine, q1: G2Affine) -> Fq12 {
+        Bls12::pairing(p1, q1)
+    }
 
-/// A wrapper of BLS::pairing. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub(crate) fn pointproofs_pairing(p1: PointproofsG1Affine, q1: PointproofsG2Affine) -> Fq12 {
-    Bls12::pairing(q1, p1)
+    fn pairing_product(p1: G1Affine, q1: G2Affine, p2: G1Affine, q2: G2Affine) -> Fq12 {
+        Bls12::pairing_product(p1, q1, p2, q2)
+    }
+
+    fn pairing_multi_product(g1_vec: &[G1Affine], g2_vec: &[G2Affine]) -> Fq12 {
+        Bls12::pairing_multi_product(g1_vec, g2_vec)
+    }
+
+    fn prepare_g2(q: &G2Affine) -> G2Prepared {
+        q.prepare()
+    }
+
+    fn pairing_multi_product_prepared(g1_vec: &[G1Affine], g2_vec: &[&G2Prepared]) -> Option<Fq12> {
+        let g1_prepared: Vec<G1Prepared> = g1_vec.iter().map(|p| p.prepare()).collect();
+        let pairs: Vec<(&G1Prepared, &G2Prepared)> =
+            g1_prepared.iter().zip(g2_vec.iter().cloned()).collect();
+        Bls12::final_exponentiation(&Bls12::miller_loop(&pairs))
+    }
+
+    fn select_generators(
+        param: PointproofsParams,
+    ) -> (Vec<G1Affine>, Vec<G1Affine>, Vec<G2Affine>) {
+        (
+            param.g1_alpha_1_to_n,
+            param.g1_alpha_nplus2_to_2n,
+            param.g2_alpha_1_to_n,
+        )
+    }
 }
-/// A wrapper of BLS::pairing_product. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub(crate) fn pointproofs_pairing_product(
-    p1: PointproofsG1Affine,
-    q1: PointproofsG2Affine,
-    p2: PointproofsG1Affine,
-    q2: PointproofsG2Affine,
-) -> Fq12 {
-    Bls12::pairing_product(q1, p1, q2, p2)
+
+/// Groups are switched: proof/commits are in BLS::G2.
+/// In this setting almost everything is slower, except that
+/// the (batch) verification will be faster.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct G2Placement;
+
+impl GroupPlacement for G2Placement {
+    type G1 = G2;
+    type G1Affine = G2Affine;
+    type G2 = G1;
+    type G2Affine = G1Affine;
+    // the lines of the Miller loop come from the BLS::G2 points, which are the proofs and
+    // commitments here; there is nothing to pre-compute for the verifier parameters
+    type G2Prepared = G1Affine;
+
+    const TAG: u8 = PLACEMENT_TAG_MASK;
+    const G1_LEN: usize = 96;
+    const G2_LEN: usize = 48;
+    const COMMIT_LEN: usize = 97;
+    const PROOF_LEN: usize = 97;
+
+    fn pairing(p1: G2Affine, q1: G1Affine) -> Fq12 {
+        Bls12::pairing(q1, p1)
+    }
+
+    fn pairing_product(p1: G2Affine, q1: G1Affine, p2: G2Affine, q2: G1Affine) -> Fq12 {
+        Bls12::pairing_product(q1, p1, q2, p2)
+    }
+
+    fn pairing_multi_product(g1_vec: &[G2Affine], g2_vec: &[G1Affine]) -> Fq12 {
+        Bls12::pairing_multi_product(g2_vec, g1_vec)
+    }
+
+    fn prepare_g2(q: &G1Affine) -> G1Affine {
+        *q
+    }
+
+    fn pairing_multi_product_prepared(g1_vec: &[G2Affine], g2_vec: &[&G1Affine]) -> Option<Fq12> {
+        let g2_vec: Vec<G1Affine> = g2_vec.iter().map(|q| **q).collect();
+        Some(Bls12::pairing_multi_product(&g2_vec, g1_vec))
+    }
+
+    fn select_generators(
+        param: PointproofsParams,
+    ) -> (Vec<G2Affine>, Vec<G2Affine>, Vec<G1Affine>) {
+        (
+            param.g2_alpha_1_to_n,
+            param.g2_alpha_nplus2_to_2n,
+            param.g1_alpha_1_to_n,
+        )
+    }
 }
-/// A wrapper of BLS::pairing_multi_product. Groups are switched and proof/commits are in BLS::G2
+
+// =========================
+// the default placement, used by the C wrapper and by the type aliases
+// such as `Commitment`; it is switched with the `group_switched` feature
+
+/// The default placement. Groups are not switched and proof/commits are in BLS::G1
+#[cfg(not(feature = "group_switched"))]
+pub type DefaultPlacement = G1Placement;
+/// The default placement. Groups are switched and proof/commits are in BLS::G2
 #[cfg(feature = "group_switched")]
-pub(crate) fn pointproofs_pairing_multi_product(
-    g1_vec: &[PointproofsG1Affine],
-    g2_vec: &[PointproofsG2Affine],
-) -> Fq12 {
-    Bls12::pairing_multi_product(g2_vec, g1_vec)
-}
+pub type DefaultPlacement = G2Placement;
+
+/// The group of the proof/commits in the default placement.
+pub type PointproofsG1 = <DefaultPlacement as GroupPlacement>::G1;
+/// The group of the verifier parameters in the default placement.
+pub type PointproofsG2 = <DefaultPlacement as GroupPlacement>::G2;
+/// The affine form of `PointproofsG1`.
+pub type PointproofsG1Affine = <DefaultPlacement as GroupPlacement>::G1Affine;
+/// The affine form of `PointproofsG2`.
+pub type PointproofsG2Affine = <DefaultPlacement as GroupPlacement>::G2Affine;
 
+/// Size for a serialized element of `PointproofsG1`.
+pub const POINTPROOFSG1_LEN: usize = <DefaultPlacement as GroupPlacement>::G1_LEN;
+/// Size for a serialized element of `PointproofsG2`.
+pub const POINTPROOFSG2_LEN: usize = <DefaultPlacement as GroupPlacement>::G2_LEN;
 
 /// Size for serialized commitment.
-#[cfg(feature = "group_switched")]
-pub const COMMIT_LEN: usize = 97;
+pub const COMMIT_LEN: usize = <DefaultPlacement as GroupPlacement>::COMMIT_LEN;
 
 /// Size for serialized proof.
-#[cfg(feature = "group_switched")]
-pub const PROOF_LEN: usize = 97;
+pub const PROOF_LEN: usize = <DefaultPlacement as GroupPlacement>::PROOF_LEN;
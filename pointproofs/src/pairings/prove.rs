@@ -2,13 +2,17 @@
 //! It defines functions for proofs.
 use ff::{Field, PrimeField};
 use pairing::{bls12_381::*, CurveAffine, CurveProjective};
+use pairings::ciphersuite::*;
 use pairings::err::*;
 use pairings::hash_to_field_pointproofs::*;
 use pairings::misc::*;
+use pairings::msm::*;
 use pairings::param::*;
 use pairings::*;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
 
-impl Proof {
+impl<P: GroupPlacement> ProofIn<P> {
     /// Generate a new proof.
     ///     * input: prover parameter set
     ///     * input: values for the proof
@@ -22,28 +26,26 @@ impl Proof {
     /// In this scenario, the caller should define the right
     /// format for padding.
     pub fn new<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
+        prover_params: &ProverParamsIn<P>,
         values: &[Blob],
         index: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
 
         // check index is valid
         if index >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         };
         // check param
         if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         }
 
         // hash into a set of scalars
         let scalars_fr_repr: Vec<FrRepr> = values
             .iter()
-            .map(|s| hash_to_field_repr_pointproofs(&s.as_ref()))
+            .map(|s| suite.hash_value_repr(s.as_ref()))
             .collect();
         let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
 
@@ -73,38 +75,36 @@ impl Proof {
     /// 0 is invalid -- no proof will be generated
     /// n is valid -- generating proof for the whole vector
     pub fn batch_new<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
+        prover_params: &ProverParamsIn<P>,
         values: &[Blob],
         indices: &[usize],
-    ) -> Result<Vec<Self>, String> {
+    ) -> Result<Vec<Self>, PointproofsError> {
         // checks that cipersuite is supported
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
 
         // check index is valid
         if indices.len() > prover_params.n || indices.is_empty() {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         };
         for e in indices {
             if *e >= prover_params.n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex);
             }
         }
 
         if !misc::has_unique_elements(indices) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
 
         // check param
         if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         }
 
         // hash into a set of scalars
         let scalars_fr_repr: Vec<FrRepr> = values
             .iter()
-            .map(|s| hash_to_field_repr_pointproofs(&s.as_ref()))
+            .map(|s| suite.hash_value_repr(s.as_ref()))
             .collect();
         let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
 
@@ -122,6 +122,122 @@ impl Proof {
             .collect())
     }
 
+    /// Generate a new set of proofs for a vector of scalars.
+    ///     * input: prover parameter set
+    ///     * input: a list of n scalars
+    ///     * input: the list of indices of the proof
+    ///     * output: a list of new proofs
+    ///     * error: invalid ciphersuite/parameters
+    /// Same as `batch_new`, except that the scalars are used as they are,
+    /// without hashing; see `Commitment::new_from_scalars`.
+    pub fn batch_new_from_scalars(
+        prover_params: &ProverParamsIn<P>,
+        scalars: &[Fr],
+        indices: &[usize],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+
+        // check index is valid
+        if indices.len() > prover_params.n || indices.is_empty() {
+            return Err(PointproofsError::InvalidIndex);
+        };
+        for e in indices {
+            if *e >= prover_params.n {
+                return Err(PointproofsError::InvalidIndex);
+            }
+        }
+
+        if !misc::has_unique_elements(indices) {
+            return Err(PointproofsError::DuplicatedIndex);
+        }
+
+        // check param
+        if scalars.len() != prover_params.n {
+            return Err(PointproofsError::InvalidIndex);
+        }
+
+        let scalars_fr_repr: Vec<FrRepr> = scalars.iter().map(|s| s.into_repr()).collect();
+        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+        Ok(indices
+            .iter()
+            .map(|e| Self {
+                ciphersuite: prover_params.ciphersuite,
+                proof: pp_sum_of_prod_helper(
+                    &prover_params,
+                    &scalars_u64,
+                    prover_params.n - *e,
+                    2 * prover_params.n - *e,
+                ),
+            })
+            .collect())
+    }
+
+    /// Generate the proofs for all n indices at once.
+    ///     * input: prover parameter set
+    ///     * input: values for the proof
+    ///     * output: the list of proofs for indices 0..n
+    ///     * error: invalid ciphersuite/parameters
+    /// The output is the same as `batch_new` over all indices, which costs
+    /// n sums of products of size n. Here the proofs are computed as one
+    /// convolution of the generators with the scalars, via FFT over the group,
+    /// which is O(n log n) group operations.
+    pub fn all_proofs<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParamsIn<P>,
+        values: &[Blob],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
+
+        // check param
+        let n = prover_params.n;
+        if values.len() != n || n == 0 {
+            return Err(PointproofsError::InvalidIndex);
+        }
+
+        // proof[e] = \prod_{j=0}^{n-1} generators[n - e + j]^m[j]
+        // with r[i] = m[n - 1 - i] this is the convolution
+        //     (generators * r)[2n - 1 - e]
+        // A cyclic convolution of size >= 2n has no wrap around on those entries.
+        let size = (2 * n).next_power_of_two();
+        let log_size = size.trailing_zeros();
+        let omega = root_of_unity(log_size);
+        let omega_inv = omega.inverse().unwrap();
+
+        let mut bases: Vec<P::G1> = vec![P::G1::zero(); size];
+        for (base, generator) in bases.iter_mut().zip(prover_params.generators.iter()) {
+            *base = generator.into_projective();
+        }
+
+        // the scaling of the inverse FFT is merged into the scalars
+        let mut size_inv = Fr::from_repr(FrRepr::from(size as u64)).unwrap();
+        size_inv = size_inv.inverse().unwrap();
+        let mut scalars: Vec<Fr> = vec![Fr::zero(); size];
+        for (i, value) in values.iter().enumerate() {
+            let mut scalar = suite.hash_value(value.as_ref());
+            scalar.mul_assign(&size_inv);
+            scalars[n - 1 - i] = scalar;
+        }
+
+        fft_g1(&mut bases, &omega);
+        fft_fr(&mut scalars, &omega);
+        for (base, scalar) in bases.iter_mut().zip(scalars.iter()) {
+            base.mul_assign(*scalar);
+        }
+        // the inverse FFT is the FFT with omega^{-1}
+        fft_g1(&mut bases, &omega_inv);
+
+        Ok((0..n)
+            .map(|e| Self {
+                ciphersuite: prover_params.ciphersuite,
+                proof: bases[2 * n - 1 - e],
+            })
+            .collect())
+    }
+
     /// Generate a new set of proofs.
     ///     * input: prover parameter set
     ///     * input: the commitment
@@ -133,41 +249,34 @@ impl Proof {
     /// 0 is invalid -- no proof will be generated
     /// n is valid -- generating proof for the whole vector
     pub fn batch_new_aggregated<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
-        commit: &Commitment,
+        prover_params: &ProverParamsIn<P>,
+        commit: &CommitmentIn<P>,
         values: &[Blob],
         indices: &[usize],
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
         if prover_params.ciphersuite != commit.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
 
         // check index is valid
         if indices.len() > prover_params.n || indices.is_empty() {
-            println!("inidces length to much");
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         };
         for e in indices {
             if *e >= prover_params.n {
-                println!("index to hight");
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex);
             }
         }
 
         if !misc::has_unique_elements(indices) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
 
         // check param
         if values.len() != prover_params.n {
-            println!("not correct length"); 
-            println!("length values {}", values.len());
-            println!("prover params {}", prover_params.n);
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         }
 
         // generate the list of sub_values
@@ -179,7 +288,7 @@ impl Proof {
         // hash into a set of scalars
         let scalars_fr: Vec<Fr> = values
             .iter()
-            .map(|s| hash_to_field_pointproofs(&s.as_ref()))
+            .map(|s| suite.hash_value(s.as_ref()))
             .collect();
         // get the list of scalars for each proof
         let ti = hash_to_ti_fr(commit, indices, &value_sub_vector, prover_params.n)?;
@@ -196,7 +305,7 @@ impl Proof {
 
         // remove the generators where the scalars are 0s, to form the final basis
         // also convert Fr-s to FrRepr-s to [u64;4]-s
-        let mut final_basis: Vec<PointproofsG1Affine> = Vec::with_capacity(2 * prover_params.n);
+        let mut final_basis: Vec<P::G1Affine> = Vec::with_capacity(2 * prover_params.n);
         let mut final_scalars_repr: Vec<FrRepr> = Vec::with_capacity(2 * prover_params.n);
         for (i, e) in final_scalars.iter().enumerate() {
             if !e.is_zero() {
@@ -211,7 +320,7 @@ impl Proof {
             if prover_params.precomp.len() == 512 * prover_params.n
                 && final_scalars_repr.len() <= 1024
             {
-                let mut final_basis_pp: Vec<PointproofsG1Affine> =
+                let mut final_basis_pp: Vec<P::G1Affine> =
                     Vec::with_capacity(512 * prover_params.n);
                 for (i, e) in final_scalars.iter().enumerate() {
                     if !e.is_zero() {
@@ -222,17 +331,17 @@ impl Proof {
                         .concat();
                     }
                 }
-                PointproofsG1Affine::sum_of_products_precomp_256(
+                P::G1Affine::sum_of_products_precomp_256(
                     &final_basis,
                     &scalars_u64,
                     &final_basis_pp,
                 )
             } else {
-                PointproofsG1Affine::sum_of_products(&final_basis, &scalars_u64)
+                P::G1Affine::sum_of_products(&final_basis, &scalars_u64)
             }
         };
 
-        Ok(Proof {
+        Ok(Self {
             ciphersuite: prover_params.ciphersuite,
             proof: agg_proof,
         })
@@ -248,24 +357,21 @@ impl Proof {
     ///     * error: invalid ciphersuite/parameters
     pub fn update<Blob: AsRef<[u8]>>(
         &mut self,
-        prover_params: &ProverParams,
+        prover_params: &ProverParamsIn<P>,
         proof_index: usize,
         changed_index: usize,
         value_before: Blob,
         value_after: Blob,
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
+        let suite = ciphersuite_def(prover_params.ciphersuite)?;
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
 
         // check indices are valid
         if proof_index >= prover_params.n || changed_index >= prover_params.n {
-            println!("in the function update");
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex);
         }
 
         // update the proof
@@ -274,9 +380,9 @@ impl Proof {
         // proof_param may be pre-computed -- the code will determine this
         // by checking the length of pre_comp
         if proof_index != changed_index {
-            let mut multiplier = hash_to_field_pointproofs(&value_before);
+            let mut multiplier = suite.hash_value(value_before.as_ref());
             multiplier.negate();
-            multiplier.add_assign(&hash_to_field_pointproofs(&value_after));
+            multiplier.add_assign(&suite.hash_value(value_after.as_ref()));
 
             let param_index = changed_index + prover_params.n - proof_index;
             let res = pp_single_exp_helper(&prover_params, multiplier, param_index);
@@ -288,6 +394,82 @@ impl Proof {
         Ok(())
     }
 
+    /// Updating an existing proof with a list of changed values,
+    /// using a single multi-exponentiation.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the indices for the values that are being changed
+    ///     * input: the values before the change
+    ///     * input: the values after the change
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// A change at proof_index itself does not change the proof, and is skipped.
+    pub fn batch_update<Blob: AsRef<[u8]>>(
+        &mut self,
+        prover_params: &ProverParamsIn<P>,
+        proof_index: usize,
+        changed_index: &[usize],
+        value_before: &[Blob],
+        value_after: &[Blob],
+    ) -> Result<(), PointproofsError> {
+        if self.ciphersuite != prover_params.ciphersuite {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if proof_index >= prover_params.n {
+            return Err(PointproofsError::InvalidIndex);
+        }
+        let multipliers =
+            update_multipliers(prover_params, changed_index, value_before, value_after)?;
+
+        let delta = proof_delta(prover_params, proof_index, changed_index, &multipliers);
+        self.proof.add_assign(&delta);
+        Ok(())
+    }
+
+    /// Updating a list of existing proofs with a same list of changed values.
+    /// The values are hashed once, and the proofs are updated in parallel,
+    /// each with a single multi-exponentiation.
+    ///     * input: prover parameter set
+    ///     * input: the proofs
+    ///     * input: the indices for the proofs
+    ///     * input: the indices for the values that are being changed
+    ///     * input: the values before the change
+    ///     * input: the values after the change
+    ///     * output: update the proofs to the new proofs
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// The proofs are left unchanged if an error is returned.
+    pub fn update_many_proofs<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParamsIn<P>,
+        proofs: &mut [Self],
+        proof_indices: &[usize],
+        changed_index: &[usize],
+        value_before: &[Blob],
+        value_after: &[Blob],
+    ) -> Result<(), PointproofsError> {
+        if proofs.len() != proof_indices.len() {
+            return Err(PointproofsError::IndexProofNotMatch);
+        }
+        for (proof, proof_index) in proofs.iter().zip(proof_indices.iter()) {
+            if proof.ciphersuite != prover_params.ciphersuite {
+                return Err(PointproofsError::InvalidCiphersuite);
+            }
+            if *proof_index >= prover_params.n {
+                return Err(PointproofsError::InvalidIndex);
+            }
+        }
+        let multipliers =
+            update_multipliers(prover_params, changed_index, value_before, value_after)?;
+
+        proofs
+            .par_iter_mut()
+            .zip(proof_indices.par_iter())
+            .for_each(|(proof, proof_index)| {
+                let delta = proof_delta(prover_params, *proof_index, changed_index, &multipliers);
+                proof.proof.add_assign(&delta);
+            });
+        Ok(())
+    }
+
     /// Verify the proof.
     ///     * input: the proof
     ///     * input: verifier parameter set
@@ -297,23 +479,19 @@ impl Proof {
     ///     * output: if the proof is valid w.r.t. the rest of inputs
     pub fn verify<Blob: AsRef<[u8]>>(
         &self,
-        verifier_params: &VerifierParams,
-        com: &Commitment,
+        verifier_params: &VerifierParamsIn<P>,
+        com: &CommitmentIn<P>,
         value: Blob,
         index: usize,
     ) -> bool {
         if self.ciphersuite != verifier_params.ciphersuite || self.ciphersuite != com.ciphersuite {
-            #[cfg(debug_assertions)]
-            println!(
-                " ciphersuite fails {}, {}, {}",
-                self.ciphersuite, verifier_params.ciphersuite, com.ciphersuite
-            );
             return false;
         }
 
-        if !check_ciphersuite(com.ciphersuite) {
-            return false;
-        }
+        let suite = match ciphersuite_def(com.ciphersuite) {
+            Ok(suite) => suite,
+            Err(_) => return false,
+        };
 
         if index >= verifier_params.n {
             return false;
@@ -329,7 +507,7 @@ impl Proof {
         // to take advantage of the pairing product computation, which is faster than two pairings.
 
         // step 1. compute hash_inverse
-        let hash = hash_to_field_pointproofs(&value);
+        let hash = suite.hash_value(value.as_ref());
         // we can safely assume that hash is invertible
         // see `hash_to_field` function
         let hash_inverse = hash.inverse().unwrap();
@@ -342,12 +520,66 @@ impl Proof {
         proof_mut.mul_assign(hash_inverse);
 
         // step 3. check pairing product
-        pointproofs_pairing_product(
-            com_mut.into_affine(),
-            verifier_params.generators[verifier_params.n - index - 1],
-            proof_mut.into_affine(),
-            PointproofsG2Affine::one(),
-        ) == verifier_params.gt_elt
+        verifier_params.pairing_product_with_generators(
+            &[],
+            &[],
+            &[
+                (com_mut.into_affine(), verifier_params.n - index - 1),
+                (proof_mut.into_affine(), verifier_params.n),
+            ],
+        ) == Some(verifier_params.gt_elt)
+    }
+
+    /// Verify the proof for a scalar.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment, from `Commitment::new_from_scalars`
+    ///     * input: the scalar
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    pub fn verify_scalars(
+        &self,
+        verifier_params: &VerifierParamsIn<P>,
+        com: &CommitmentIn<P>,
+        scalar: &Fr,
+        index: usize,
+    ) -> bool {
+        if self.ciphersuite != verifier_params.ciphersuite || self.ciphersuite != com.ciphersuite {
+            return false;
+        }
+
+        if !check_ciphersuite(com.ciphersuite) {
+            return false;
+        }
+
+        if index >= verifier_params.n {
+            return false;
+        }
+
+        // verification formula: e(com, param[n-index-1]) = gt_elt ^ scalar * e(proof, generator_of_g2)
+        // as in `verify`, both sides are multiplied by 1/scalar to stay out of the target group.
+        // Unlike a hash, a scalar can be zero, then the check is
+        //  e(com, param[n-index-1]) * e(proof^{-1}, generator_of_g2) ?= 1
+        let mut com_mut = com.commit;
+        let mut proof_mut = self.proof;
+        proof_mut.negate();
+        let expected = match scalar.inverse() {
+            Some(scalar_inverse) => {
+                com_mut.mul_assign(scalar_inverse);
+                proof_mut.mul_assign(scalar_inverse);
+                verifier_params.gt_elt
+            }
+            None => Fq12::one(),
+        };
+
+        verifier_params.pairing_product_with_generators(
+            &[],
+            &[],
+            &[
+                (com_mut.into_affine(), verifier_params.n - index - 1),
+                (proof_mut.into_affine(), verifier_params.n),
+            ],
+        ) == Some(expected)
     }
 
     /// Aggregates a vector of proofs from a same commitment into a single one.
@@ -362,43 +594,42 @@ impl Proof {
     ///         the aggregator does not check the validity of individual commit/proofs.
     ///         The caller may need to check them if they care for it.
     pub fn same_commit_aggregate<Blob: AsRef<[u8]>>(
-        commit: &Commitment,
+        commit: &CommitmentIn<P>,
         proofs: &[Self],
         set: &[usize],
         value_sub_vector: &[Blob],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // check that the csids are valid/match
         let csid = commit.ciphersuite;
         if !check_ciphersuite(csid) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         for e in proofs.iter() {
             if e.ciphersuite != csid {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         // check that the length of proofs and sets match
         if proofs.len() != set.len() || proofs.len() != value_sub_vector.len() {
-            return Err(ERR_INDEX_PROOF_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexProofNotMatch);
         }
 
         if !misc::has_unique_elements(set) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
 
         // get the list of scalas
         let ti = hash_to_ti_repr(commit, set, value_sub_vector, n)?;
         let scalars_u64: Vec<&[u64; 4]> = ti.iter().map(|s| &s.0).collect();
 
-        let mut bases: Vec<PointproofsG1> = proofs.iter().map(|s| s.proof).collect();
+        let mut bases: Vec<P::G1> = proofs.iter().map(|s| s.proof).collect();
         CurveProjective::batch_normalization(&mut bases);
-        let bases_affine: Vec<PointproofsG1Affine> =
-            bases.iter().map(|s| s.into_affine()).collect();
+        let bases_affine: Vec<P::G1Affine> = bases.iter().map(|s| s.into_affine()).collect();
         // proof = \prod proofs[i]^ti[i]
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = P::G1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
 
-        Ok(Proof {
+        Ok(Self {
             ciphersuite: csid,
             proof,
         })
@@ -418,35 +649,34 @@ impl Proof {
     ///         * t\[j\] = hash_to_tj(...)
     ///         * return prod proofs\[j\]^t\[j\]
     pub fn cross_commit_aggregate_partial<Blob: AsRef<[u8]>>(
-        commits: &[Commitment],
+        commits: &[CommitmentIn<P>],
         proofs: &[Self],
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // check ciphersuite
         let ciphersuite = commits[0].ciphersuite;
         if !check_ciphersuite(ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         for e in commits.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         for e in proofs.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         for e in set.iter() {
             if !misc::has_unique_elements(e) {
-                return Err(ERR_DUPLICATED_INDEX.to_owned());
+                return Err(PointproofsError::DuplicatedIndex);
             }
             for ee in e.iter() {
                 if *ee >= n {
-                    println!("in the funcion cross commit aggregate");
-                    return Err(ERR_INVALID_INDEX.to_owned());
+                    return Err(PointproofsError::InvalidIndex);
                 }
             }
         }
@@ -457,15 +687,7 @@ impl Proof {
             || commits.len() != value_sub_vector.len()
             || commits.is_empty()
         {
-            #[cfg(debug_assertions)]
-            println!(
-                "commit: {}, proofs: {}, set: {}, value_sub_vector: {}",
-                commits.len(),
-                proofs.len(),
-                set.len(),
-                value_sub_vector.len()
-            );
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::XComSize);
         };
 
         // if commit.len() == 1, return the aggregated proof
@@ -476,20 +698,19 @@ impl Proof {
         // start aggregation
         let scalars = hash_to_tj_repr(&commits, &set, &value_sub_vector, n)?;
         if scalars.len() != proofs.len() {
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::XComSize);
         }
 
         let scalars_u64: Vec<&[u64; 4]> = scalars.iter().map(|s| &s.0).collect();
 
-        let mut bases: Vec<PointproofsG1> = proofs.iter().map(|s| s.proof).collect();
+        let mut bases: Vec<P::G1> = proofs.iter().map(|s| s.proof).collect();
         CurveProjective::batch_normalization(&mut bases);
-        let bases_affine: Vec<PointproofsG1Affine> =
-            bases.iter().map(|s| s.into_affine()).collect();
+        let bases_affine: Vec<P::G1Affine> = bases.iter().map(|s| s.into_affine()).collect();
 
         // proof = \prod pi[i] ^ tj[i]
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = P::G1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
 
-        Ok(Proof { ciphersuite, proof })
+        Ok(Self { ciphersuite, proof })
     }
 
     /// Aggregate a 2-dim array of proofs, each row corresponding to a
@@ -509,36 +730,36 @@ impl Proof {
     ///         2. pi[j] = same_commit_aggregate(...)
     ///         3. return prod pi[j]^t[j]
     pub fn cross_commit_aggregate_full<Blob: AsRef<[u8]>>(
-        commits: &[Commitment],
+        commits: &[CommitmentIn<P>],
         proofs: &[Vec<Self>],
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // check ciphersuite
         let ciphersuite = commits[0].ciphersuite;
         if !check_ciphersuite(ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         for e in commits.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         for e in proofs.iter() {
             for ee in e.iter() {
                 if ee.ciphersuite != ciphersuite {
-                    return Err(ERR_CIPHERSUITE.to_owned());
+                    return Err(PointproofsError::InvalidCiphersuite);
                 }
             }
         }
         for e in set.iter() {
             if !misc::has_unique_elements(e) {
-                return Err(ERR_DUPLICATED_INDEX.to_owned());
+                return Err(PointproofsError::DuplicatedIndex);
             }
             for ee in e.iter() {
                 if *ee >= n {
-                    return Err(ERR_INVALID_INDEX.to_owned());
+                    return Err(PointproofsError::InvalidIndex);
                 }
             }
         }
@@ -549,15 +770,7 @@ impl Proof {
             || commits.len() != value_sub_vector.len()
             || commits.is_empty()
         {
-            #[cfg(debug_assertions)]
-            println!(
-                "commit: {}, proofs: {}, set: {}, value_sub_vector: {}",
-                commits.len(),
-                proofs.len(),
-                set.len(),
-                value_sub_vector.len()
-            );
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::XComSize);
         };
 
         // if commit.len() == 1, call normal aggregation
@@ -596,17 +809,16 @@ impl Proof {
         let scalars_u64: Vec<&[u64; 4]> = scalars_repr.iter().map(|s| &s.0).collect();
 
         // form the final basis
-        let mut bases: Vec<PointproofsG1> = proofs.concat().iter().map(|x| x.proof).collect();
+        let mut bases: Vec<P::G1> = proofs.concat().iter().map(|x| x.proof).collect();
         CurveProjective::batch_normalization(&mut bases);
         // `into_affine()` here only performs a type conversion
         // the CurveProjective points are already normalized via batch nomarlization
-        let bases_affine: Vec<PointproofsG1Affine> =
-            bases.iter().map(|s| s.into_affine()).collect();
+        let bases_affine: Vec<P::G1Affine> = bases.iter().map(|s| s.into_affine()).collect();
 
         // proof = \prod pi[i] ^ {tj[i] * ti[i,j]}
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = P::G1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
 
-        Ok(Proof { ciphersuite, proof })
+        Ok(Self { ciphersuite, proof })
     }
 
     /// batch verify a proof for a list of values/indices
@@ -618,8 +830,8 @@ impl Proof {
     ///     * output: if the proof is valid w.r.t. the rest of inputs
     pub fn same_commit_batch_verify<Blob: AsRef<[u8]>>(
         &self,
-        verifier_params: &VerifierParams,
-        com: &Commitment,
+        verifier_params: &VerifierParamsIn<P>,
+        com: &CommitmentIn<P>,
         set: &[usize],
         value_sub_vector: &[Blob],
     ) -> bool {
@@ -634,16 +846,11 @@ impl Proof {
         //   tmp = 1/ \sum value_i*t_i
 
         // 0. check the validity of the inputs: csid, length, etc
-        if !check_ciphersuite(com.ciphersuite) {
-            return false;
-        }
+        let suite = match ciphersuite_def(com.ciphersuite) {
+            Ok(suite) => suite,
+            Err(_) => return false,
+        };
         if com.ciphersuite != verifier_params.ciphersuite || com.ciphersuite != self.ciphersuite {
-            #[cfg(debug_assertions)]
-            println!(
-                "Ciphersuite failed? {} {} {}\n",
-                com.ciphersuite, verifier_params.ciphersuite, self.ciphersuite
-            );
-
             return false;
         }
         if set.len() != value_sub_vector.len() {
@@ -675,7 +882,7 @@ impl Proof {
         // 1.2 tmp = 1/\sum value_i*t_i
         let mut tmp = Fr::zero();
         for k in 0..set.len() {
-            let mut mi = hash_to_field_pointproofs(value_sub_vector[k].as_ref());
+            let mut mi = suite.hash_value(value_sub_vector[k].as_ref());
             mi.mul_assign(&ti[k]);
             tmp.add_assign(&mi);
         }
@@ -698,7 +905,7 @@ impl Proof {
             .collect();
 
         // 2.2 g2^{\sum_{i \in set} \alpha^{N+1-i} t_i*tmp}
-        let bases: Vec<PointproofsG2Affine> = set
+        let bases: Vec<P::G2Affine> = set
             .iter()
             .map(|index| verifier_params.generators[verifier_params.n - index - 1])
             .collect();
@@ -707,8 +914,7 @@ impl Proof {
             // the second condition `n <= 1024` comes from benchmarking
             // pre-computation is faster only when the #basis is <1024
             if verifier_params.precomp.len() == 256 * verifier_params.n && bases.len() <= 1024 {
-                let mut bases_precomp: Vec<PointproofsG2Affine> =
-                    Vec::with_capacity(bases.len() * 256);
+                let mut bases_precomp: Vec<P::G2Affine> = Vec::with_capacity(bases.len() * 256);
                 for e in set.iter() {
                     bases_precomp = [
                         bases_precomp,
@@ -718,13 +924,9 @@ impl Proof {
                     ]
                     .concat();
                 }
-                PointproofsG2Affine::sum_of_products_precomp_256(
-                    &bases,
-                    &scalars_u64,
-                    &bases_precomp,
-                )
+                P::G2Affine::sum_of_products_precomp_256(&bases, &scalars_u64, &bases_precomp)
             } else {
-                PointproofsG2Affine::sum_of_products(&bases, &scalars_u64)
+                P::G2Affine::sum_of_products(&bases, &scalars_u64)
             }
         };
 
@@ -734,12 +936,11 @@ impl Proof {
         proof_mut.mul_assign(tmp);
 
         // 3 pairing product
-        pointproofs_pairing_product(
-            com.commit.into_affine(),
-            param_subset_sum.into_affine(),
-            proof_mut.into_affine(),
-            PointproofsG2Affine::one(),
-        ) == verifier_params.gt_elt
+        verifier_params.pairing_product_with_generators(
+            &[com.commit.into_affine()],
+            &[param_subset_sum.into_affine()],
+            &[(proof_mut.into_affine(), verifier_params.n)],
+        ) == Some(verifier_params.gt_elt)
     }
 
     /// Verify a proof which was aggregated from 2-dim array of proofs
@@ -751,8 +952,8 @@ impl Proof {
     ///     * output: if the proof is valid w.r.t. the rest of inputs
     pub fn cross_commit_batch_verify<Blob: AsRef<[u8]>>(
         &self,
-        verifier_params: &VerifierParams,
-        com: &[Commitment],
+        verifier_params: &VerifierParamsIn<P>,
+        com: &[CommitmentIn<P>],
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
     ) -> bool {
@@ -765,6 +966,10 @@ impl Proof {
                 return false;
             }
         }
+        let suite = match ciphersuite_def(self.ciphersuite) {
+            Ok(suite) => suite,
+            Err(_) => return false,
+        };
 
         // check length
         let num_commit = com.len();
@@ -825,7 +1030,7 @@ impl Proof {
             // tmp2 = sum_i m_ij * t_ij
             for k in 0..ti_s[j].len() {
                 let mut tmp3 = ti_s[j][k];
-                let mij = hash_to_field_pointproofs(value_sub_vector[j][k].as_ref());
+                let mij = suite.hash_value(value_sub_vector[j][k].as_ref());
                 tmp3.mul_assign(&mij);
                 tmp2.add_assign(&tmp3);
             }
@@ -851,7 +1056,7 @@ impl Proof {
 
         // g1_vec stores the g1 components for the pairing product
         // for j \in [num_commit], store com[j]
-        let mut g1_proj: Vec<PointproofsG1> = com.iter().map(|x| x.commit).collect();
+        let mut g1_proj: Vec<P::G1> = com.iter().map(|x| x.commit).collect();
         // the last element for g1_vec is proof^{-1/tmp}
         let mut tmp2 = self.proof;
         tmp2.negate();
@@ -859,12 +1064,12 @@ impl Proof {
         g1_proj.push(tmp2);
 
         // convert g1_proj into g1_affine
-        PointproofsG1::batch_normalization(&mut g1_proj);
-        let g1_vec: Vec<PointproofsG1Affine> = g1_proj.iter().map(|s| s.into_affine()).collect();
+        P::G1::batch_normalization(&mut g1_proj);
+        let g1_vec: Vec<P::G1Affine> = g1_proj.iter().map(|s| s.into_affine()).collect();
 
         // g2_vec stores the g2 components for the pairing product
         // for j \in [num_commit], g2^{\sum alpha^{n + 1 - i} * t_i,j} * tj/tmp )
-        let mut g2_proj: Vec<PointproofsG2> = Vec::with_capacity(num_commit + 1);
+        let mut g2_proj: Vec<P::G2> = Vec::with_capacity(num_commit + 1);
         for j in 0..num_commit {
             let num_proof = ti_s[j].len();
             let mut tmp3 = tmp_inverse;
@@ -874,7 +1079,7 @@ impl Proof {
             tmp3.mul_assign(&scalar);
 
             // subset_sum = \sum alpha^{n + 1 - i} * t_i,j}
-            let mut bases: Vec<PointproofsG2Affine> = Vec::with_capacity(num_proof);
+            let mut bases: Vec<P::G2Affine> = Vec::with_capacity(num_proof);
             let mut scalars_u64: Vec<[u64; 4]> = Vec::with_capacity(num_proof);
             for k in 0..num_proof {
                 bases.push(verifier_params.generators[verifier_params.n - set[j][k] - 1]);
@@ -888,8 +1093,7 @@ impl Proof {
                 // the second condition `n <= 1024` comes from benchmarking
                 // pre-computation is faster only when the #basis is <1024
                 if verifier_params.precomp.len() == 256 * verifier_params.n && bases.len() <= 1024 {
-                    let mut bases_precomp: Vec<PointproofsG2Affine> =
-                        Vec::with_capacity(num_proof * 256);
+                    let mut bases_precomp: Vec<P::G2Affine> = Vec::with_capacity(num_proof * 256);
                     for k in 0..num_proof {
                         bases_precomp = [
                             bases_precomp,
@@ -899,23 +1103,270 @@ impl Proof {
                         ]
                         .concat();
                     }
-                    PointproofsG2Affine::sum_of_products_precomp_256(
+                    P::G2Affine::sum_of_products_precomp_256(
                         &bases,
                         &scalars_u64_ref,
                         &bases_precomp,
                     )
                 } else {
-                    PointproofsG2Affine::sum_of_products(&bases, &scalars_u64_ref)
+                    P::G2Affine::sum_of_products(&bases, &scalars_u64_ref)
                 }
             };
             g2_proj.push(param_subset_sum);
         }
-        // the last element for g1_vec is g2
-        g2_proj.push(PointproofsG2::one());
         // convert g2_proj into g2_affine
-        PointproofsG2::batch_normalization(&mut g2_proj);
-        let g2_vec: Vec<PointproofsG2Affine> = g2_proj.iter().map(|s| s.into_affine()).collect();
-        // now check the pairing product ?= verifier_params.gt_elt
-        pointproofs_pairing_multi_product(&g1_vec[..], &g2_vec[..]) == verifier_params.gt_elt
+        P::G2::batch_normalization(&mut g2_proj);
+        let g2_vec: Vec<P::G2Affine> = g2_proj.iter().map(|s| s.into_affine()).collect();
+        // now check the pairing product ?= verifier_params.gt_elt,
+        // where the last element of g1_vec is paired with g2
+        verifier_params.pairing_product_with_generators(
+            &g1_vec[..num_commit],
+            &g2_vec[..],
+            &[(g1_vec[num_commit], verifier_params.n)],
+        ) == Some(verifier_params.gt_elt)
+    }
+
+    /// Batch verify a list of independent proofs, e.g., proofs for different commitments
+    /// that are not aggregated.
+    ///     * input: verifier parameter set
+    ///     * input: a list of (commitment, proof, index, value)
+    ///     * output: the positions of the invalid proofs in the list, sorted; empty if all are valid
+    /// The proofs are checked together with a random linear combination and a single
+    /// multi-pairing, instead of two pairings per proof. The coefficients are derived from
+    /// a hash of the whole list (Fiat-Shamir). If this check fails, the list is bisected
+    /// to identify the invalid proofs.
+    pub fn batch_verify_many<Blob: AsRef<[u8]>>(
+        verifier_params: &VerifierParamsIn<P>,
+        items: &[(&CommitmentIn<P>, &Self, usize, Blob)],
+    ) -> Vec<usize> {
+        // proofs with a wrong ciphersuite or index are invalid on their own,
+        // and are not part of the linear combination
+        let mut invalid: Vec<usize> = vec![];
+        let mut positions: Vec<usize> = Vec::with_capacity(items.len());
+        for (k, (com, proof, index, _value)) in items.iter().enumerate() {
+            if proof.ciphersuite != verifier_params.ciphersuite
+                || com.ciphersuite != verifier_params.ciphersuite
+                || *index >= verifier_params.n
+            {
+                invalid.push(k);
+            } else {
+                positions.push(k);
+            }
+        }
+        let suite = match ciphersuite_def(verifier_params.ciphersuite) {
+            Ok(suite) => suite,
+            Err(_) => return (0..items.len()).collect(),
+        };
+        if positions.is_empty() {
+            return invalid;
+        }
+
+        // step 1. hash the inputs into the coefficients r_k
+        let hash_input: Vec<(&CommitmentIn<P>, &ProofIn<P>, usize, &[u8])> = positions
+            .iter()
+            .map(|k| (items[*k].0, items[*k].1, items[*k].2, items[*k].3.as_ref()))
+            .collect();
+        let coefficients = match hash_to_rk_fr(verifier_params.ciphersuite, &hash_input) {
+            Ok(p) => p,
+            Err(_) => return (0..items.len()).collect(),
+        };
+
+        // step 2. normalize the commitments and the proofs once for all the checks
+        let mut g1_proj: Vec<P::G1> = hash_input
+            .iter()
+            .flat_map(|(com, proof, _, _)| vec![com.commit, proof.proof])
+            .collect();
+        P::G1::batch_normalization(&mut g1_proj);
+        let batch: Vec<BatchItem<P>> = hash_input
+            .iter()
+            .zip(coefficients.iter())
+            .enumerate()
+            .map(|(k, ((_, _, index, value), coefficient))| BatchItem {
+                index: *index,
+                commit: g1_proj[2 * k].into_affine(),
+                proof: g1_proj[2 * k + 1].into_affine(),
+                hash: suite.hash_value(value),
+                coefficient: *coefficient,
+            })
+            .collect();
+
+        // step 3. check the whole list, and bisect it if the check fails
+        let mut invalid_batch: Vec<usize> = vec![];
+        let all: Vec<usize> = (0..batch.len()).collect();
+        find_invalid(verifier_params, &batch, &all, false, &mut invalid_batch);
+        invalid.extend(invalid_batch.iter().map(|k| positions[*k]));
+        invalid.sort_unstable();
+        invalid
+    }
+}
+
+/// Checks a list of changed values, and hashes each of them into the multiplier
+/// hash(new_value) - hash(old_value).
+///     * error: invalid ciphersuite, indices or lengths
+/// The indices shall be distinct and within [0, n); there can be up to n of them.
+fn update_multipliers<P: GroupPlacement, Blob: AsRef<[u8]>>(
+    prover_params: &ProverParamsIn<P>,
+    changed_index: &[usize],
+    value_before: &[Blob],
+    value_after: &[Blob],
+) -> Result<Vec<FrRepr>, PointproofsError> {
+    let suite = ciphersuite_def(prover_params.ciphersuite)?;
+    for index in changed_index {
+        if *index >= prover_params.n {
+            return Err(PointproofsError::InvalidIndex);
+        }
+    }
+    if changed_index.len() != value_before.len() || changed_index.len() != value_after.len() {
+        return Err(PointproofsError::IndexValueNotMatch);
+    }
+    if !misc::has_unique_elements(changed_index) {
+        return Err(PointproofsError::DuplicatedIndex);
+    }
+
+    Ok(value_before
+        .iter()
+        .zip(value_after.iter())
+        .map(|(before, after)| {
+            let mut multiplier = suite.hash_value(before.as_ref());
+            multiplier.negate();
+            multiplier.add_assign(&suite.hash_value(after.as_ref()));
+            multiplier.into_repr()
+        })
+        .collect())
+}
+
+/// Computes the change of the proof for proof_index:
+///     \prod_{j != proof_index} g[j + n - proof_index]^multiplier[j]
+/// where j ranges over the changed indices.
+fn proof_delta<P: GroupPlacement>(
+    prover_params: &ProverParamsIn<P>,
+    proof_index: usize,
+    changed_index: &[usize],
+    multipliers: &[FrRepr],
+) -> P::G1 {
+    let mut param_indices: Vec<usize> = Vec::with_capacity(changed_index.len());
+    let mut scalars_u64: Vec<&[u64; 4]> = Vec::with_capacity(changed_index.len());
+    for (index, multiplier) in changed_index.iter().zip(multipliers.iter()) {
+        // a change of the value at proof_index does not change the proof
+        if *index != proof_index {
+            param_indices.push(index + prover_params.n - proof_index);
+            scalars_u64.push(&multiplier.0);
+        }
+    }
+    if param_indices.is_empty() {
+        return P::G1::zero();
+    }
+    pp_sum_of_prod_indexed_helper(prover_params, &scalars_u64, &param_indices)
+}
+
+/// A proof in `Proof::batch_verify_many`, with the data that the checks share.
+struct BatchItem<P: GroupPlacement> {
+    index: usize,
+    commit: P::G1Affine,
+    proof: P::G1Affine,
+    hash: Fr,
+    coefficient: Fr,
+}
+
+/// Checks the random linear combination of the proofs of a sub-list.
+/// Each proof satisfies e(com, g2[n-index-1]) = gt_elt ^ hash(value) * e(proof, generator_of_g2);
+/// with the coefficients r_k and tmp = \sum r_k * hash_k, the combination is
+///     \prod_{i} e(\sum_{index_k = i} com_k^{r_k/tmp}, g2[n-i-1])
+///         * e(\sum_k proof_k^{-r_k/tmp}, generator_of_g2) ?= gt_elt
+/// which takes one pairing per distinct index, plus one.
+fn batch_check<P: GroupPlacement>(
+    verifier_params: &VerifierParamsIn<P>,
+    batch: &[BatchItem<P>],
+    subset: &[usize],
+) -> bool {
+    // step 1. compute 1/tmp
+    let mut tmp = Fr::zero();
+    for k in subset {
+        let mut t = batch[*k].coefficient;
+        t.mul_assign(&batch[*k].hash);
+        tmp.add_assign(&t);
+    }
+    // tmp == 0 happens with negligible probability;
+    // the sub-lists are then checked separately
+    let tmp_inverse = match tmp.inverse() {
+        Some(p) => p,
+        None => return false,
+    };
+
+    // step 2. group the commitments by index, and sum them and the proofs
+    let mut groups: BTreeMap<usize, (Vec<P::G1Affine>, Vec<FrRepr>)> = BTreeMap::new();
+    let mut proof_bases: Vec<P::G1Affine> = Vec::with_capacity(subset.len());
+    let mut proof_scalars: Vec<FrRepr> = Vec::with_capacity(subset.len());
+    for k in subset {
+        let mut scalar = batch[*k].coefficient;
+        scalar.mul_assign(&tmp_inverse);
+        let group = groups
+            .entry(batch[*k].index)
+            .or_insert_with(|| (vec![], vec![]));
+        group.0.push(batch[*k].commit);
+        group.1.push(scalar.into_repr());
+        scalar.negate();
+        proof_bases.push(batch[*k].proof);
+        proof_scalars.push(scalar.into_repr());
+    }
+
+    // generators[n] stands for the generator of the group
+    let mut g1_proj: Vec<P::G1> = Vec::with_capacity(groups.len() + 1);
+    let mut g2_index: Vec<usize> = Vec::with_capacity(groups.len() + 1);
+    for (index, (bases, scalars)) in groups.iter() {
+        g1_proj.push(sum_of_products_helper(bases, scalars));
+        g2_index.push(verifier_params.n - index - 1);
+    }
+    g1_proj.push(sum_of_products_helper(&proof_bases, &proof_scalars));
+    g2_index.push(verifier_params.n);
+
+    // step 3. check the pairing product
+    P::G1::batch_normalization(&mut g1_proj);
+    let fixed: Vec<(P::G1Affine, usize)> = g1_proj
+        .iter()
+        .zip(g2_index.iter())
+        .map(|(s, index)| (s.into_affine(), *index))
+        .collect();
+    verifier_params.pairing_product_with_generators(&[], &[], &fixed)
+        == Some(verifier_params.gt_elt)
+}
+
+/// Computes \sum_i bases[i]^scalars[i], with the multi-threaded multi-exponentiation for large inputs.
+fn sum_of_products_helper<G: CurveAffine>(bases: &[G], scalars: &[FrRepr]) -> G::Projective
+where
+    G: Sync,
+    G::Projective: Send,
+{
+    let scalars_u64: Vec<&[u64; 4]> = scalars.iter().map(|s| &s.0).collect();
+    if bases.len() >= PARALLEL_MSM_THRESHOLD {
+        parallel_sum_of_products(bases, &scalars_u64)
+    } else {
+        G::sum_of_products(bases, &scalars_u64)
+    }
+}
+
+/// Finds the invalid proofs of a sub-list by bisection, and appends their positions to invalid.
+/// If the sub-list is known to fail the check, e.g., its sibling passed while their union
+/// failed, the check is skipped.
+fn find_invalid<P: GroupPlacement>(
+    verifier_params: &VerifierParamsIn<P>,
+    batch: &[BatchItem<P>],
+    subset: &[usize],
+    known_invalid: bool,
+    invalid: &mut Vec<usize>,
+) {
+    if subset.is_empty() || (!known_invalid && batch_check(verifier_params, batch, subset)) {
+        return;
+    }
+    if subset.len() == 1 {
+        invalid.push(subset[0]);
+        return;
     }
+    let (left, right) = subset.split_at(subset.len() / 2);
+    let num_invalid = invalid.len();
+    find_invalid(verifier_params, batch, left, false, invalid);
+    // the combination is multiplicative, so if the left half passes, the right half fails
+    // (except if tmp is 0 for the sub-list, which happens with negligible probability)
+    let left_passed = invalid.len() == num_invalid;
+    find_invalid(verifier_params, batch, right, left_passed, invalid);
 }
@@ -0,0 +1,96 @@
+//! this file is part of the pointproofs.
+//! It implements serde on top of the SerDes encodings (compressed, with the ciphersuite id):
+//! a hex string for human-readable formats such as JSON, raw bytes for binary formats such as CBOR.
+//! Deserialization goes through SerDes, so it performs the same validations.
+
+use pairing::serdes::SerDes;
+use pairings::*;
+use serde::de::Error as DeError;
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::Error as SerError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string or a byte string")
+    }
+
+    fn visit_str<E: DeError>(self, v: &str) -> Result<Vec<u8>, E> {
+        from_hex(v).ok_or_else(|| E::custom("invalid hex string"))
+    }
+
+    fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(v)
+    }
+
+    // some binary formats encode bytes as a sequence of u8
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+macro_rules! impl_serde {
+    ($type:ident) => {
+        impl<P: GroupPlacement> Serialize for $type<P> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut buf: Vec<u8> = vec![];
+                <$type<P> as SerDes>::serialize(self, &mut buf, true).map_err(S::Error::custom)?;
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&to_hex(&buf))
+                } else {
+                    serializer.serialize_bytes(&buf)
+                }
+            }
+        }
+
+        impl<'de, P: GroupPlacement> Deserialize<'de> for $type<P> {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let buf = if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(BytesVisitor)?
+                } else {
+                    deserializer.deserialize_bytes(BytesVisitor)?
+                };
+                let mut reader = buf.as_slice();
+                let value = <$type<P> as SerDes>::deserialize(&mut reader, true).map_err(D::Error::custom)?;
+                // trailing bytes are an error, like a length mismatch
+                if !reader.is_empty() {
+                    return Err(D::Error::custom("trailing bytes"));
+                }
+                Ok(value)
+            }
+        }
+    };
+}
+
+impl_serde!(CommitmentIn);
+impl_serde!(ProofIn);
+impl_serde!(ProverParamsIn);
+impl_serde!(VerifierParamsIn);
+impl_serde!(UpdateProofIn);
@@ -1,6 +1,7 @@
 //! this file is part of the pointproofs.
 //! It defines APIs for (de)serialization.
 
+use ff::{PrimeField, PrimeFieldRepr};
 use pairing::bls12_381::*;
 use pairing::serdes::SerDes;
 use pairing::CurveProjective;
@@ -10,7 +11,35 @@ use pairings::pointproofs_groups::*;
 use pairings::*;
 
 type Compressed = bool;
-impl SerDes for Commitment {
+
+/// Reads the ciphersuite id of a blob, whose high bit is the tag of the group placement.
+/// Returns an error if the blob is of another placement, or if the ciphersuite id is invalid.
+fn read_ciphersuite<P: GroupPlacement, R: std::io::Read>(
+    reader: &mut R,
+) -> std::io::Result<Ciphersuite> {
+    let mut constants: [u8; 1] = [0u8; 1];
+    reader.read_exact(&mut constants)?;
+
+    // check the group placement of the blob
+    if constants[0] & PLACEMENT_TAG_MASK != P::TAG {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            PointproofsError::Placement,
+        ));
+    }
+
+    // check the ciphersuite id in the blob
+    let ciphersuite = constants[0] & !PLACEMENT_TAG_MASK;
+    if !check_ciphersuite(ciphersuite) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            PointproofsError::InvalidCiphersuite,
+        ));
+    }
+    Ok(ciphersuite)
+}
+
+impl<P: GroupPlacement> SerDes for CommitmentIn<P> {
     /// Convert a pop into a blob:
     ///
     /// `|ciphersuite id| commit |` => bytes
@@ -25,7 +54,7 @@ impl SerDes for Commitment {
         if !check_ciphersuite(self.ciphersuite) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                PointproofsError::InvalidCiphersuite,
             ));
         }
 
@@ -33,11 +62,11 @@ impl SerDes for Commitment {
         if !compressed {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
+                PointproofsError::Compress,
             ));
         }
 
-        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        let mut buf: Vec<u8> = vec![self.ciphersuite | P::TAG];
         self.commit.into_affine().serialize(&mut buf, compressed)?;
 
         // format the output
@@ -59,34 +88,23 @@ impl SerDes for Commitment {
         if !compressed {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
-        // constants stores id and the number of ssk-s
-        let mut constants: [u8; 1] = [0u8; 1];
-
-        reader.read_exact(&mut constants)?;
-
-        // check the ciphersuite id in the blob
-        if !check_ciphersuite(constants[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                PointproofsError::Compress,
             ));
         }
+        let ciphersuite = read_ciphersuite::<P, R>(reader)?;
 
         // read into commit
-        let commit = PointproofsG1::deserialize(reader, compressed)?;
+        let commit = P::G1::deserialize(reader, compressed)?;
 
         // finished
-        Ok(Commitment {
-            ciphersuite: constants[0],
+        Ok(Self {
+            ciphersuite,
             commit,
         })
     }
 }
 
-impl SerDes for Proof {
+impl<P: GroupPlacement> SerDes for ProofIn<P> {
     /// Convert a pop into a blob:
     ///
     /// `|ciphersuite id| commit |` => bytes
@@ -101,17 +119,17 @@ impl SerDes for Proof {
         if !compressed {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
+                PointproofsError::Compress,
             ));
         }
         // check the cipher suite id
         if !check_ciphersuite(self.ciphersuite) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                PointproofsError::InvalidCiphersuite,
             ));
         }
-        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        let mut buf: Vec<u8> = vec![self.ciphersuite | P::TAG];
         self.proof.into_affine().serialize(&mut buf, compressed)?;
 
         // format the output
@@ -133,34 +151,20 @@ impl SerDes for Proof {
         if !compressed {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
-        // constants stores ciphersuite id
-        let mut constants: [u8; 1] = [0u8; 1];
-
-        reader.read_exact(&mut constants)?;
-
-        // check the ciphersuite id in the blob
-        if !check_ciphersuite(constants[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                PointproofsError::Compress,
             ));
         }
+        let ciphersuite = read_ciphersuite::<P, R>(reader)?;
 
         // read into proof
-        let proof = PointproofsG1::deserialize(reader, compressed)?;
+        let proof = P::G1::deserialize(reader, compressed)?;
 
         // finished
-        Ok(Proof {
-            ciphersuite: constants[0],
-            proof,
-        })
+        Ok(Self { ciphersuite, proof })
     }
 }
 
-impl SerDes for ProverParams {
+impl<P: GroupPlacement> SerDes for ProverParamsIn<P> {
     /// Convert a ProverParam into a blob:
     ///
     /// `|ciphersuite id | n | generators | pp_len | [pre_compute]` => bytes
@@ -175,33 +179,33 @@ impl SerDes for ProverParams {
         if !check_ciphersuite(self.ciphersuite) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                PointproofsError::InvalidCiphersuite,
             ));
         }
         if !compressed {
             // we only support compress == true mode
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
+                PointproofsError::Compress,
             ));
         }
         // check that #generators and #precomp matches sp value
         if self.n * 2 != self.generators.len() || self.pp_len != self.precomp.len() || self.n == 0 {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
+                PointproofsError::InvalidValue,
             ));
         }
 
         if self.n > (u32::max_value() as usize) || self.pp_len > (u32::max_value() as usize) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
+                PointproofsError::InvalidValue,
             ));
         }
 
         // write csid
-        writer.write_all(&[self.ciphersuite])?;
+        writer.write_all(&[self.ciphersuite | P::TAG])?;
         writer.write_all(&(self.n as u32).to_le_bytes())?;
 
         // write the generators
@@ -231,19 +235,10 @@ impl SerDes for ProverParams {
             // we only support compress == true mode
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
-        // read into buf of compressed size
-        let mut csid = vec![0u8; 1];
-        reader.read_exact(&mut csid)?;
-
-        if !check_ciphersuite(csid[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE.to_owned(),
+                PointproofsError::Compress,
             ));
         }
+        let ciphersuite = read_ciphersuite::<P, R>(reader)?;
 
         // read n
         let mut buf = [0u8; 4];
@@ -254,16 +249,16 @@ impl SerDes for ProverParams {
             // to prevent potential DoS kind of attacks
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_MAX_N,
+                PointproofsError::MaxN,
             ));
         }
 
         // write csid
-        let mut generators: Vec<PointproofsG1Affine> = vec![];
+        let mut generators: Vec<P::G1Affine> = vec![];
 
         // write the generators
         for _i in 0..n * 2 {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
+            let g = P::G1Affine::deserialize(reader, true)?;
             generators.push(g);
         }
 
@@ -271,15 +266,15 @@ impl SerDes for ProverParams {
         reader.read_exact(&mut buf)?;
         let pp_len = u32::from_le_bytes(buf) as usize;
 
-        let mut precomp: Vec<PointproofsG1Affine> = vec![];
+        let mut precomp: Vec<P::G1Affine> = vec![];
         for _i in 0..pp_len {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
+            let g = P::G1Affine::deserialize(reader, true)?;
             precomp.push(g);
         }
 
         // format the output
         Ok(Self {
-            ciphersuite: csid[0],
+            ciphersuite,
             n,
             generators,
             pp_len,
@@ -288,7 +283,7 @@ impl SerDes for ProverParams {
     }
 }
 
-impl SerDes for VerifierParams {
+impl<P: GroupPlacement> SerDes for VerifierParamsIn<P> {
     /// Convert a VerifierParams into a blob:
     ///
     /// `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element` => bytes
@@ -303,31 +298,31 @@ impl SerDes for VerifierParams {
         if !check_ciphersuite(self.ciphersuite) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                PointproofsError::InvalidCiphersuite,
             ));
         }
         if !compressed {
             // we only support compress == true mode
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
+                PointproofsError::Compress,
             ));
         }
         // check that #generators matches sp
         if self.n != self.generators.len() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
+                PointproofsError::InvalidValue,
             ));
         }
         if self.n > (u32::max_value() as usize) || self.pp_len > (u32::max_value() as usize) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
+                PointproofsError::InvalidValue,
             ));
         }
 
-        writer.write_all(&[self.ciphersuite])?;
+        writer.write_all(&[self.ciphersuite | P::TAG])?;
         writer.write_all(&(self.n as u32).to_le_bytes())?;
 
         // write the generators
@@ -359,19 +354,10 @@ impl SerDes for VerifierParams {
             // we only support compress == true mode
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
-        // read into buf of compressed size
-        let mut csid = vec![0u8; 1];
-        reader.read_exact(&mut csid)?;
-
-        if !check_ciphersuite(csid[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE.to_owned(),
+                PointproofsError::Compress,
             ));
         }
+        let ciphersuite = read_ciphersuite::<P, R>(reader)?;
 
         // read n
         let mut buf = [0u8; 4];
@@ -382,14 +368,14 @@ impl SerDes for VerifierParams {
             // to prevent potential DoS kind of attacks
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_MAX_N,
+                PointproofsError::MaxN,
             ));
         }
 
         // write the generators
-        let mut generators: Vec<PointproofsG2Affine> = vec![];
+        let mut generators: Vec<P::G2Affine> = vec![];
         for _i in 0..n {
-            let g = PointproofsG2Affine::deserialize(reader, true)?;
+            let g = P::G2Affine::deserialize(reader, true)?;
             generators.push(g);
         }
 
@@ -397,9 +383,9 @@ impl SerDes for VerifierParams {
         reader.read_exact(&mut buf)?;
         let pp_len = u32::from_le_bytes(buf) as usize;
 
-        let mut precomp: Vec<PointproofsG1Affine> = vec![];
+        let mut precomp: Vec<P::G2Affine> = vec![];
         for _i in 0..pp_len {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
+            let g = P::G2Affine::deserialize(reader, true)?;
             precomp.push(g);
         }
 
@@ -407,12 +393,94 @@ impl SerDes for VerifierParams {
 
         // format the output
         Ok(Self {
-            ciphersuite: csid[0],
+            ciphersuite,
             n,
             generators,
             pp_len: 0,
             precomp: vec![],
             gt_elt,
+            prepared: vec![],
+        })
+    }
+}
+
+impl<P: GroupPlacement> SerDes for UpdateProofIn<P> {
+    /// Convert an UpdateProof into a blob:
+    ///
+    /// `|ciphersuite id | delta_g1 | delta_g2 | nonce_commit | response |` => bytes
+    ///
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+    ) -> std::io::Result<()> {
+        // compressed must be true
+        if !compressed {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                PointproofsError::Compress,
+            ));
+        }
+        // check the cipher suite id
+        if !check_ciphersuite(self.ciphersuite) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                PointproofsError::InvalidCiphersuite,
+            ));
+        }
+        let mut buf: Vec<u8> = vec![self.ciphersuite | P::TAG];
+        self.delta_g1.serialize(&mut buf, compressed)?;
+        self.delta_g2.serialize(&mut buf, compressed)?;
+        self.nonce_commit.serialize(&mut buf, compressed)?;
+        self.response.into_repr().write_be(&mut buf)?;
+
+        // format the output
+        writer.write_all(&buf)?;
+        Ok(())
+    }
+
+    /// Convert a blob into an UpdateProof:
+    ///
+    /// bytes => `|ciphersuite id | delta_g1 | delta_g2 | nonce_commit | response |`
+    ///
+    /// Returns an error if deserialization fails, or if
+    /// the response is not a valid scalar.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        // compressed must be true
+        if !compressed {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                PointproofsError::Compress,
+            ));
+        }
+        let ciphersuite = read_ciphersuite::<P, R>(reader)?;
+
+        let delta_g1 = P::G1Affine::deserialize(reader, compressed)?;
+        let delta_g2 = P::G2Affine::deserialize(reader, compressed)?;
+        let nonce_commit = P::G1Affine::deserialize(reader, compressed)?;
+        let mut repr = FrRepr::default();
+        repr.read_be(reader)?;
+        let response = match Fr::from_repr(repr) {
+            Ok(p) => p,
+            Err(e) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    e.to_string(),
+                ))
+            }
+        };
+
+        // finished
+        Ok(Self {
+            ciphersuite,
+            delta_g1,
+            delta_g2,
+            nonce_commit,
+            response,
         })
     }
 }
@@ -7,7 +7,7 @@ fn negative_test_same_commit_aggregation() {
     let (prover_params, verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut verifier_params2 = verifier_params.clone();
-    verifier_params2.ciphersuite = 1;
+    verifier_params2.ciphersuite = 255;
 
     let mut init_values = Vec::with_capacity(n);
     for i in 0..n {
@@ -22,7 +22,7 @@ fn negative_test_same_commit_aggregation() {
 
     let com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 255;
 
     let set = vec![1usize, 4, 7];
     let set2 = vec![1usize, 4, 6, 7];
@@ -35,7 +35,7 @@ fn negative_test_same_commit_aggregation() {
         value_sub_vector.push(values[*index]);
     }
     let mut proofs2 = proofs.clone();
-    proofs2[0].ciphersuite = 1;
+    proofs2[0].ciphersuite = 255;
 
     assert!(
         Proof::same_commit_aggregate(&com2, &proofs, &set, &value_sub_vector, prover_params.n)
@@ -183,7 +183,7 @@ fn negative_test_cross_commit_aggregation() {
         value_sub_vector.push(tmp_value_sub_vector);
     }
 
-    commits[0].ciphersuite = 1;
+    commits[0].ciphersuite = 255;
     assert!(Proof::cross_commit_aggregate_full(
         &commits,
         &proofs,
@@ -194,7 +194,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[0].ciphersuite = 0;
 
-    commits[1].ciphersuite = 1;
+    commits[1].ciphersuite = 255;
     assert!(Proof::cross_commit_aggregate_full(
         &commits,
         &proofs,
@@ -205,7 +205,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[1].ciphersuite = 0;
 
-    proofs[0][0].ciphersuite = 1;
+    proofs[0][0].ciphersuite = 255;
     assert!(Proof::cross_commit_aggregate_full(
         &commits,
         &proofs,
@@ -236,7 +236,7 @@ fn negative_test_cross_commit_aggregation() {
     )
     .unwrap();
 
-    commits[0].ciphersuite = 1;
+    commits[0].ciphersuite = 255;
     assert!(Proof::cross_commit_aggregate_partial(
         &commits,
         &same_commit_proof,
@@ -247,7 +247,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[0].ciphersuite = 0;
 
-    commits[1].ciphersuite = 1;
+    commits[1].ciphersuite = 255;
     assert!(Proof::cross_commit_aggregate_partial(
         &commits,
         &same_commit_proof,
@@ -258,7 +258,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[1].ciphersuite = 0;
 
-    same_commit_proof[0].ciphersuite = 1;
+    same_commit_proof[0].ciphersuite = 255;
     assert!(Proof::cross_commit_aggregate_partial(
         &commits,
         &same_commit_proof,
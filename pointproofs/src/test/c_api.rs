@@ -95,6 +95,112 @@ fn test_c_api_basic() {
     }
 }
 
+#[test]
+fn test_c_api_batch_update() {
+    let n = 16;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let init_values: Vec<Vec<u8>> = (0..n)
+        .map(|i| format!("this is message number {}", i).into_bytes())
+        .collect();
+    let values: Vec<pointproofs_value> = init_values
+        .iter()
+        .map(|e| pointproofs_value {
+            data: e.as_ptr(),
+            len: e.len(),
+        })
+        .collect();
+    // values[i] is changed into values[n - 1 - i] for i in changed_idx
+    let changed_idx = [1usize, 4, 9];
+    let val_old: Vec<pointproofs_value> = changed_idx.iter().map(|i| values[*i].clone()).collect();
+    let val_new: Vec<pointproofs_value> = changed_idx
+        .iter()
+        .map(|i| values[n - 1 - *i].clone())
+        .collect();
+    let mut new_values = values.clone();
+    for i in changed_idx.iter() {
+        new_values[*i] = values[n - 1 - *i].clone();
+    }
+
+    let mut param = pointproofs_params::default();
+    let mut com = pointproofs_commitment::default();
+    let mut proofs = vec![pointproofs_proof::default(); 2];
+    let mut new_proof = pointproofs_proof::default();
+    let mut new_proofs = vec![pointproofs_proof::default(); 2];
+    unsafe {
+        assert!(pointproofs_paramgen(seed.as_ptr(), seed.len(), 0, n, &mut param) == 0);
+        assert!(pointproofs_commit(param.prover.clone(), new_values.as_ptr(), n, &mut com) == 0);
+        for (k, idx) in [0usize, 4].iter().enumerate() {
+            assert!(
+                pointproofs_prove(
+                    param.prover.clone(),
+                    values.as_ptr(),
+                    n,
+                    *idx,
+                    &mut proofs[k]
+                ) == 0
+            );
+        }
+
+        assert!(
+            pointproofs_proof_batch_update(
+                param.prover.clone(),
+                proofs[0].clone(),
+                0,
+                changed_idx.as_ptr(),
+                val_old.as_ptr(),
+                val_new.as_ptr(),
+                changed_idx.len(),
+                &mut new_proof,
+            ) == 0
+        );
+        assert!(pointproofs_verify(
+            param.verifier.clone(),
+            com.clone(),
+            new_proof.clone(),
+            new_values[0].clone(),
+            0
+        ));
+
+        assert!(
+            pointproofs_update_many_proofs(
+                param.prover.clone(),
+                proofs.as_ptr(),
+                [0usize, 4].as_ptr(),
+                2,
+                changed_idx.as_ptr(),
+                val_old.as_ptr(),
+                val_new.as_ptr(),
+                changed_idx.len(),
+                new_proofs.as_mut_ptr(),
+            ) == 0
+        );
+        for (k, idx) in [0usize, 4].iter().enumerate() {
+            assert!(pointproofs_verify(
+                param.verifier.clone(),
+                com.clone(),
+                new_proofs[k].clone(),
+                new_values[*idx].clone(),
+                *idx
+            ));
+        }
+
+        // duplicated indices
+        assert_eq!(
+            pointproofs_proof_batch_update(
+                param.prover.clone(),
+                proofs[0].clone(),
+                0,
+                [1usize, 1].as_ptr(),
+                val_old.as_ptr(),
+                val_new.as_ptr(),
+                2,
+                &mut new_proof,
+            ),
+            POINTPROOFS_ERR_DUPLICATED_INDEX
+        );
+    }
+}
+
 #[test]
 fn test_c_api_aggregate() {
     let n = 1024;
@@ -291,3 +397,59 @@ fn test_c_api_aggregate() {
         ));
     }
 }
+
+#[test]
+fn negative_test_c_api_error_codes() {
+    let n = 8;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let init_values: Vec<Vec<u8>> = (0..n)
+        .map(|i| format!("this is message number {}", i).into_bytes())
+        .collect();
+    let values: Vec<pointproofs_value> = init_values
+        .iter()
+        .map(|e| pointproofs_value {
+            data: e.as_ptr(),
+            len: e.len(),
+        })
+        .collect();
+    let mut param = pointproofs_params::default();
+    let mut com = pointproofs_commitment::default();
+    let mut proof = pointproofs_proof::default();
+    unsafe {
+        assert_eq!(
+            pointproofs_paramgen(seed.as_ptr(), 16, 0, n, &mut param),
+            POINTPROOFS_ERR_SEED_TOO_SHORT
+        );
+        assert_eq!(
+            pointproofs_paramgen(seed.as_ptr(), seed.len(), 255, n, &mut param),
+            POINTPROOFS_ERR_CIPHERSUITE
+        );
+        assert_eq!(
+            pointproofs_paramgen(seed.as_ptr(), seed.len(), 0, 65537, &mut param),
+            POINTPROOFS_ERR_MAX_N
+        );
+        assert_eq!(
+            pointproofs_paramgen(seed.as_ptr(), seed.len(), 0, n, &mut param),
+            0
+        );
+
+        assert_eq!(
+            pointproofs_commit(param.prover.clone(), values.as_ptr(), n - 1, &mut com),
+            POINTPROOFS_ERR_INVALID_VALUE
+        );
+        assert_eq!(
+            pointproofs_prove(param.prover.clone(), values.as_ptr(), n, n, &mut proof),
+            POINTPROOFS_ERR_INVALID_INDEX
+        );
+
+        let mut invalid_bytes = vec![0u8; COMMIT_LEN];
+        let invalid_commit = pointproofs_commitment_bytes {
+            data: invalid_bytes.as_mut_ptr(),
+            len: invalid_bytes.len(),
+        };
+        assert_eq!(
+            pointproofs_commit_deserial(invalid_commit, &mut com),
+            POINTPROOFS_ERR_SERIALIZATION
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use pairing::serdes::SerDes;
+use pairings::ceremony::*;
+use pairings::param::validate;
+use pairings::*;
+
+#[test]
+fn test_ceremony() {
+    let n = 8usize;
+    let mut params = vec![init(0, n).unwrap()];
+    let mut proofs: Vec<UpdateProof> = vec![];
+    for i in 0..3 {
+        let entropy = format!("This is participant {}'s very very very long entropy", i);
+        let (pp, vp, proof) = contribute(
+            &params.last().unwrap().0,
+            &params.last().unwrap().1,
+            entropy.as_ref(),
+        )
+        .unwrap();
+        assert!(validate(&pp, &vp).is_ok());
+        assert!(pp.check_parameters(&vp));
+        params.push((pp, vp));
+        proofs.push(proof);
+    }
+    assert!(verify_transcript(&params, &proofs).is_ok());
+
+    // the final parameters work
+    let (pp, vp) = params.last().unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(pp, &values).unwrap();
+    let proof = Proof::new(pp, &values, 3).unwrap();
+    assert!(proof.verify(vp, &com, &values[3], 3));
+
+    // proofs can be published
+    for proof in proofs.iter() {
+        let mut buf: Vec<u8> = vec![];
+        proof.serialize(&mut buf, true).unwrap();
+        let proof_recover = UpdateProof::deserialize(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(*proof, proof_recover);
+    }
+}
+
+#[test]
+fn negative_test_ceremony() {
+    let n = 8usize;
+    let (pp0, vp0) = init(0, n).unwrap();
+    assert!(init(255, n).is_err());
+    assert!(init(0, 0).is_err());
+    assert!(contribute(&pp0, &vp0, b"too short").is_err());
+
+    let entropy = b"This is participant 0's very very very long entropy";
+    let (pp1, vp1, proof1) = contribute(&pp0, &vp0, entropy).unwrap();
+    let (pp2, vp2, proof2) = contribute(&pp1, &vp1, entropy).unwrap();
+    let params = vec![
+        (pp0.clone(), vp0.clone()),
+        (pp1.clone(), vp1.clone()),
+        (pp2.clone(), vp2.clone()),
+    ];
+    assert!(verify_transcript(&params, &[proof1.clone(), proof2.clone()]).is_ok());
+
+    // missing, swapped or replayed proofs
+    assert!(verify_transcript(&params, &[proof1.clone()]).is_err());
+    assert!(verify_transcript(&params[..1], &[]).is_err());
+    assert!(verify_transcript(&params, &[proof2.clone(), proof1.clone()]).is_err());
+    assert!(verify_contribution(&pp1, &vp1, &pp2, &vp2, &proof1).is_err());
+
+    // skipped steps
+    assert!(verify_contribution(&pp0, &vp0, &pp2, &vp2, &proof2).is_err());
+    assert!(verify_transcript(&params[1..], &[proof2.clone()]).is_err());
+
+    // parameters for a known alpha, with a proof of another contribution
+    let (pp, vp) =
+        param::paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n)
+            .unwrap();
+    assert!(verify_contribution(&pp1, &vp1, &pp, &vp, &proof2).is_err());
+
+    // a contribution from other parameters
+    let (pp2_other, vp2_other, proof2_other) = contribute(&pp, &vp, entropy).unwrap();
+    assert!(verify_contribution(&pp1, &vp1, &pp2_other, &vp2_other, &proof2_other).is_err());
+
+    // a tampered proof
+    let mut buf: Vec<u8> = vec![];
+    proof2.serialize(&mut buf, true).unwrap();
+    let last = buf.len() - 1;
+    buf[last] ^= 1;
+    let tampered = UpdateProof::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert!(verify_contribution(&pp1, &vp1, &pp2, &vp2, &tampered).is_err());
+}
@@ -0,0 +1,186 @@
+use pairings::ciphersuite::*;
+use pairings::param::{check_ciphersuite, paramgen_from_seed};
+use pairings::{Commitment, Proof, ProverParams, VerifierParams};
+
+// commits to 4 vectors, proves 3 values of each, and verifies the proofs, their same commit
+// aggregation and their cross commit aggregation
+fn commit_prove_verify(prover_params: &ProverParams, verifier_params: &VerifierParams) {
+    let n = prover_params.n;
+    let set = vec![1usize, 4, 7];
+    let mut commits: Vec<Commitment> = vec![];
+    let mut proofs: Vec<Vec<Proof>> = vec![];
+    let mut value_sub_vector: Vec<Vec<Vec<u8>>> = vec![];
+    let mut same_commit_proof: Vec<Proof> = vec![];
+    for j in 0..4 {
+        let values: Vec<Vec<u8>> = (0..n)
+            .map(|i| format!("this is message number {} for commit {}", i, j).into_bytes())
+            .collect();
+        let com = Commitment::new(prover_params, &values).unwrap();
+        let mut tmp_proofs: Vec<Proof> = vec![];
+        let mut tmp_value_sub_vector: Vec<Vec<u8>> = vec![];
+        for index in &set {
+            let proof = Proof::new(prover_params, &values, *index).unwrap();
+            assert!(proof.verify(verifier_params, &com, &values[*index], *index));
+            assert!(!proof.verify(verifier_params, &com, &values[0], *index));
+            tmp_proofs.push(proof);
+            tmp_value_sub_vector.push(values[*index].clone());
+        }
+        let agg_proof =
+            Proof::same_commit_aggregate(&com, &tmp_proofs, &set, &tmp_value_sub_vector, n)
+                .unwrap();
+        assert!(agg_proof.same_commit_batch_verify(
+            verifier_params,
+            &com,
+            &set,
+            &tmp_value_sub_vector
+        ));
+        same_commit_proof.push(agg_proof);
+        commits.push(com);
+        proofs.push(tmp_proofs);
+        value_sub_vector.push(tmp_value_sub_vector);
+    }
+    let sets = vec![set; 4];
+    let agg_proof =
+        Proof::cross_commit_aggregate_full(&commits, &proofs, &sets, &value_sub_vector, n).unwrap();
+    assert!(agg_proof.cross_commit_batch_verify(
+        verifier_params,
+        &commits,
+        &sets,
+        &value_sub_vector
+    ));
+    let agg_proof2 = Proof::cross_commit_aggregate_partial(
+        &commits,
+        &same_commit_proof,
+        &sets,
+        &value_sub_vector,
+        n,
+    )
+    .unwrap();
+    assert_eq!(agg_proof, agg_proof2);
+}
+
+#[test]
+fn test_ciphersuite_1() {
+    let n = 8usize;
+    let (pp0, vp0) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let (pp1, vp1) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 1, n).unwrap();
+    assert!(check_ciphersuite(1));
+    commit_prove_verify(&pp1, &vp1);
+
+    // the parameters only differ by their ciphersuite
+    assert_eq!(pp0.with_ciphersuite(1).unwrap(), pp1);
+    assert_eq!(vp0.with_ciphersuite(1).unwrap(), vp1);
+    assert!(pp0.with_ciphersuite(255).is_err());
+    assert!(vp0.with_ciphersuite(255).is_err());
+
+    // the values are hashed differently
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com0 = Commitment::new(&pp0, &values).unwrap();
+    let com1 = Commitment::new(&pp1, &values).unwrap();
+    assert_ne!(com0.commit, com1.commit);
+}
+
+#[test]
+fn negative_test_mixed_ciphersuites() {
+    let n = 8usize;
+    let (pp0, vp0) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let (pp1, vp1) = (
+        pp0.with_ciphersuite(1).unwrap(),
+        vp0.with_ciphersuite(1).unwrap(),
+    );
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com0 = Commitment::new(&pp0, &values).unwrap();
+    let com1 = Commitment::new(&pp1, &values).unwrap();
+    let proof0 = Proof::new(&pp0, &values, 1).unwrap();
+    let proof1 = Proof::new(&pp1, &values, 1).unwrap();
+
+    // commitments, proofs and parameters of different ciphersuites
+    let mut com = com0.clone();
+    assert!(com.update(&pp1, 1, &values[1], &values[2]).is_err());
+    assert!(!proof1.verify(&vp0, &com1, &values[1], 1));
+    assert!(!proof1.verify(&vp1, &com0, &values[1], 1));
+    assert!(!proof0.verify(&vp1, &com0, &values[1], 1));
+
+    // aggregation of commitments with different ciphersuites
+    let sets = vec![vec![1usize]; 2];
+    let value_sub_vector = vec![vec![values[1].clone()]; 2];
+    assert!(Proof::cross_commit_aggregate_full(
+        &[com0.clone(), com1.clone()],
+        &[vec![proof0.clone()], vec![proof1.clone()]],
+        &sets,
+        &value_sub_vector,
+        n,
+    )
+    .is_err());
+    assert!(Proof::cross_commit_aggregate_partial(
+        &[com0.clone(), com1.clone()],
+        &[proof0.clone(), proof1.clone()],
+        &sets,
+        &value_sub_vector,
+        n,
+    )
+    .is_err());
+    let agg_proof = Proof::cross_commit_aggregate_full(
+        &[com0.clone(), com0.clone()],
+        &[vec![proof0.clone()], vec![proof0.clone()]],
+        &sets,
+        &value_sub_vector,
+        n,
+    )
+    .unwrap();
+    assert!(agg_proof.cross_commit_batch_verify(
+        &vp0,
+        &[com0.clone(), com0.clone()],
+        &sets,
+        &value_sub_vector
+    ));
+    assert!(!agg_proof.cross_commit_batch_verify(&vp0, &[com0, com1], &sets, &value_sub_vector));
+}
+
+#[test]
+fn test_register_ciphersuite() {
+    let n = 8usize;
+    let (pp0, vp0) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    assert!(!check_ciphersuite(7));
+    assert!(pp0.with_ciphersuite(7).is_err());
+
+    let def = CiphersuiteDef {
+        value_hash: HashToField::XmdSha256(b"MY-APP-V01-VALUE_XMD:SHA-256_".to_vec()),
+        aggregation_hash: HashToField::XmdSha256(b"MY-APP-V01-AGGREGATION_XMD:SHA-256_".to_vec()),
+    };
+    assert!(register_ciphersuite(7, def.clone()).is_ok());
+    assert!(check_ciphersuite(7));
+    assert_eq!(get_ciphersuite(7), Some(def.clone()));
+    commit_prove_verify(
+        &pp0.with_ciphersuite(7).unwrap(),
+        &vp0.with_ciphersuite(7).unwrap(),
+    );
+
+    // the values are hashed with the registered tag
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com1 = Commitment::new(&pp0.with_ciphersuite(1).unwrap(), &values).unwrap();
+    let com7 = Commitment::new(&pp0.with_ciphersuite(7).unwrap(), &values).unwrap();
+    assert_ne!(com1.commit, com7.commit);
+
+    // IDs cannot be reused, and the tags shall have 1 to 255 bytes
+    assert!(register_ciphersuite(0, def.clone()).is_err());
+    assert!(register_ciphersuite(1, def.clone()).is_err());
+    assert!(register_ciphersuite(7, def.clone()).is_err());
+    let mut invalid_def = def.clone();
+    invalid_def.value_hash = HashToField::XmdSha256(vec![]);
+    assert!(register_ciphersuite(8, invalid_def).is_err());
+    let mut invalid_def = def;
+    invalid_def.aggregation_hash = HashToField::XmdSha256(vec![0x41; 256]);
+    assert!(register_ciphersuite(8, invalid_def).is_err());
+    assert!(!check_ciphersuite(8));
+}
@@ -8,7 +8,7 @@ fn negative_test_commit() {
     let (prover_params, _verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut prover_params2 = prover_params.clone();
-    prover_params2.ciphersuite = 1;
+    prover_params2.ciphersuite = 255;
 
     let mut init_values = Vec::with_capacity(n);
     for i in 0..n {
@@ -42,7 +42,7 @@ fn negative_test_commit() {
         .update(&prover_params2, 0, &values[0], &new_values[0][..].as_ref())
         .is_err());
 
-    com.ciphersuite = 1;
+    com.ciphersuite = 255;
     assert!(com
         .update(&prover_params2, 0, &values[0], &new_values[0][..].as_ref())
         .is_err());
@@ -69,7 +69,7 @@ fn negative_test_commit_batch_update() {
 
     let mut com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 255;
 
     // update values
     let mut new_init_values = Vec::with_capacity(n);
@@ -94,7 +94,7 @@ fn negative_test_commit_batch_update() {
         .is_err());
 
     let mut pp2 = prover_params.clone();
-    pp2.ciphersuite = 1;
+    pp2.ciphersuite = 255;
     assert!(com2
         .batch_update(&pp2, &indices, &value_before, &value_after)
         .is_err());
@@ -470,3 +470,76 @@ fn test_commit_full() {
         }
     }
 }
+
+// checks that every update path, with every pre-computation,
+// gives the commitment to the new vector
+fn check_update_paths(
+    params: &[ProverParams],
+    values: &[Vec<u8>],
+    new_values: &[Vec<u8>],
+    indices: &[usize],
+) {
+    let mut updated_values = values.to_vec();
+    for i in indices {
+        updated_values[*i] = new_values[*i].clone();
+    }
+    let value_before: Vec<&[u8]> = indices.iter().map(|i| values[*i].as_ref()).collect();
+    let value_after: Vec<&[u8]> = indices.iter().map(|i| new_values[*i].as_ref()).collect();
+
+    let expected = Commitment::new(&params[0], &updated_values).unwrap();
+    for pp in params {
+        let com = Commitment::new(pp, values).unwrap();
+        assert_eq!(Commitment::new(pp, &updated_values).unwrap(), expected);
+
+        let mut com_batch = com.clone();
+        com_batch
+            .batch_update(pp, indices, &value_before, &value_after)
+            .unwrap();
+        assert_eq!(
+            com_batch, expected,
+            "batch_update with indices {:?}",
+            indices
+        );
+
+        let mut com_serial = com;
+        for (k, i) in indices.iter().enumerate() {
+            com_serial
+                .update(pp, *i, value_before[k], value_after[k])
+                .unwrap();
+        }
+        assert_eq!(com_serial, expected, "update with indices {:?}", indices);
+    }
+}
+
+#[test]
+fn test_commit_update_differential() {
+    let n = 16usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp3 = prover_params.clone();
+    pp3.precomp_3();
+    let mut pp256 = prover_params.clone();
+    pp256.precomp_256();
+    let params = [prover_params, pp3, pp256];
+
+    let values: Vec<Vec<u8>> = (0..n)
+        .map(|i| format!("this is message number {}", i).into_bytes())
+        .collect();
+    let new_values: Vec<Vec<u8>> = (0..n)
+        .map(|i| format!("new string {}", i).into_bytes())
+        .collect();
+
+    // the indices are not sorted, and do not match their positions,
+    // so that a position used as an index would select the wrong basis
+    let index_sets: Vec<Vec<usize>> = vec![
+        vec![],
+        vec![0],
+        vec![n - 1],
+        vec![7, 2, 11],
+        vec![15, 0, 8, 3, 12, 6],
+        (1..n).rev().collect(),
+    ];
+    for indices in index_sets.iter() {
+        check_update_paths(&params, &values, &new_values, indices);
+    }
+}
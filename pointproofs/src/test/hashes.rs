@@ -1,6 +1,8 @@
 use ff::{Field, PrimeField};
 use pairing::bls12_381::*;
-use pairings::hash_to_field_pointproofs::os2ip_mod_p;
+use pairings::hash_to_field_pointproofs::{
+    expand_message_xmd_sha256, hash_to_field_repr_xmd_sha256, os2ip_mod_p,
+};
 
 // examples from
 // https://crypto.stackexchange.com/questions/37537/what-are-i2osp-os2ip-in-rsa-pkcs1
@@ -34,3 +36,45 @@ fn test_os2ip() {
 
     assert_eq!(Fr::from_repr(FrRepr([1, 0, 0, 0])).unwrap(), Fr::one());
 }
+
+fn from_hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+// examples from
+// https://www.rfc-editor.org/rfc/rfc9380.html#appendix-K.1
+#[test]
+fn test_expand_message_xmd_sha256() {
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    assert_eq!(
+        expand_message_xmd_sha256(b"", dst, 0x20),
+        from_hex("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+    );
+    assert_eq!(
+        expand_message_xmd_sha256(b"abc", dst, 0x20),
+        from_hex("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")
+    );
+    assert_eq!(
+        expand_message_xmd_sha256(b"", dst, 0x80),
+        from_hex(
+            "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe\
+             e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18\
+             eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+             c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"
+        )
+    );
+}
+
+#[test]
+fn test_hash_to_field_xmd_sha256() {
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    // the output is the 48 bytes of expand_message_xmd, reduced modulo r
+    let t = hash_to_field_repr_xmd_sha256(b"abc", dst);
+    assert_eq!(t, os2ip_mod_p(&expand_message_xmd_sha256(b"abc", dst, 48)));
+    assert!(Fr::from_repr(t).is_ok());
+    // and depends on the domain separation tag
+    assert_ne!(t, hash_to_field_repr_xmd_sha256(b"abc", b"another tag"));
+}
@@ -1,7 +1,14 @@
 mod aggregate;
 mod c_api;
+mod ceremony;
+mod ciphersuite;
 mod commit;
 mod hashes;
+mod msm;
 mod param;
+mod param_store;
+mod placement;
 mod prove;
+#[cfg(feature = "serde")]
+mod serde_impl;
 mod serdes;
@@ -0,0 +1,75 @@
+use ff::{Field, PrimeField};
+use pairing::bls12_381::Fr;
+use pairing::{CurveAffine, CurveProjective};
+use pairings::hash_to_field_pointproofs::hash_to_field_repr_pointproofs;
+use pairings::msm::*;
+use pairings::param::paramgen_from_seed;
+use pairings::pointproofs_groups::*;
+use pairings::*;
+
+#[test]
+fn test_parallel_sum_of_products() {
+    for n in [1usize, 5, 33, 300].iter() {
+        let n = *n;
+        let mut bases: Vec<PointproofsG1Affine> = Vec::with_capacity(n);
+        let mut scalars: Vec<[u64; 4]> = Vec::with_capacity(n);
+        for i in 0..n {
+            let mut base = PointproofsG1::one();
+            base.mul_assign(hash_to_field_repr_pointproofs(format!(
+                "this is base number {}",
+                i
+            )));
+            bases.push(base.into_affine());
+            scalars.push(hash_to_field_repr_pointproofs(format!("this is message number {}", i)).0);
+        }
+        // edge cases: 0, 1 and r - 1
+        let mut minus_one = Fr::zero();
+        minus_one.sub_assign(&Fr::one());
+        scalars[0] = minus_one.into_repr().0;
+        if n > 2 {
+            scalars[1] = [0u64; 4];
+            scalars[2] = [1u64, 0, 0, 0];
+        }
+        let scalars_u64: Vec<&[u64; 4]> = scalars.iter().collect();
+
+        let expected = PointproofsG1Affine::sum_of_products(&bases, &scalars_u64);
+        assert_eq!(parallel_sum_of_products(&bases, &scalars_u64), expected);
+        for c in [1usize, 2, 3, 7, 11, 16].iter() {
+            assert_eq!(
+                parallel_sum_of_products_with_window(&bases, &scalars_u64, *c),
+                expected,
+                "window size {} failed",
+                c
+            );
+        }
+    }
+}
+
+#[test]
+fn test_window_size() {
+    assert_eq!(window_size(1), 3);
+    assert_eq!(window_size(1024), 9);
+    for n in 1..10000 {
+        assert!(window_size(n) <= window_size(n + 1));
+    }
+}
+
+#[test]
+fn test_parallel_commit_and_prove() {
+    // large enough for commit and prove to go through the parallel multi-exponentiation
+    let n = PARALLEL_MSM_THRESHOLD;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    for i in [0usize, 1, n / 2, n - 1].iter() {
+        let proof = Proof::new(&prover_params, &values, *i).unwrap();
+        assert!(proof.verify(&verifier_params, &com, &values[*i], *i));
+        assert!(!proof.verify(&verifier_params, &com, &values[(*i + 1) % n], *i));
+    }
+}
@@ -1,4 +1,6 @@
-use pairings::param::paramgen_from_seed;
+use pairing::serdes::SerDes;
+use pairings::param::{paramgen_from_seed, read_param, validate};
+use pairings::*;
 
 #[test]
 fn test_param() {
@@ -26,22 +28,29 @@ fn test_param() {
 #[test]
 fn negative_test_param() {
     let n = 16;
-    assert!(paramgen_from_seed("seed too short", 0, n).is_err());
+    assert!(matches!(
+        paramgen_from_seed("seed too short", 0, n),
+        Err(PointproofsError::SeedTooShort)
+    ));
 
-    assert!(paramgen_from_seed(
-        "This is Leo's Second Favourite very very very long Seed",
-        1,
-        n
-    )
-    .is_err());
+    assert!(matches!(
+        paramgen_from_seed(
+            "This is Leo's Second Favourite very very very long Seed",
+            255,
+            n
+        ),
+        Err(PointproofsError::InvalidCiphersuite)
+    ));
 
     let n = 65537;
-    assert!(paramgen_from_seed(
-        "This is Leo's Second Favourite very very very long Seed",
-        0,
-        n
-    )
-    .is_err());
+    assert!(matches!(
+        paramgen_from_seed(
+            "This is Leo's Second Favourite very very very long Seed",
+            0,
+            n
+        ),
+        Err(PointproofsError::MaxN)
+    ));
 
     let n = 16;
     let (pp1, vp1) =
@@ -76,3 +85,159 @@ fn test_param_slow() {
         assert!(!pp2.check_parameters(&vp1));
     }
 }
+
+#[test]
+fn test_restrict_param() {
+    let n = 16;
+    let (pp, vp) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp3 = pp.clone();
+    pp3.precomp_3();
+    let mut pp256 = pp.clone();
+    pp256.precomp_256();
+    let mut vp3 = vp.clone();
+    vp3.precomp_3();
+
+    for m in [1usize, 2, 5, 8, 16].iter() {
+        let pp_m = pp.restrict(*m).unwrap();
+        let vp_m = vp.restrict(*m).unwrap();
+        assert!(pp_m.check_parameters(&vp_m));
+
+        // same alpha, same parameters
+        let (mut expected_pp, mut expected_vp) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *m).unwrap();
+        assert_eq!(pp_m, expected_pp);
+        assert_eq!(vp_m, expected_vp);
+
+        expected_pp.precomp_3();
+        assert_eq!(pp3.restrict(*m).unwrap(), expected_pp);
+        expected_pp.precomp_256();
+        assert_eq!(pp256.restrict(*m).unwrap(), expected_pp);
+        expected_vp.precomp_3();
+        let vp3_m = vp3.restrict(*m).unwrap();
+        assert_eq!(vp3_m, expected_vp);
+    }
+
+    // a ceremony output
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let (pp, vp) = read_param(&mut f).unwrap();
+    let n = pp.n;
+    for m in 1..=n / 2 {
+        assert!(pp
+            .restrict(m)
+            .unwrap()
+            .check_parameters(&vp.restrict(m).unwrap()));
+    }
+    assert!(pp
+        .restrict(n)
+        .unwrap()
+        .check_parameters(&vp.restrict(n).unwrap()));
+}
+
+#[test]
+fn negative_test_restrict_param() {
+    let n = 16;
+    let (pp, vp) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    // g1^{alpha^{n+1}} would be needed for n/2 < m < n
+    for m in [0usize, 9, 15, 17].iter() {
+        assert!(pp.restrict(*m).is_err());
+        assert!(vp.restrict(*m).is_err());
+    }
+
+    // parameters for different dimensions do not match
+    assert!(!pp
+        .restrict(4)
+        .unwrap()
+        .check_parameters(&vp.restrict(8).unwrap()));
+}
+
+#[test]
+fn test_validate_param() {
+    for n in [1usize, 2, 16].iter() {
+        let (mut pp, mut vp) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *n).unwrap();
+        assert!(validate(&pp, &vp).is_ok());
+        pp.precomp_256();
+        vp.precomp_3();
+        assert!(validate(&pp, &vp).is_ok());
+    }
+
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let (pp, vp) = read_param(&mut f).unwrap();
+    assert!(validate(&pp, &vp).is_ok());
+    assert!(validate(&pp.restrict(1).unwrap(), &vp.restrict(1).unwrap()).is_ok());
+}
+
+// swaps the elements i and j of size len, which start at offset in bytes
+fn swap(bytes: &[u8], offset: usize, len: usize, i: usize, j: usize) -> Vec<u8> {
+    let mut res = bytes.to_vec();
+    for k in 0..len {
+        res.swap(offset + i * len + k, offset + j * len + k);
+    }
+    res
+}
+
+#[test]
+fn negative_test_validate_param() {
+    let n = 16;
+    let (pp, vp) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let (pp2, vp2) = paramgen_from_seed(
+        "This is Leo's Second Favourite very very very long Seed",
+        0,
+        n,
+    )
+    .unwrap();
+
+    // parameters for another alpha or another n
+    assert!(validate(&pp, &vp2).is_err());
+    assert!(validate(&pp2, &vp).is_err());
+    assert!(validate(&pp.restrict(8).unwrap(), &vp).is_err());
+
+    // `|ciphersuite id | n | generators | pp_len | [pre_compute]`
+    let mut pp_bytes: Vec<u8> = vec![];
+    pp.serialize(&mut pp_bytes, true).unwrap();
+    let g1_len = (pp_bytes.len() - 9) / (2 * n);
+    for (i, j) in [
+        (1, 2),
+        (0, 2 * n - 1),
+        (n - 1, n),
+        (n, n + 1),
+        (n + 1, n + 2),
+    ]
+    .iter()
+    {
+        let bytes = swap(&pp_bytes, 5, g1_len, *i, *j);
+        let tampered = ProverParams::deserialize(&mut bytes[..].as_ref(), true).unwrap();
+        assert!(validate(&tampered, &vp).is_err(), "swap {} {}", i, j);
+    }
+
+    // `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element`
+    let mut vp_bytes: Vec<u8> = vec![];
+    vp.serialize(&mut vp_bytes, true).unwrap();
+    let mut vp2_bytes: Vec<u8> = vec![];
+    vp2.serialize(&mut vp2_bytes, true).unwrap();
+    let gt_len = 576;
+    let g2_len = (vp_bytes.len() - 9 - gt_len) / n;
+    for (i, j) in [(0, 1), (n - 2, n - 1), (3, 7)].iter() {
+        let bytes = swap(&vp_bytes, 5, g2_len, *i, *j);
+        let tampered = VerifierParams::deserialize(&mut bytes[..].as_ref(), true).unwrap();
+        assert!(validate(&pp, &tampered).is_err(), "swap {} {}", i, j);
+    }
+    let gt_start = vp_bytes.len() - gt_len;
+    let bytes = [&vp_bytes[..gt_start], &vp2_bytes[gt_start..]].concat();
+    let tampered = VerifierParams::deserialize(&mut bytes[..].as_ref(), true).unwrap();
+    assert!(validate(&pp, &tampered).is_err());
+
+    // a wrong pre-computation
+    let mut pp3 = pp.clone();
+    pp3.precomp_3();
+    let mut pp3_bytes: Vec<u8> = vec![];
+    pp3.serialize(&mut pp3_bytes, true).unwrap();
+    let precomp_start = 9 + 2 * n * g1_len;
+    let bytes = swap(&pp3_bytes, precomp_start, g1_len, 1, 4);
+    let tampered = ProverParams::deserialize(&mut bytes[..].as_ref(), true).unwrap();
+    assert!(validate(&tampered, &vp).is_err());
+}
@@ -0,0 +1,114 @@
+use pairings::err::PointproofsError;
+use pairings::param::{read_param, read_param_in};
+use pairings::param_store::*;
+use pairings::pointproofs_groups::*;
+use std::path::PathBuf;
+
+fn store_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("pointproofs_{}_{}.store", name, std::process::id()))
+}
+
+#[test]
+fn test_param_store() {
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let (prover_params, verifier_params) = read_param(&mut f).unwrap();
+    let mut prover_params256 = prover_params.clone();
+    prover_params256.precomp_256();
+    let mut prover_params3 = prover_params.clone();
+    prover_params3.precomp_3();
+
+    let path = store_path("test_param_store");
+    let store = ParamStore::new(&path);
+    assert!(store.load().is_err());
+
+    // first run builds the store, later runs load it
+    let (pp, vp) = store
+        .load_or_build("crs.param", PrecompKind::Precomp256)
+        .unwrap();
+    assert_eq!(pp, prover_params256);
+    assert_eq!(vp, verifier_params);
+    assert_eq!(store.load().unwrap(), (pp.clone(), vp.clone()));
+    assert_eq!(
+        store
+            .load_or_build("crs.param", PrecompKind::Precomp256)
+            .unwrap(),
+        (pp, vp)
+    );
+
+    // another pre-computation replaces the store
+    let (pp, _) = store
+        .load_or_build("crs.param", PrecompKind::Precomp3)
+        .unwrap();
+    assert_eq!(pp, prover_params3);
+    assert_eq!(store.load().unwrap().0, prover_params3);
+
+    let (pp, vp) = store.build("crs.param", PrecompKind::NoPrecomp).unwrap();
+    assert_eq!(pp, prover_params);
+    assert_eq!(store.load().unwrap(), (pp, vp));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn negative_test_param_store() {
+    let path = store_path("negative_test_param_store");
+    let store = ParamStore::new(&path);
+    assert!(store
+        .load_or_build("no such crs.param", PrecompKind::Precomp3)
+        .is_err());
+    let (prover_params, verifier_params) = store.build("crs.param", PrecompKind::Precomp3).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+
+    // a flipped bit in the body
+    let mut corrupted = bytes.clone();
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 1;
+    std::fs::write(&path, &corrupted).unwrap();
+    assert!(store.load().is_err());
+
+    // a flipped bit in the header
+    let mut corrupted = bytes.clone();
+    corrupted[0] ^= 1;
+    std::fs::write(&path, &corrupted).unwrap();
+    assert!(store.load().is_err());
+
+    // a truncated or extended store
+    std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    assert!(store.load().is_err());
+    std::fs::write(&path, [&bytes[..], &[0u8]].concat()).unwrap();
+    assert!(store.load().is_err());
+
+    // an invalid store is built again
+    assert_eq!(
+        store
+            .load_or_build("crs.param", PrecompKind::Precomp3)
+            .unwrap(),
+        (prover_params, verifier_params)
+    );
+    assert_eq!(std::fs::read(&path).unwrap(), bytes);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_param_store_placements() {
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let (prover_params, verifier_params) = read_param_in::<G2Placement, _>(&mut f).unwrap();
+
+    let path = store_path("test_param_store_placements");
+    let store = ParamStoreIn::<G2Placement>::new(&path);
+    let (pp, vp) = store.build("crs.param", PrecompKind::NoPrecomp).unwrap();
+    assert_eq!(pp, prover_params);
+    assert_eq!(store.load().unwrap(), (pp, vp.clone()));
+    assert_eq!(vp, verifier_params);
+
+    // the header holds the ciphersuite id with the tag of the placement
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes[8], prover_params.ciphersuite | G2Placement::TAG);
+    match ParamStoreIn::<G1Placement>::new(&path).load() {
+        Err(PointproofsError::Placement) => (),
+        _ => panic!("a G2Placement store was loaded as G1Placement"),
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,123 @@
+use pairing::serdes::SerDes;
+use pairings::ceremony::*;
+use pairings::param::paramgen_from_seed_in;
+use pairings::pointproofs_groups::*;
+use pairings::*;
+
+fn test_placement_helper<P: GroupPlacement>() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed_in::<P, _>("This is Leo's Favourite very very very long Seed", 0, n)
+            .unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = CommitmentIn::new(&prover_params, &values).unwrap();
+    let set = [1usize, 4, 7];
+    let mut proofs: Vec<ProofIn<P>> = vec![];
+    let mut value_sub_vector: Vec<String> = vec![];
+    for index in set.iter() {
+        let proof = ProofIn::new(&prover_params, &values, *index).unwrap();
+        assert!(proof.verify(&verifier_params, &com, &values[*index], *index));
+        assert!(!proof.verify(&verifier_params, &com, &values[*index + 1], *index));
+        proofs.push(proof);
+        value_sub_vector.push(values[*index].clone());
+    }
+    let agg_proof =
+        ProofIn::same_commit_aggregate(&com, &proofs, &set, &value_sub_vector, n).unwrap();
+    assert!(agg_proof.same_commit_batch_verify(&verifier_params, &com, &set, &value_sub_vector));
+
+    // the serialized objects are tagged with the placement
+    let mut buf: Vec<u8> = vec![];
+    assert!(com.serialize(&mut buf, true).is_ok());
+    assert_eq!(buf.len(), P::COMMIT_LEN);
+    assert_eq!(buf[0], P::TAG);
+    assert_eq!(
+        com,
+        CommitmentIn::<P>::deserialize(&mut buf[..].as_ref(), true).unwrap()
+    );
+
+    let mut buf: Vec<u8> = vec![];
+    assert!(agg_proof.serialize(&mut buf, true).is_ok());
+    assert_eq!(buf.len(), P::PROOF_LEN);
+    assert_eq!(buf[0], P::TAG);
+    assert_eq!(
+        agg_proof,
+        ProofIn::<P>::deserialize(&mut buf[..].as_ref(), true).unwrap()
+    );
+
+    let mut buf: Vec<u8> = vec![];
+    assert!(prover_params.serialize(&mut buf, true).is_ok());
+    assert_eq!(buf.len(), 9 + n * P::G1_LEN * 2);
+    assert_eq!(buf[0], P::TAG);
+    assert_eq!(
+        prover_params,
+        ProverParamsIn::<P>::deserialize(&mut buf[..].as_ref(), true).unwrap()
+    );
+
+    let mut buf: Vec<u8> = vec![];
+    assert!(verifier_params.serialize(&mut buf, true).is_ok());
+    assert_eq!(buf[0], P::TAG);
+    assert_eq!(
+        verifier_params,
+        VerifierParamsIn::<P>::deserialize(&mut buf[..].as_ref(), true).unwrap()
+    );
+
+    // the ceremony works in the placement as well
+    let (pp0, vp0) = init_in::<P>(0, 8).unwrap();
+    let (pp1, vp1, update_proof) = contribute(&pp0, &vp0, &[7u8; 32]).unwrap();
+    assert!(verify_transcript(&[(pp0, vp0), (pp1, vp1)], &[update_proof]).is_ok());
+}
+
+#[test]
+fn test_placements() {
+    test_placement_helper::<G1Placement>();
+    test_placement_helper::<G2Placement>();
+}
+
+#[test]
+fn test_placements_coexist() {
+    let n = 8usize;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let (pp1, vp1) = paramgen_from_seed_in::<G1Placement, _>(seed, 0, n).unwrap();
+    let (pp2, vp2) = paramgen_from_seed_in::<G2Placement, _>(seed, 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com1 = CommitmentIn::new(&pp1, &values).unwrap();
+    let com2 = CommitmentIn::new(&pp2, &values).unwrap();
+    let proof1 = ProofIn::new(&pp1, &values, 3).unwrap();
+    let proof2 = ProofIn::new(&pp2, &values, 3).unwrap();
+    assert!(proof1.verify(&vp1, &com1, &values[3], 3));
+    assert!(proof2.verify(&vp2, &com2, &values[3], 3));
+
+    // a blob of one placement cannot be read as the other
+    let mut buf1: Vec<u8> = vec![];
+    assert!(com1.serialize(&mut buf1, true).is_ok());
+    let mut buf2: Vec<u8> = vec![];
+    assert!(com2.serialize(&mut buf2, true).is_ok());
+    assert!(CommitmentIn::<G2Placement>::deserialize(&mut buf1[..].as_ref(), true).is_err());
+    assert!(CommitmentIn::<G1Placement>::deserialize(&mut buf2[..].as_ref(), true).is_err());
+
+    let mut buf1: Vec<u8> = vec![];
+    assert!(proof1.serialize(&mut buf1, true).is_ok());
+    let mut buf2: Vec<u8> = vec![];
+    assert!(proof2.serialize(&mut buf2, true).is_ok());
+    assert!(ProofIn::<G2Placement>::deserialize(&mut buf1[..].as_ref(), true).is_err());
+    assert!(ProofIn::<G1Placement>::deserialize(&mut buf2[..].as_ref(), true).is_err());
+
+    let mut buf1: Vec<u8> = vec![];
+    assert!(vp1.serialize(&mut buf1, true).is_ok());
+    let mut buf2: Vec<u8> = vec![];
+    assert!(vp2.serialize(&mut buf2, true).is_ok());
+    assert!(VerifierParamsIn::<G2Placement>::deserialize(&mut buf1[..].as_ref(), true).is_err());
+    assert!(VerifierParamsIn::<G1Placement>::deserialize(&mut buf2[..].as_ref(), true).is_err());
+
+    // the ciphersuite ids of the registry leave the tag bit free
+    let def = pairings::ciphersuite::get_ciphersuite(1).unwrap();
+    assert!(pairings::ciphersuite::register_ciphersuite(PLACEMENT_TAG_MASK | 9, def).is_err());
+}
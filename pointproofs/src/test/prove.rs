@@ -1,5 +1,9 @@
+use ff::Field;
+use pairing::bls12_381::Fr;
 use pairing::CurveProjective;
-use pairings::param::paramgen_from_seed;
+use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
+use pairings::param::{paramgen_from_seed, paramgen_from_seed_in};
+use pairings::pointproofs_groups::*;
 use pairings::*;
 
 #[test]
@@ -9,7 +13,7 @@ fn negative_test_batch_new_proof() {
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
 
     let mut pp2 = prover_params.clone();
-    pp2.ciphersuite = 1;
+    pp2.ciphersuite = 255;
     let mut pp3 = prover_params.clone();
     pp3.n = 2;
 
@@ -45,9 +49,9 @@ fn negative_test_proof() {
     let (prover_params, verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut prover_params2 = prover_params.clone();
-    prover_params2.ciphersuite = 1;
+    prover_params2.ciphersuite = 255;
     let mut verifier_params2 = verifier_params.clone();
-    verifier_params2.ciphersuite = 1;
+    verifier_params2.ciphersuite = 255;
 
     let mut prover_params256 = prover_params.clone();
     prover_params256.precomp_256();
@@ -81,14 +85,14 @@ fn negative_test_proof() {
         .is_err());
 
     let mut proof2 = proof.clone();
-    proof2.ciphersuite = 1;
+    proof2.ciphersuite = 255;
     assert!(proof2
         .update(&prover_params, 0, 1, &values2[1], &values2[2])
         .is_err());
 
     let com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 255;
     assert!(!proof.verify(&verifier_params2, &com, &values[0], 0));
     assert!(!proof.verify(&verifier_params, &com, &values[0], 9));
     assert!(!proof.verify(&verifier_params, &com2, &values[0], 0));
@@ -444,3 +448,426 @@ fn test_batch_new_proof() {
         "pre_compute256 failed"
     );
 }
+
+#[test]
+fn test_all_proofs() {
+    for n in [1usize, 2, 3, 8, 33, 64].iter() {
+        let n = *n;
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+        let mut values: Vec<String> = vec![];
+        for i in 0..n {
+            let s = format!("this is message number {}", i);
+            values.push(s);
+        }
+        let indices: Vec<usize> = (0..n).collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+
+        let proofs = Proof::all_proofs(&prover_params, &values).unwrap();
+        let expected = Proof::batch_new(&prover_params, &values, &indices).unwrap();
+        assert_eq!(proofs, expected);
+        for (i, proof) in proofs.iter().enumerate() {
+            assert!(proof.verify(&verifier_params, &com, &values[i], i));
+        }
+    }
+}
+
+#[test]
+fn negative_test_all_proofs() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp2 = prover_params.clone();
+    pp2.ciphersuite = 255;
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+
+    assert!(Proof::all_proofs(&pp2, &values).is_err());
+    assert!(Proof::all_proofs(&prover_params, &values[0..n - 1]).is_err());
+}
+
+#[test]
+fn test_scalar_proofs() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let mut scalars: Vec<Fr> = values.iter().map(hash_to_field_pointproofs).collect();
+    let indices: Vec<usize> = (0..n).collect();
+
+    // hashing the values first gives the same commitment and proofs
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    assert_eq!(
+        com,
+        Commitment::new_from_scalars(&prover_params, &scalars).unwrap()
+    );
+    let proofs = Proof::batch_new_from_scalars(&prover_params, &scalars, &indices).unwrap();
+    assert_eq!(
+        proofs,
+        Proof::batch_new(&prover_params, &values, &indices).unwrap()
+    );
+    for i in 0..n {
+        assert!(proofs[i].verify_scalars(&verifier_params, &com, &scalars[i], i));
+        assert!(!proofs[i].verify_scalars(&verifier_params, &com, &scalars[(i + 1) % n], i));
+        assert!(!proofs[i].verify_scalars(&verifier_params, &com, &scalars[i], (i + 1) % n));
+    }
+
+    // unlike hashes, scalars can be zero
+    scalars[3] = Fr::zero();
+    scalars[5] = Fr::one();
+    let com = Commitment::new_from_scalars(&prover_params, &scalars).unwrap();
+    let proofs = Proof::batch_new_from_scalars(&prover_params, &scalars, &[3, 5]).unwrap();
+    assert!(proofs[0].verify_scalars(&verifier_params, &com, &Fr::zero(), 3));
+    assert!(!proofs[0].verify_scalars(&verifier_params, &com, &Fr::one(), 3));
+    assert!(proofs[1].verify_scalars(&verifier_params, &com, &Fr::one(), 5));
+    assert!(!proofs[1].verify_scalars(&verifier_params, &com, &Fr::zero(), 5));
+    assert!(!proofs[0].verify_scalars(&verifier_params, &com, &Fr::zero(), n));
+}
+
+#[test]
+fn negative_test_scalar_proofs() {
+    let n = 8usize;
+    let (prover_params, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let scalars: Vec<Fr> = (0..n)
+        .map(|i| hash_to_field_pointproofs(format!("{}", i)))
+        .collect();
+
+    assert!(Commitment::new_from_scalars(&prover_params, &scalars[1..]).is_err());
+    assert!(Proof::batch_new_from_scalars(&prover_params, &scalars[1..], &[0]).is_err());
+    assert!(Proof::batch_new_from_scalars(&prover_params, &scalars, &[]).is_err());
+    assert!(Proof::batch_new_from_scalars(&prover_params, &scalars, &[n]).is_err());
+    assert!(Proof::batch_new_from_scalars(&prover_params, &scalars, &[1, 1]).is_err());
+}
+
+#[test]
+fn test_batch_update_proof() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut prover_params3 = prover_params.clone();
+    prover_params3.precomp_3();
+    let mut prover_params256 = prover_params.clone();
+    prover_params256.precomp_256();
+
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let changed_index = vec![1usize, 3, 5, 8, 15];
+    let value_before: Vec<String> = changed_index.iter().map(|i| values[*i].clone()).collect();
+    let value_after: Vec<String> = changed_index
+        .iter()
+        .map(|i| format!("this is new message number {}", i))
+        .collect();
+    let mut new_values = values.clone();
+    for (i, e) in changed_index.iter().zip(value_after.iter()) {
+        new_values[*i] = e.clone();
+    }
+    let new_com = Commitment::new(&prover_params, &new_values).unwrap();
+
+    for pp in [&prover_params, &prover_params3, &prover_params256].iter() {
+        // one proof at a time, including a proof for a changed index
+        for proof_index in [0usize, 3, 15].iter() {
+            let mut proof = Proof::new(&prover_params, &values, *proof_index).unwrap();
+            proof
+                .batch_update(
+                    pp,
+                    *proof_index,
+                    &changed_index,
+                    &value_before,
+                    &value_after,
+                )
+                .unwrap();
+            assert_eq!(
+                proof,
+                Proof::new(&prover_params, &new_values, *proof_index).unwrap()
+            );
+            assert!(proof.verify(
+                &verifier_params,
+                &new_com,
+                &new_values[*proof_index],
+                *proof_index
+            ));
+        }
+
+        // all the proofs at once
+        let proof_indices: Vec<usize> = (0..n).collect();
+        let mut proofs = Proof::batch_new(&prover_params, &values, &proof_indices).unwrap();
+        Proof::update_many_proofs(
+            pp,
+            &mut proofs,
+            &proof_indices,
+            &changed_index,
+            &value_before,
+            &value_after,
+        )
+        .unwrap();
+        assert_eq!(
+            proofs,
+            Proof::batch_new(&prover_params, &new_values, &proof_indices).unwrap()
+        );
+    }
+
+    // a change of all the values, and no change at all
+    let mut proof = Proof::new(&prover_params, &values, 2).unwrap();
+    let all_index: Vec<usize> = (0..n).collect();
+    proof
+        .batch_update(&prover_params, 2, &all_index, &values, &new_values)
+        .unwrap();
+    assert_eq!(proof, Proof::new(&prover_params, &new_values, 2).unwrap());
+    let empty: Vec<String> = vec![];
+    proof
+        .batch_update(&prover_params, 2, &[], &empty, &empty)
+        .unwrap();
+    assert_eq!(proof, Proof::new(&prover_params, &new_values, 2).unwrap());
+}
+
+#[test]
+fn negative_test_batch_update_proof() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp2 = prover_params.clone();
+    pp2.ciphersuite = 255;
+
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let new_values: Vec<String> = (0..n)
+        .map(|i| format!("this is new message number {}", i))
+        .collect();
+    let proof = Proof::new(&prover_params, &values, 0).unwrap();
+
+    let mut proof2 = proof.clone();
+    assert!(proof2
+        .batch_update(&pp2, 0, &[1, 2], &values[1..3], &new_values[1..3])
+        .is_err());
+    assert!(proof2
+        .batch_update(&prover_params, n, &[1, 2], &values[1..3], &new_values[1..3])
+        .is_err());
+    assert!(proof2
+        .batch_update(&prover_params, 0, &[1, n], &values[1..3], &new_values[1..3])
+        .is_err());
+    assert!(proof2
+        .batch_update(&prover_params, 0, &[1, 1], &values[1..3], &new_values[1..3])
+        .is_err());
+    assert!(proof2
+        .batch_update(&prover_params, 0, &[1, 2], &values[1..3], &new_values[1..4])
+        .is_err());
+    assert_eq!(proof, proof2);
+
+    let mut proofs = vec![
+        proof.clone(),
+        Proof::new(&prover_params, &values, 1).unwrap(),
+    ];
+    let proofs2 = proofs.clone();
+    assert!(Proof::update_many_proofs(
+        &prover_params,
+        &mut proofs,
+        &[0],
+        &[1, 2],
+        &values[1..3],
+        &new_values[1..3]
+    )
+    .is_err());
+    assert!(Proof::update_many_proofs(
+        &prover_params,
+        &mut proofs,
+        &[0, n],
+        &[1, 2],
+        &values[1..3],
+        &new_values[1..3]
+    )
+    .is_err());
+    assert!(Proof::update_many_proofs(
+        &pp2,
+        &mut proofs,
+        &[0, 1],
+        &[1, 2],
+        &values[1..3],
+        &new_values[1..3]
+    )
+    .is_err());
+    assert!(Proof::update_many_proofs(
+        &prover_params,
+        &mut proofs,
+        &[0, 1],
+        &[2, 2],
+        &values[1..3],
+        &new_values[1..3]
+    )
+    .is_err());
+    assert_eq!(proofs, proofs2);
+}
+
+#[test]
+fn test_batch_verify_many() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    // 3 commitments, with 6 proofs each; some indices are shared by several commitments
+    let values: Vec<Vec<Vec<u8>>> = (0..3)
+        .map(|j| {
+            (0..n)
+                .map(|i| format!("this is message number {} for commit {}", i, j).into_bytes())
+                .collect()
+        })
+        .collect();
+    let commits: Vec<Commitment> = values
+        .iter()
+        .map(|v| Commitment::new(&prover_params, v).unwrap())
+        .collect();
+    let indices = [0usize, 3, 7, 8, 12, 15];
+    let mut proofs: Vec<Proof> = vec![];
+    let mut items: Vec<(usize, usize)> = vec![];
+    for (j, v) in values.iter().enumerate() {
+        for index in indices.iter().skip(j).chain(indices.iter().take(j)) {
+            proofs.push(Proof::new(&prover_params, v, *index).unwrap());
+            items.push((j, *index));
+        }
+    }
+    let wrong_value = b"this is a wrong value".to_vec();
+    let mut wrong_proof = proofs[4].clone();
+    wrong_proof.proof.double();
+    let mut wrong_ciphersuite = proofs[9].clone();
+    wrong_ciphersuite.ciphersuite = 255;
+    let batch: Vec<(&Commitment, &Proof, usize, &[u8])> = items
+        .iter()
+        .zip(proofs.iter())
+        .map(|((j, index), proof)| (&commits[*j], proof, *index, &values[*j][*index][..]))
+        .collect();
+
+    // all proofs are valid
+    assert!(Proof::batch_verify_many(&verifier_params, &batch).is_empty());
+    assert!(Proof::batch_verify_many::<&[u8]>(&verifier_params, &[]).is_empty());
+    assert!(Proof::batch_verify_many(&verifier_params, &batch[..1]).is_empty());
+
+    // invalid proofs are identified, whatever their positions
+    for invalid in [vec![0usize], vec![17], vec![2, 3], vec![1, 4, 9, 16]].iter() {
+        let mut batch2 = batch.clone();
+        for k in invalid {
+            match k % 4 {
+                0 => batch2[*k].3 = &wrong_value[..],
+                1 => batch2[*k].1 = &wrong_proof,
+                2 => batch2[*k].2 = (batch2[*k].2 + 1) % n,
+                _ => batch2[*k].0 = &commits[(items[*k].0 + 1) % 3],
+            }
+        }
+        for (k, (com, proof, index, value)) in batch2.iter().enumerate() {
+            assert_eq!(
+                proof.verify(&verifier_params, com, value, *index),
+                !invalid.contains(&k)
+            );
+        }
+        assert_eq!(
+            &Proof::batch_verify_many(&verifier_params, &batch2),
+            invalid
+        );
+    }
+
+    // proofs with invalid ciphersuites or indices
+    let mut batch2 = batch.clone();
+    batch2[9].1 = &wrong_ciphersuite;
+    batch2[11].2 = n;
+    assert_eq!(
+        Proof::batch_verify_many(&verifier_params, &batch2),
+        vec![9, 11]
+    );
+    let mut verifier_params2 = verifier_params.clone();
+    verifier_params2.ciphersuite = 255;
+    assert_eq!(
+        Proof::batch_verify_many(&verifier_params2, &batch),
+        (0..batch.len()).collect::<Vec<usize>>()
+    );
+
+    // every proof is invalid
+    let batch2: Vec<(&Commitment, &Proof, usize, &[u8])> = batch
+        .iter()
+        .map(|(com, proof, index, _value)| (*com, *proof, *index, &wrong_value[..]))
+        .collect();
+    assert_eq!(
+        Proof::batch_verify_many(&verifier_params, &batch2),
+        (0..batch.len()).collect::<Vec<usize>>()
+    );
+}
+
+fn check_prepared_verification<P: GroupPlacement>() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed_in::<P, _>("This is Leo's Favourite very very very long Seed", 0, n)
+            .unwrap();
+    let mut prepared_params = verifier_params.clone();
+    prepared_params.prepare();
+    // the restriction keeps the prepared lines of the first generators and of the generator
+    let prepared_restricted = prepared_params.restrict(8).unwrap();
+
+    let values: Vec<Vec<u8>> = (0..n)
+        .map(|i| format!("this is message number {}", i).into_bytes())
+        .collect();
+    let com = CommitmentIn::new(&prover_params, &values).unwrap();
+    let set = vec![1usize, 4, 7];
+    let mut proofs: Vec<ProofIn<P>> = vec![];
+    let mut value_sub_vector: Vec<Vec<u8>> = vec![];
+    for index in set.iter() {
+        let proof = ProofIn::new(&prover_params, &values, *index).unwrap();
+        for vp in [&verifier_params, &prepared_params].iter() {
+            assert!(proof.verify(vp, &com, &values[*index], *index));
+            assert!(!proof.verify(vp, &com, &values[*index + 1], *index));
+            assert!(!proof.verify(vp, &com, &values[*index], *index + 1));
+        }
+        proofs.push(proof);
+        value_sub_vector.push(values[*index].clone());
+    }
+
+    let restricted_prover_params = prover_params.restrict(8).unwrap();
+    let restricted_com = CommitmentIn::new(&restricted_prover_params, &values[..8]).unwrap();
+    let restricted_proof = ProofIn::new(&restricted_prover_params, &values[..8], 5).unwrap();
+    assert!(restricted_proof.verify(&prepared_restricted, &restricted_com, &values[5], 5));
+    assert!(!restricted_proof.verify(&prepared_restricted, &restricted_com, &values[4], 5));
+
+    let agg_proof =
+        ProofIn::same_commit_aggregate(&com, &proofs, &set, &value_sub_vector, n).unwrap();
+    let mut wrong_values = value_sub_vector.clone();
+    wrong_values[2] = b"this is a wrong value".to_vec();
+    for vp in [&verifier_params, &prepared_params].iter() {
+        assert!(agg_proof.same_commit_batch_verify(vp, &com, &set, &value_sub_vector));
+        assert!(!agg_proof.same_commit_batch_verify(vp, &com, &set, &wrong_values));
+        assert!(agg_proof.cross_commit_batch_verify(
+            vp,
+            &[com.clone()],
+            &[set.clone()],
+            &[value_sub_vector.clone()]
+        ));
+        assert!(!agg_proof.cross_commit_batch_verify(
+            vp,
+            &[com.clone()],
+            &[set.clone()],
+            &[wrong_values.clone()]
+        ));
+
+        let items: Vec<(&CommitmentIn<P>, &ProofIn<P>, usize, &[u8])> = set
+            .iter()
+            .zip(proofs.iter())
+            .map(|(index, proof)| (&com, proof, *index, &values[*index][..]))
+            .chain(vec![(&com, &proofs[0], set[1], &values[set[1]][..])])
+            .collect();
+        assert_eq!(ProofIn::batch_verify_many(vp, &items), vec![3]);
+    }
+}
+
+#[test]
+fn test_prepared_verification() {
+    check_prepared_verification::<G1Placement>();
+    check_prepared_verification::<G2Placement>();
+}
@@ -0,0 +1,79 @@
+use ciborium;
+use serde_json;
+
+use pairing::serdes::SerDes;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_serde_json() {
+    let n = 16;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n).map(|i| format!("this is message number {}", i)).collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+
+    // human-readable formats use the hex string of the SerDes encoding
+    let json = serde_json::to_string(&com).unwrap();
+    let mut buf: Vec<u8> = vec![];
+    com.serialize(&mut buf, true).unwrap();
+    let hex: String = buf.iter().map(|byte| format!("{:02x}", byte)).collect();
+    assert_eq!(json, format!("\"{}\"", hex));
+    assert_eq!(serde_json::from_str::<Commitment>(&json).unwrap(), com);
+
+    let json = serde_json::to_string(&proof).unwrap();
+    let proof_recover: Proof = serde_json::from_str(&json).unwrap();
+    assert_eq!(proof_recover, proof);
+
+    let json = serde_json::to_string(&verifier_params).unwrap();
+    let verifier_params_recover: VerifierParams = serde_json::from_str(&json).unwrap();
+    assert!(proof_recover.verify(&verifier_params_recover, &com, &values[3], 3));
+
+    let json = serde_json::to_string(&prover_params).unwrap();
+    assert_eq!(serde_json::from_str::<ProverParams>(&json).unwrap(), prover_params);
+}
+
+#[test]
+fn test_serde_cbor() {
+    let n = 16;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n).map(|i| format!("this is message number {}", i)).collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 5).unwrap();
+
+    // binary formats carry the raw bytes
+    let mut cbor: Vec<u8> = vec![];
+    ciborium::ser::into_writer(&(&com, &proof, &verifier_params), &mut cbor).unwrap();
+    let (com_recover, proof_recover, verifier_params_recover): (Commitment, Proof, VerifierParams) =
+        ciborium::de::from_reader(&cbor[..]).unwrap();
+    assert_eq!(com_recover, com);
+    assert_eq!(proof_recover, proof);
+    assert!(proof_recover.verify(&verifier_params_recover, &com_recover, &values[5], 5));
+}
+
+#[test]
+fn negative_test_serde() {
+    let n = 16;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n).map(|i| format!("this is message number {}", i)).collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let json = serde_json::to_string(&com).unwrap();
+
+    // invalid hex, an invalid ciphersuite, a coordinate larger than the field modulus and trailing bytes
+    assert!(serde_json::from_str::<Commitment>("\"0g\"").is_err());
+    let mut invalid = json.clone();
+    invalid.replace_range(1..3, "ff");
+    assert!(serde_json::from_str::<Commitment>(&invalid).is_err());
+    let mut invalid = json.clone();
+    invalid.replace_range(3..7, "9fff");
+    assert!(serde_json::from_str::<Commitment>(&invalid).is_err());
+    let invalid = format!("{}00\"", &json[..json.len() - 1]);
+    assert!(serde_json::from_str::<Commitment>(&invalid).is_err());
+    // the same validations apply to binary formats
+    let mut cbor: Vec<u8> = vec![];
+    ciborium::ser::into_writer(&ciborium::value::Value::Bytes(vec![0u8; 3]), &mut cbor).unwrap();
+    assert!(ciborium::de::from_reader::<Commitment, _>(&cbor[..]).is_err());
+}
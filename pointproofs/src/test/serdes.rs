@@ -172,7 +172,7 @@ fn test_serdes_commit() {
     assert!(Commitment::deserialize(&mut buf[..].as_ref(), false).is_err());
 
     let invalid_commit = Commitment {
-        ciphersuite: 1,
+        ciphersuite: 255,
         commit: PointproofsG1::one(),
     };
     assert!(invalid_commit.serialize(&mut buf, true).is_err());
@@ -203,7 +203,7 @@ fn test_serdes_proof() {
     assert!(Proof::deserialize(&mut buf[..].as_ref(), false).is_err());
 
     let invalid_proof = Proof {
-        ciphersuite: 1,
+        ciphersuite: 255,
         proof: PointproofsG1::one(),
     };
     assert!(invalid_proof.serialize(&mut buf, true).is_err());
@@ -0,0 +1,111 @@
+import pytest
+
+import verkle_tree
+
+SEED = b"This is Leo's Favourite very very very long Seed"
+WIDTH = 4
+
+
+def values(n):
+    return [b"value %d of the tree" % i for i in range(n)]
+
+
+@pytest.fixture(scope="module")
+def params():
+    return verkle_tree.paramgen_from_seed(SEED, WIDTH)
+
+
+def test_pointproofs_prove_and_verify(params):
+    prover, verifier = params
+    data = values(WIDTH ** 3)
+    tree = verkle_tree.PointproofsTree(data, WIDTH, prover)
+    assert tree.width == WIDTH
+    assert tree.depth == 3
+
+    indices = [1, 17, 63]
+    root = tree.root()
+    proof = tree.prove(indices)
+    claimed = [data[i] for i in indices]
+    assert verkle_tree.PointproofsTree.verify(root, proof, indices, claimed, WIDTH, tree.depth, verifier)
+
+    wrong = list(claimed)
+    wrong[1] = b"not the value"
+    assert not verkle_tree.PointproofsTree.verify(root, proof, indices, wrong, WIDTH, tree.depth, verifier)
+    # the proof is for other indices
+    assert not verkle_tree.PointproofsTree.verify(root, proof, [1, 18, 63], claimed, WIDTH, tree.depth, verifier)
+
+    other_root = verkle_tree.PointproofsTree(values(WIDTH ** 3)[::-1], WIDTH, prover).root()
+    assert not verkle_tree.PointproofsTree.verify(other_root, proof, indices, claimed, WIDTH, tree.depth, verifier)
+
+
+def test_pointproofs_invalid_input(params):
+    prover, _ = params
+    with pytest.raises(ValueError):
+        verkle_tree.PointproofsTree(values(WIDTH ** 2 - 1), WIDTH, prover)
+    tree = verkle_tree.PointproofsTree(values(WIDTH ** 2), WIDTH, prover)
+    with pytest.raises(ValueError):
+        tree.prove([WIDTH ** 2])
+
+
+def test_params_serialization(params):
+    prover, verifier = params
+    prover_again = verkle_tree.ProverParams.from_bytes(prover.to_bytes())
+    verifier_again = verkle_tree.VerifierParams.from_bytes(verifier.to_bytes())
+    assert prover_again.to_bytes() == prover.to_bytes()
+    assert verifier_again.to_bytes() == verifier.to_bytes()
+
+    data = values(WIDTH ** 2)
+    tree = verkle_tree.PointproofsTree(data, WIDTH, prover_again)
+    assert tree.root() == verkle_tree.PointproofsTree(data, WIDTH, prover).root()
+    proof = tree.prove([3])
+    assert verkle_tree.PointproofsTree.verify(tree.root(), proof, [3], [data[3]], WIDTH, tree.depth, verifier_again)
+
+    with pytest.raises(ValueError):
+        verkle_tree.VerifierParams.from_bytes(b"not parameters")
+
+
+def test_read_param_missing_file(tmp_path):
+    with pytest.raises(OSError):
+        verkle_tree.read_param(str(tmp_path / "missing.param"))
+
+
+def test_kzg_prove_and_verify():
+    setup = verkle_tree.KzgSetup(WIDTH)
+    data = values(WIDTH ** 2)
+    tree = verkle_tree.KzgTree(data, WIDTH, setup)
+    assert tree.depth == 2
+
+    indices = [2, 9]
+    root = tree.root()
+    proof = tree.prove(indices)
+    claimed = [data[i] for i in indices]
+    assert verkle_tree.KzgTree.verify(root, proof, indices, claimed, WIDTH, tree.depth, setup)
+
+    wrong = list(claimed)
+    wrong[1] = b"not the value"
+    assert not verkle_tree.KzgTree.verify(root, proof, indices, wrong, WIDTH, tree.depth, setup)
+    other_root = verkle_tree.KzgTree(data[::-1], WIDTH, setup).root()
+    assert other_root != root
+    assert not verkle_tree.KzgTree.verify(other_root, proof, indices, claimed, WIDTH, tree.depth, setup)
+    assert not verkle_tree.KzgTree.verify(root, proof, [2, 10], claimed, WIDTH, tree.depth, setup)
+    # the proof only verifies against the setup of the tree
+    other_setup = verkle_tree.KzgSetup(WIDTH)
+    assert not verkle_tree.KzgTree.verify(root, proof, indices, claimed, WIDTH, tree.depth, other_setup)
+
+    with pytest.raises(ValueError):
+        tree.prove([WIDTH ** 2])
+
+
+def test_kzg_setup_serialization():
+    setup = verkle_tree.KzgSetup(WIDTH, SEED)
+    again = verkle_tree.KzgSetup.from_bytes(setup.to_bytes())
+    assert again.width == WIDTH
+    assert again.to_bytes() == verkle_tree.KzgSetup(WIDTH, SEED).to_bytes()
+
+    data = values(WIDTH)
+    tree = verkle_tree.KzgTree(data, WIDTH, setup)
+    assert tree.root() == verkle_tree.KzgTree(data, WIDTH, again).root()
+    with pytest.raises(ValueError):
+        verkle_tree.KzgTree(values(4), 2, setup)
+    with pytest.raises(ValueError):
+        verkle_tree.KzgSetup.from_bytes(b"not a setup")
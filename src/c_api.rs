@@ -0,0 +1,358 @@
+//! this file exposes the Verkle tree (pointproofs backend) to C.
+//! the header is c_test/verkle_c.h, generated with cbindgen (see the Makefile).
+//! functions return VERKLE_OK or a negative error code, panics are caught at the boundary.
+#![allow(non_camel_case_types)]
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use pairing_plus::serdes::SerDes;
+use pointproofs::pairings::param::paramgen_from_seed;
+use pointproofs::pairings::{Commitment, ProverParams, VerifierParams};
+
+use crate::file_io::{ProofBody, ProofFile};
+use crate::verkle_tree_point::{check_shape, VerkleTree};
+
+/// Success.
+pub const VERKLE_OK: i32 = 0;
+/// A pointer argument is null.
+pub const VERKLE_ERR_NULL_POINTER: i32 = -1;
+/// An argument is out of range, e.g. an index outside of the tree.
+pub const VERKLE_ERR_INVALID_ARGUMENT: i32 = -2;
+/// The parameters could not be generated or the tree could not be built.
+pub const VERKLE_ERR_BUILD: i32 = -3;
+/// The proof could not be generated.
+pub const VERKLE_ERR_PROOF: i32 = -4;
+/// Bytes could not be (de)serialized.
+pub const VERKLE_ERR_SERIALIZATION: i32 = -5;
+/// The library panicked, this is a bug.
+pub const VERKLE_ERR_PANIC: i32 = -6;
+
+/// a value of the tree
+#[repr(C)]
+#[derive(Clone)]
+pub struct verkle_value {
+    pub data: *const u8,
+    pub len: libc::size_t,
+}
+
+/// bytes allocated by the library, free them with verkle_free_bytes
+#[repr(C)]
+pub struct verkle_bytes {
+    pub data: *mut u8,
+    pub len: libc::size_t,
+}
+
+/// prover and verifier parameters
+#[repr(C)]
+pub struct verkle_params {
+    prover: *mut ffi::c_void,
+    verifier: *mut ffi::c_void,
+}
+
+/// a tree, keeps its own copy of the prover parameters
+#[repr(C)]
+pub struct verkle_tree {
+    data: *mut ffi::c_void,
+}
+
+/// a proof for several indices of a tree
+#[repr(C)]
+pub struct verkle_proof {
+    data: *mut ffi::c_void,
+}
+
+fn guard<F: FnOnce() -> i32>(f: F) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(VERKLE_ERR_PANIC)
+}
+
+unsafe fn values_from(values: *const verkle_value, n: usize) -> Option<Vec<Vec<u8>>> {
+    if values.is_null() {
+        return None;
+    }
+    slice::from_raw_parts(values, n)
+        .iter()
+        .map(|value| match value.data.is_null() {
+            true if value.len > 0 => None,
+            true => Some(Vec::new()),
+            false => Some(slice::from_raw_parts(value.data, value.len).to_vec()),
+        })
+        .collect()
+}
+
+fn into_bytes(buf: Vec<u8>) -> verkle_bytes {
+    let mut boxed_buf = buf.into_boxed_slice();
+    let data = boxed_buf.as_mut_ptr();
+    let len = boxed_buf.len();
+    std::mem::forget(boxed_buf);
+    verkle_bytes { data, len }
+}
+
+/// Generating parameters for trees of width n from a seed
+#[no_mangle]
+pub unsafe extern "C" fn verkle_paramgen(
+    seedbuf: *const u8,
+    seedlen: libc::size_t,
+    ciphersuite: u8,
+    n: libc::size_t,
+    params: *mut verkle_params,
+) -> i32 {
+    guard(|| {
+        if seedbuf.is_null() || params.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let seed = slice::from_raw_parts(seedbuf, seedlen);
+        let (pp, vp) = match paramgen_from_seed(seed, ciphersuite, n) {
+            Ok(p) => p,
+            Err(_e) => return VERKLE_ERR_BUILD,
+        };
+        *params = verkle_params {
+            prover: Box::into_raw(Box::new(pp)) as *mut ffi::c_void,
+            verifier: Box::into_raw(Box::new(vp)) as *mut ffi::c_void,
+        };
+        VERKLE_OK
+    })
+}
+
+/// Deserializing parameters written by pointproofs_pp_serial and pointproofs_vp_serial
+#[no_mangle]
+pub unsafe extern "C" fn verkle_params_deserial(
+    prover_bytes: *const u8,
+    prover_len: libc::size_t,
+    verifier_bytes: *const u8,
+    verifier_len: libc::size_t,
+    params: *mut verkle_params,
+) -> i32 {
+    guard(|| {
+        if prover_bytes.is_null() || verifier_bytes.is_null() || params.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let pp = match ProverParams::deserialize(&mut slice::from_raw_parts(prover_bytes, prover_len), true) {
+            Ok(p) => p,
+            Err(_e) => return VERKLE_ERR_SERIALIZATION,
+        };
+        let vp = match VerifierParams::deserialize(&mut slice::from_raw_parts(verifier_bytes, verifier_len), true) {
+            Ok(p) => p,
+            Err(_e) => return VERKLE_ERR_SERIALIZATION,
+        };
+        *params = verkle_params {
+            prover: Box::into_raw(Box::new(pp)) as *mut ffi::c_void,
+            verifier: Box::into_raw(Box::new(vp)) as *mut ffi::c_void,
+        };
+        VERKLE_OK
+    })
+}
+
+/// Free parameters
+#[no_mangle]
+pub unsafe extern "C" fn verkle_free_params(params: verkle_params) {
+    if !params.prover.is_null() {
+        drop(Box::from_raw(params.prover as *mut ProverParams));
+    }
+    if !params.verifier.is_null() {
+        drop(Box::from_raw(params.verifier as *mut VerifierParams));
+    }
+}
+
+/// Free bytes returned by the library
+#[no_mangle]
+pub unsafe extern "C" fn verkle_free_bytes(buf: verkle_bytes) {
+    if !buf.data.is_null() {
+        drop(Box::from_raw(slice::from_raw_parts_mut(buf.data, buf.len) as *mut [u8]));
+    }
+}
+
+/// Building a tree of the given width from n values, n has to be a power of the width
+#[no_mangle]
+pub unsafe extern "C" fn verkle_tree_new(
+    params: *const verkle_params,
+    values: *const verkle_value,
+    n: libc::size_t,
+    width: libc::size_t,
+    tree: *mut verkle_tree,
+) -> i32 {
+    guard(|| {
+        if params.is_null() || (*params).prover.is_null() || tree.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let values = match values_from(values, n) {
+            Some(v) => v,
+            None => return VERKLE_ERR_NULL_POINTER,
+        };
+        // the tree asserts that it is completely filled, check it here instead of panicking
+        if check_shape(values.len(), width).is_err() {
+            return VERKLE_ERR_INVALID_ARGUMENT;
+        }
+        let pp = (*((*params).prover as *const ProverParams)).clone();
+        let built = match VerkleTree::new(&values, width, pp) {
+            Ok(t) => t,
+            Err(_e) => return VERKLE_ERR_BUILD,
+        };
+        *tree = verkle_tree {
+            data: Box::into_raw(Box::new(built)) as *mut ffi::c_void,
+        };
+        VERKLE_OK
+    })
+}
+
+/// Free a tree
+#[no_mangle]
+pub unsafe extern "C" fn verkle_free_tree(tree: verkle_tree) {
+    if !tree.data.is_null() {
+        drop(Box::from_raw(tree.data as *mut VerkleTree));
+    }
+}
+
+/// The serialized root commitment, free it with verkle_free_bytes
+#[no_mangle]
+pub unsafe extern "C" fn verkle_tree_root(tree: *const verkle_tree, root: *mut verkle_bytes) -> i32 {
+    guard(|| {
+        if tree.is_null() || (*tree).data.is_null() || root.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let t = &*((*tree).data as *const VerkleTree);
+        let commitment = match t.root_commitment() {
+            Some(c) => c,
+            None => return VERKLE_ERR_BUILD,
+        };
+        let mut buf: Vec<u8> = vec![];
+        if commitment.serialize(&mut buf, true).is_err() {
+            return VERKLE_ERR_SERIALIZATION;
+        }
+        *root = into_bytes(buf);
+        VERKLE_OK
+    })
+}
+
+/// The number of layers of the tree, needed to verify its proofs
+#[no_mangle]
+pub unsafe extern "C" fn verkle_tree_depth(tree: *const verkle_tree, depth: *mut libc::size_t) -> i32 {
+    guard(|| {
+        if tree.is_null() || (*tree).data.is_null() || depth.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        *depth = (*((*tree).data as *const VerkleTree)).depth();
+        VERKLE_OK
+    })
+}
+
+/// Proving the values at n indices, free the proof with verkle_free_proof
+#[no_mangle]
+pub unsafe extern "C" fn verkle_tree_prove(
+    tree: *const verkle_tree,
+    indices: *const libc::size_t,
+    n: libc::size_t,
+    proof: *mut verkle_proof,
+) -> i32 {
+    guard(|| {
+        if tree.is_null() || (*tree).data.is_null() || indices.is_null() || proof.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let t = &*((*tree).data as *const VerkleTree);
+        let indices = slice::from_raw_parts(indices, n).to_vec();
+        let opened = match t.open(&indices) {
+            Ok(p) => p,
+            Err(_e) => return VERKLE_ERR_PROOF,
+        };
+        let file = ProofFile {
+            width: t.width(),
+            depth: t.depth(),
+            indices,
+            body: ProofBody::Pointproofs(opened),
+        };
+        *proof = verkle_proof {
+            data: Box::into_raw(Box::new(file)) as *mut ffi::c_void,
+        };
+        VERKLE_OK
+    })
+}
+
+/// Free a proof
+#[no_mangle]
+pub unsafe extern "C" fn verkle_free_proof(proof: verkle_proof) {
+    if !proof.data.is_null() {
+        drop(Box::from_raw(proof.data as *mut ProofFile));
+    }
+}
+
+/// Serializing a proof into bytes (the proof file format of the command line tool)
+#[no_mangle]
+pub unsafe extern "C" fn verkle_proof_serial(proof: *const verkle_proof, bytes: *mut verkle_bytes) -> i32 {
+    guard(|| {
+        if proof.is_null() || (*proof).data.is_null() || bytes.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        match (*((*proof).data as *const ProofFile)).to_bytes() {
+            Ok(buf) => {
+                *bytes = into_bytes(buf);
+                VERKLE_OK
+            }
+            Err(_e) => VERKLE_ERR_SERIALIZATION,
+        }
+    })
+}
+
+/// Deserializing bytes into a proof
+#[no_mangle]
+pub unsafe extern "C" fn verkle_proof_deserial(data: *const u8, len: libc::size_t, proof: *mut verkle_proof) -> i32 {
+    guard(|| {
+        if data.is_null() || proof.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let file = match ProofFile::from_reader(&mut slice::from_raw_parts(data, len)) {
+            Ok(f) => f,
+            Err(_e) => return VERKLE_ERR_SERIALIZATION,
+        };
+        if !matches!(file.body, ProofBody::Pointproofs(_)) {
+            return VERKLE_ERR_SERIALIZATION;
+        }
+        *proof = verkle_proof {
+            data: Box::into_raw(Box::new(file)) as *mut ffi::c_void,
+        };
+        VERKLE_OK
+    })
+}
+
+/// Verifying that values[i] is the value at indices[i] of the tree with the given root.
+/// Returns 1 for a valid proof, 0 for an invalid one and a negative error code otherwise.
+#[no_mangle]
+pub unsafe extern "C" fn verkle_verify(
+    params: *const verkle_params,
+    root: *const u8,
+    root_len: libc::size_t,
+    proof: *const verkle_proof,
+    indices: *const libc::size_t,
+    values: *const verkle_value,
+    n: libc::size_t,
+    width: libc::size_t,
+    depth: libc::size_t,
+) -> i32 {
+    guard(|| {
+        if params.is_null() || (*params).verifier.is_null() || root.is_null() || proof.is_null() || (*proof).data.is_null() || indices.is_null() {
+            return VERKLE_ERR_NULL_POINTER;
+        }
+        let values = match values_from(values, n) {
+            Some(v) => v,
+            None => return VERKLE_ERR_NULL_POINTER,
+        };
+        let commitment = match Commitment::deserialize(&mut slice::from_raw_parts(root, root_len), true) {
+            Ok(c) => c,
+            Err(_e) => return VERKLE_ERR_SERIALIZATION,
+        };
+        let file = &*((*proof).data as *const ProofFile);
+        let indices = slice::from_raw_parts(indices, n);
+        // the proof has to be for the claimed indices and the shape of the tree
+        if file.width != width || file.depth != depth || file.indices != indices {
+            return 0;
+        }
+        let opened = match &file.body {
+            ProofBody::Pointproofs(p) => p,
+            ProofBody::Kzg(_) => return 0,
+        };
+        let vp = &*((*params).verifier as *const VerifierParams);
+        VerkleTree::verify_proof(&commitment, opened, indices, &values, width, depth, vp) as i32
+    })
+}
+
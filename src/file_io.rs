@@ -0,0 +1,349 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use ark_bls12_381::{Fr as F, G1Affine};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use pairing_plus::serdes::SerDes;
+use pointproofs::pairings::{Commitment, Proof};
+
+use crate::verkle_tree::ProofNode as KzgProofNode;
+use crate::verkle_tree_point::{ProofNode as PointProofNode, VerkleProof as PointVerkleProof};
+
+/* File formats used by the command line tool.
+    All integers are little endian, byte strings are prefixed with their length as u32.
+    The lengths are read from the input, so the readers never allocate more than the input actually holds */
+
+const TREE_MAGIC: &[u8; 4] = b"VKTR";
+const PROOF_MAGIC: &[u8; 4] = b"VKPF";
+const VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Kzg,
+    Pointproofs,
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kzg" => Ok(Backend::Kzg),
+            "point" | "pointproofs" => Ok(Backend::Pointproofs),
+            _ => Err(format!("unknown backend {}, expected kzg or pointproofs", s)),
+        }
+    }
+}
+
+impl Backend {
+    fn to_byte(self) -> u8 {
+        match self {
+            Backend::Pointproofs => 0,
+            Backend::Kzg => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Backend::Pointproofs),
+            1 => Ok(Backend::Kzg),
+            _ => Err(invalid_data("unknown backend")),
+        }
+    }
+}
+
+// How the values of the leaves are stored in an input file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    // one value per line
+    Lines,
+    // every comma separated field is a value, row by row
+    Csv,
+    // a sequence of length prefixed values
+    Binary,
+}
+
+impl FromStr for DataFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lines" => Ok(DataFormat::Lines),
+            "csv" => Ok(DataFormat::Csv),
+            "binary" => Ok(DataFormat::Binary),
+            _ => Err(format!("unknown format {}, expected lines, csv or binary", s)),
+        }
+    }
+}
+
+pub fn read_values(path: &Path, format: DataFormat) -> io::Result<Vec<Vec<u8>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut values: Vec<Vec<u8>> = Vec::new();
+    match format {
+        DataFormat::Lines => {
+            for line in reader.lines() {
+                values.push(line?.into_bytes());
+            }
+        }
+        DataFormat::Csv => {
+            for line in reader.lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                values.extend(line.split(',').map(|field| field.trim().as_bytes().to_vec()));
+            }
+        }
+        DataFormat::Binary => {
+            while !reader.fill_buf()?.is_empty() {
+                values.push(read_bytes(&mut reader)?);
+            }
+        }
+    }
+    Ok(values)
+}
+
+// The KZG tree commits to field elements, the bytes are read as a little endian number modulo the field order
+pub fn values_to_field(values: &[Vec<u8>]) -> Vec<F> {
+    values.iter().map(|value| F::from_le_bytes_mod_order(value)).collect()
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim().trim_start_matches("0x");
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/* A saved tree keeps the leaves and the root. Loading rebuilds the nodes from the leaves,
+    the stored root is used to check that the rebuilt tree (and the parameters) are the same */
+pub struct TreeFile {
+    pub backend: Backend,
+    pub width: usize,
+    pub values: Vec<Vec<u8>>,
+    pub root: Vec<u8>,
+}
+
+impl TreeFile {
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(TREE_MAGIC)?;
+        writer.write_all(&[VERSION, self.backend.to_byte()])?;
+        write_u32(&mut writer, self.width)?;
+        write_u32(&mut writer, self.values.len())?;
+        for value in self.values.iter() {
+            write_bytes(&mut writer, value)?;
+        }
+        write_bytes(&mut writer, &self.root)?;
+        writer.flush()
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let backend = read_header(&mut reader, TREE_MAGIC)?;
+        let width = read_u32(&mut reader)?;
+        let len = read_u32(&mut reader)?;
+        let values = (0..len).map(|_| read_bytes(&mut reader)).collect::<io::Result<Vec<Vec<u8>>>>()?;
+        let root = read_bytes(&mut reader)?;
+        Ok(TreeFile {
+            backend,
+            width,
+            values,
+            root,
+        })
+    }
+}
+
+pub enum ProofBody {
+    Pointproofs(PointVerkleProof),
+    Kzg(Vec<Option<KzgProofNode>>),
+}
+
+// A proof for the values at `indices`, together with the shape of the tree it was made for
+pub struct ProofFile {
+    pub width: usize,
+    pub depth: usize,
+    pub indices: Vec<usize>,
+    pub body: ProofBody,
+}
+
+impl ProofFile {
+    pub fn backend(&self) -> Backend {
+        match self.body {
+            ProofBody::Pointproofs(_) => Backend::Pointproofs,
+            ProofBody::Kzg(_) => Backend::Kzg,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&self.to_bytes()?)?;
+        writer.flush()
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut writer: Vec<u8> = Vec::new();
+        writer.write_all(PROOF_MAGIC)?;
+        writer.write_all(&[VERSION, self.backend().to_byte()])?;
+        write_u32(&mut writer, self.width)?;
+        write_u32(&mut writer, self.depth)?;
+        write_u32(&mut writer, self.indices.len())?;
+        for index in self.indices.iter() {
+            write_u32(&mut writer, *index)?;
+        }
+        match &self.body {
+            ProofBody::Pointproofs(proof) => {
+                write_u32(&mut writer, proof.proofs.len())?;
+                for node in proof.proofs.iter() {
+                    node.commitment.serialize(&mut writer, true)?;
+                    node.proof.serialize(&mut writer, true)?;
+                    write_u32(&mut writer, node.indices.len())?;
+                    for (index, value) in node.indices.iter().zip(node.values.iter()) {
+                        write_u32(&mut writer, *index)?;
+                        write_bytes(&mut writer, value)?;
+                    }
+                }
+            }
+            ProofBody::Kzg(proofs) => {
+                write_u32(&mut writer, proofs.len())?;
+                for node in proofs.iter() {
+                    match node {
+                        None => writer.write_all(&[0])?,
+                        Some(node) => {
+                            writer.write_all(&[1])?;
+                            node.commitment.serialize_compressed(&mut writer).map_err(serialization_error)?;
+                            node.proof.serialize_compressed(&mut writer).map_err(serialization_error)?;
+                            node.point.serialize_compressed(&mut writer).map_err(serialization_error)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(writer)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        Self::from_reader(&mut BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader<R: Read>(mut reader: &mut R) -> io::Result<Self> {
+        let backend = read_header(&mut reader, PROOF_MAGIC)?;
+        let width = read_u32(&mut reader)?;
+        let depth = read_u32(&mut reader)?;
+        let len = read_u32(&mut reader)?;
+        let indices = (0..len).map(|_| read_u32(&mut reader)).collect::<io::Result<Vec<usize>>>()?;
+        let nodes = read_u32(&mut reader)?;
+        let body = match backend {
+            Backend::Pointproofs => {
+                let mut proofs: Vec<PointProofNode> = Vec::new();
+                for _ in 0..nodes {
+                    let commitment = Commitment::deserialize(&mut reader, true)?;
+                    let proof = Proof::deserialize(&mut reader, true)?;
+                    let opened = read_u32(&mut reader)?;
+                    let mut node_indices: Vec<usize> = Vec::new();
+                    let mut values: Vec<Vec<u8>> = Vec::new();
+                    for _ in 0..opened {
+                        node_indices.push(read_u32(&mut reader)?);
+                        values.push(read_bytes(&mut reader)?);
+                    }
+                    proofs.push(PointProofNode {
+                        commitment,
+                        proof,
+                        indices: node_indices,
+                        values,
+                    });
+                }
+                ProofBody::Pointproofs(PointVerkleProof { proofs })
+            }
+            Backend::Kzg => {
+                let mut proofs: Vec<Option<KzgProofNode>> = Vec::new();
+                for _ in 0..nodes {
+                    let mut flag = [0u8; 1];
+                    reader.read_exact(&mut flag)?;
+                    if flag[0] == 0 {
+                        proofs.push(None);
+                        continue;
+                    }
+                    let commitment = G1Affine::deserialize_compressed(&mut reader).map_err(serialization_error)?;
+                    let proof = G1Affine::deserialize_compressed(&mut reader).map_err(serialization_error)?;
+                    // written as an ark-serialize vector, a u64 length and the pairs
+                    let mut len = [0u8; 8];
+                    reader.read_exact(&mut len)?;
+                    let mut point: Vec<(F, F)> = Vec::new();
+                    for _ in 0..u64::from_le_bytes(len) {
+                        point.push(<(F, F)>::deserialize_compressed(&mut reader).map_err(serialization_error)?);
+                    }
+                    proofs.push(Some(KzgProofNode {
+                        commitment,
+                        proof,
+                        point,
+                    }));
+                }
+                ProofBody::Kzg(proofs)
+            }
+        };
+        Ok(ProofFile {
+            width,
+            depth,
+            indices,
+            body,
+        })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn serialization_error(error: ark_serialize::SerializationError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn read_header<R: Read>(reader: &mut R, magic: &[u8; 4]) -> io::Result<Backend> {
+    let mut header = [0u8; 6];
+    reader.read_exact(&mut header)?;
+    if header[..4] != magic[..] {
+        return Err(invalid_data("not a verkle tree file"));
+    }
+    if header[4] != VERSION {
+        return Err(invalid_data("unsupported file version"));
+    }
+    Backend::from_byte(header[5])
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    let value = u32::try_from(value).map_err(|_| invalid_data("length does not fit in u32"))?;
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes) as usize)
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_u32(writer, bytes.len())?;
+    writer.write_all(bytes)
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_u32(reader)?;
+    let mut bytes: Vec<u8> = Vec::new();
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the input ends inside a value"));
+    }
+    Ok(bytes)
+}
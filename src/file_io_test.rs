@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+    use std::io::ErrorKind;
+
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ec::AffineRepr;
+    use ark_serialize::CanonicalSerialize;
+
+    use crate::file_io::{read_values, DataFormat, ProofBody, ProofFile};
+    use crate::VerkleTree;
+
+    fn header(backend: u8, nodes: u32) -> Vec<u8> {
+        let mut bytes: Vec<u8> = b"VKPF".to_vec();
+        bytes.extend_from_slice(&[1, backend]);
+        // width, depth, no indices and the number of nodes
+        for word in [4u32, 1, 0, nodes] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_kzg_proof_round_trip() {
+        let datas: Vec<F> = (0..16u64).map(|i| F::from(3 * i + 11)).collect();
+        let tree = VerkleTree::new(&datas, 4).unwrap();
+        let proof = ProofFile {
+            width: 4,
+            depth: tree.depth(),
+            indices: vec![2, 9, 9],
+            body: ProofBody::Kzg(tree.proof(vec![2, 9, 9], &datas)),
+        };
+        let bytes = proof.to_bytes().unwrap();
+        let read = ProofFile::from_reader(&mut &bytes[..]).unwrap();
+        assert_eq!(read.indices, proof.indices);
+        assert_eq!(read.to_bytes().unwrap(), bytes);
+        // a truncated file is an error
+        assert!(ProofFile::from_reader(&mut &bytes[..bytes.len() - 1]).is_err());
+    }
+
+    // The counts and lengths are untrusted, a huge one in a short input fails without allocating it first
+    #[test]
+    fn test_lengths_beyond_the_input() {
+        let bytes = header(0, u32::MAX);
+        assert!(ProofFile::from_reader(&mut &bytes[..]).is_err());
+
+        let mut bytes = header(1, u32::MAX);
+        bytes.push(1);
+        G1Affine::generator().serialize_compressed(&mut bytes).unwrap();
+        G1Affine::generator().serialize_compressed(&mut bytes).unwrap();
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert!(ProofFile::from_reader(&mut &bytes[..]).is_err());
+
+        let path = std::env::temp_dir().join(format!("verkle-file-io-{}.bin", std::process::id()));
+        let mut bytes = u32::MAX.to_le_bytes().to_vec();
+        bytes.extend_from_slice(b"short");
+        fs::write(&path, &bytes).unwrap();
+        let error = read_values(&path, DataFormat::Binary).err().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}
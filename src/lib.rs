@@ -1,8 +1,31 @@
-pub use verkle_tree::{VerkleTree, VerkleProof, ProofNode};
+pub use verkle_tree::{VerkleTree, VerkleProof, ProofNode, KzgSetup};
 mod verkle_tree;
 
-pub use verkle_tree_point::{VerkleTree as VerkleTree_point, VerkleProof as VerkleProof_point, ProofNode as ProofNode_point};
+pub use verkle_tree_point::{VerkleTree as VerkleTree_point, VerkleProof as VerkleProof_point, ProofNode as ProofNode_point, TransitionProof, WriteBatch, TreeUpdate, LeafWrite, check_shape};
 mod verkle_tree_point;
+#[cfg(test)]
+mod verkle_tree_point_test;
+
+pub use merkle_tree::{MerkleTree, MerkleProof, MerkleTreeError};
+mod merkle_tree;
+#[cfg(test)]
+mod merkle_tree_test;
+
+pub use solidity_verifier::{KzgVerifierKey, VerifierError, verifier_contract, verifier_calldata};
+mod solidity_verifier;
+#[cfg(test)]
+mod solidity_verifier_test;
+
+pub mod file_io;
+#[cfg(test)]
+mod file_io_test;
+pub mod c_api;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(all(test, feature = "serde"))]
+mod serde_impl_test;
+#[cfg(feature = "python")]
+mod python;
 
 pub use pointproofs::pairings::Commitment as Commitment;
 pub use pointproofs::pairings::ProverParams as ProverParams;
@@ -1,164 +1,348 @@
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::BufRead;
-use std::io::BufReader;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use ark_bls12_381::{Fr as F, G1Affine};
+use ark_bls12_381::G1Affine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use pairing_plus::serdes::SerDes;
 
-use std::fs::OpenOptions;
-use std::io::Write;
+use pointproofs::pairings::param::{paramgen_from_seed, read_param};
+use pointproofs::pairings::{Commitment, ProverParams, VerifierParams};
 
-use rand::Rng;
-use rand::prelude::*;
-use rayon::vec;
+use verkle_tree::file_io::{from_hex, read_values, to_hex, values_to_field, Backend, DataFormat, ProofBody, ProofFile, TreeFile};
+use verkle_tree::{check_shape, KzgSetup, VerkleTree as VerkleTree_kzg, VerkleTree_point};
 
-mod verkle_tree_point;
-use verkle_tree_point::{VerkleTree as VerkleTree_point};
+// Only used without --params, parameters from a public seed are not secure
+const DEFAULT_SEED: &str = "This is our Favourite very very long Seed";
 
-mod verkle_tree;
-use verkle_tree::{VerkleTree as VerkleTree_kzg};
+const USAGE: &str = "verkle <command> [options]
 
+commands:
+    build   --input <file> --output <tree> [--format lines|csv|binary] [--backend pointproofs|kzg] [--width <n>] [--params <file>]
+    root    --tree <tree>
+    prove   --tree <tree> --indices <i,j,..> --output <proof> [--params <file>]
+    verify  --proof <proof> --root <hex> --values <file> [--format lines|csv|binary] [--params <file>]
+    update  --tree <tree> (--set <index>=<value> ... | --updates <csv file of index,value>) [--output <tree>] [--params <file>]
 
-//The following is to import data from a file in the root of the folder.
+The number of values has to be a power of the width (default 4, backend default pointproofs).
+--params is a pointproofs parameter file for n = width, or for the kzg backend a setup written by KzgSetup::to_bytes.
+Without it the parameters come from a public test seed.";
 
-fn get_receiver_data (size_input: usize) -> Vec<Vec<u8>>{
-    let file = File::open("Receiver.txt").expect("failed to open file");
-    let reader = BufReader::new(file);
+// The options of a command, every option is `--name value` and may be repeated
+struct Options {
+    values: HashMap<String, Vec<String>>,
+}
 
-    let mut input:Vec<Vec<u8>> = Vec::with_capacity(size_input);
-    for line in reader.lines() {
-       let paswd = line.expect("failed to unrap line");
-        if input.len()< size_input{
-            //input.push(paswd.as_bytes().to_vec());
-            input.push(paswd.into_bytes());
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut values: HashMap<String, Vec<String>> = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg.strip_prefix("--").ok_or_else(|| format!("unexpected argument {}", arg))?;
+            let value = args.next().ok_or_else(|| format!("missing value for --{}", name))?;
+            values.entry(name.to_string()).or_default().push(value.clone());
         }
-        else {
-            return input;
+        Ok(Options { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).and_then(|values| values.last()).map(|value| value.as_str())
+    }
+
+    fn all(&self, name: &str) -> &[String] {
+        self.values.get(name).map(|values| values.as_slice()).unwrap_or(&[])
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.get(name).ok_or_else(|| format!("missing option --{}", name))
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, String> {
+        self.required(name).map(PathBuf::from)
+    }
+
+    fn parsed<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.get(name) {
+            Some(value) => value.parse().map_err(|_| format!("invalid value {} for --{}", value, name)),
+            None => Ok(default),
         }
     }
-    input
 }
 
+fn parse_indices(list: &str) -> Result<Vec<usize>, String> {
+    list.split(',')
+        .map(|index| index.trim().parse().map_err(|_| format!("invalid index {}", index)))
+        .collect()
+}
 
-
-fn example_pointproofs (width:usize, input_len:usize, data: &Vec<Vec<u8>>) {
-    //println!("create parameters");
-    let (prover_params, verifier_params) =
-        pointproofs::pairings::param::paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
-    
-    //println!("start making tree");
-    let start = Instant::now();
-    let tree: VerkleTree_point = VerkleTree_point::new(data, width, prover_params).unwrap();
-    let tree_test= start.elapsed();
-   // println!("Tree is constructed");
-    
-    let indices: Vec<usize> = (0..=(input_len-1) )
-        .choose_multiple(&mut thread_rng(),(input_len as f64 *(0.2))as usize);
-    //println!("We'll start proving");
-    let startproof = Instant::now();
-    let proof = tree.proof(indices.clone(), data);
-    let endproof_test= startproof.elapsed();
-    //println!("We are done proving");
-
-    //println!("Commitment");
-    let root = tree.root_commitment().unwrap();
-    let depth = tree.depth();
-    //println!("commitment done");
-
-    //println!("data to verify");
-
-    let mut datas_verify: Vec<Vec<u8>> = Vec::new();
-    for i in indices.clone() {
-        datas_verify.push(data[i].clone());
-    }
-
-    //println!("start verify");
-    let startverify = Instant::now();
-    let b = VerkleTree_point::verify(root, proof.clone(), width, indices, depth, datas_verify, verifier_params);
-    let verify_test= startverify.elapsed();
-    //println!("end verify");
-
-    println!("POINTPROOF tree create {:?}", tree_test.as_millis());
-    println!("POINTPROOF proof time {:?}", endproof_test.as_millis());
-    println!("POINTPROOF verify time {:?}", verify_test.as_millis());
+fn io_error(path: &Path) -> impl Fn(std::io::Error) -> String + '_ {
+    move |error| format!("{}: {}", path.display(), error)
 }
 
+fn pointproofs_params(options: &Options, width: usize) -> Result<(ProverParams, VerifierParams), String> {
+    let (prover_params, verifier_params) = match options.get("params") {
+        Some(path) => {
+            let path = Path::new(path);
+            let mut file = File::open(path).map_err(io_error(path))?;
+            read_param(&mut file).map_err(|error| error.to_string())?
+        }
+        None => {
+            eprintln!("warning: no --params given, using parameters from a public seed");
+            paramgen_from_seed(DEFAULT_SEED, 0, width).map_err(|error| error.to_string())?
+        }
+    };
+    // The parameters have to be generated for vectors of exactly `width` values
+    if Commitment::new(&prover_params, &vec![Vec::<u8>::new(); width]).is_err() {
+        return Err(format!("the parameters are not for width {}", width));
+    }
+    Ok((prover_params, verifier_params))
+}
 
+fn kzg_setup(options: &Options, width: usize) -> Result<KzgSetup, String> {
+    let setup = match options.get("params") {
+        Some(path) => {
+            let path = Path::new(path);
+            let bytes = std::fs::read(path).map_err(io_error(path))?;
+            KzgSetup::from_bytes(&bytes).map_err(|error| format!("{}: {:?}", path.display(), error))?
+        }
+        None => {
+            eprintln!("warning: no --params given, using a setup from a public seed");
+            KzgSetup::from_seed(DEFAULT_SEED.as_bytes(), width)
+        }
+    };
+    if setup.width() != width {
+        return Err(format!("the setup is not for width {}", width));
+    }
+    Ok(setup)
+}
 
-fn example_kzg (width:usize, input_len:usize, data: &Vec<F>) {
-    //println!("start making tree");
-    //println!("data {:?}", data);
-    let start = Instant::now();
-    let tree: VerkleTree_kzg = VerkleTree_kzg::new(data, width).unwrap();
-    let tree_test= start.elapsed();
-   // println!("Tree is constructed");
-    
-    let indices: Vec<usize> = (0..=(input_len-1) )
-        .choose_multiple(&mut thread_rng(),(input_len as f64 *(0.2))as usize);
-    //let indices = vec![0];
-    //println!("We'll start proving");
-    let startproof = Instant::now();
-    let proof = tree.proof(indices.clone(), data);
-    let endproof_test= startproof.elapsed();
-    //println!("We are done proving");
-
-    //println!("Commitment");
-    let root = tree.root_commitment().unwrap();
-    let depth = tree.depth();
-    //println!("commitment done");
-
-    //println!("data to verify");
-
-    let mut datas_verify: Vec<F> = Vec::new();
-    for i in indices.clone() {
-        datas_verify.push(data[i].clone());
-    }
-
-    //println!("start verify");
-    let startverify = Instant::now();
-    let b = VerkleTree_kzg::verify(root, proof.clone(), width, indices, depth, datas_verify);
-    let verify_test= startverify.elapsed();
-    //println!("end verify");
-
-    println!("KZG tree create {:?}", tree_test.as_millis());
-    println!("KZG proof time {:?}", endproof_test.as_millis());
-    println!("KZG verify time {:?}", verify_test.as_millis());
+enum Tree {
+    Point(VerkleTree_point, VerifierParams),
+    Kzg(VerkleTree_kzg),
 }
 
+impl Tree {
+    fn build(backend: Backend, values: &[Vec<u8>], width: usize, options: &Options) -> Result<Self, String> {
+        check_shape(values.len(), width)?;
+        match backend {
+            Backend::Pointproofs => {
+                let (prover_params, verifier_params) = pointproofs_params(options, width)?;
+                let tree = VerkleTree_point::new(values, width, prover_params).map_err(|error| format!("{:?}", error))?;
+                Ok(Tree::Point(tree, verifier_params))
+            }
+            Backend::Kzg => {
+                let setup = kzg_setup(options, width)?;
+                let tree = VerkleTree_kzg::new_with_setup(&values_to_field(values), width, setup).map_err(|error| format!("{:?}", error))?;
+                Ok(Tree::Kzg(tree))
+            }
+        }
+    }
 
-fn main (){
-    println!("Hello world");
+    // Rebuilds a saved tree, the root has to match the stored one
+    fn load(file: &TreeFile, options: &Options) -> Result<Self, String> {
+        let tree = Self::build(file.backend, &file.values, file.width, options)?;
+        if tree.root()? != file.root {
+            return Err("the rebuilt root does not match the saved root, are these the parameters the tree was built with?".to_string());
+        }
+        Ok(tree)
+    }
 
-    // let datas_kzg = vec![F::from(15), F::from(23), F::from(37), F::from(48), F::from(52), F::from(66), F::from(73), F::from(84), F::from(91), F::from(104), F::from(113), F::from(128), F::from(137), F::from(141), F::from(155), F::from(164)];
+    fn root(&self) -> Result<Vec<u8>, String> {
+        match self {
+            Tree::Point(tree, _) => {
+                let root = tree.root_commitment().ok_or("empty tree")?;
+                Ok(VerkleTree_point::map_commitment_to_vec_u8(&root))
+            }
+            Tree::Kzg(tree) => {
+                let root = tree.root_commitment().ok_or("empty tree")?;
+                let mut bytes: Vec<u8> = Vec::new();
+                root.serialize_compressed(&mut bytes).map_err(|error| error.to_string())?;
+                Ok(bytes)
+            }
+        }
+    }
+}
 
-    // let width = 4;
+fn build(options: &Options) -> Result<(), String> {
+    let input = options.path("input")?;
+    let output = options.path("output")?;
+    let format: DataFormat = options.parsed("format", DataFormat::Lines)?;
+    let backend: Backend = options.parsed("backend", Backend::Pointproofs)?;
+    let width: usize = options.parsed("width", 4)?;
+
+    let values = read_values(&input, format).map_err(io_error(&input))?;
+    let tree = Tree::build(backend, &values, width, options)?;
+    let root = tree.root()?;
+    let file = TreeFile {
+        backend,
+        width,
+        values,
+        root,
+    };
+    file.write(&output).map_err(io_error(&output))?;
+    println!("{}", to_hex(&file.root));
+    Ok(())
+}
+
+fn root(options: &Options) -> Result<(), String> {
+    let path = options.path("tree")?;
+    let file = TreeFile::read(&path).map_err(io_error(&path))?;
+    println!("{}", to_hex(&file.root));
+    Ok(())
+}
 
-    // example_kzg(width, datas_kzg.len(), &datas_kzg);
+fn prove(options: &Options) -> Result<(), String> {
+    let path = options.path("tree")?;
+    let output = options.path("output")?;
+    let indices = parse_indices(options.required("indices")?)?;
+    let file = TreeFile::read(&path).map_err(io_error(&path))?;
+    if let Some(index) = indices.iter().find(|index| **index >= file.values.len()) {
+        return Err(format!("index {} is out of range, the tree has {} values", index, file.values.len()));
+    }
 
-    // println!("data length {}", datas.len());
-    let width = 4;
-    let input_len = usize::pow(2,10);
+    let (depth, body) = match Tree::load(&file, options)? {
+        Tree::Point(tree, _) => {
+            let proof = tree.open(&indices).map_err(|error| format!("{:?}", error))?;
+            (tree.depth(), ProofBody::Pointproofs(proof))
+        }
+        Tree::Kzg(tree) => {
+            let proof = tree.proof(indices.clone(), &values_to_field(&file.values));
+            (tree.depth(), ProofBody::Kzg(proof))
+        }
+    };
+    let proof = ProofFile {
+        width: file.width,
+        depth,
+        indices,
+        body,
+    };
+    proof.write(&output).map_err(io_error(&output))?;
+    Ok(())
+}
 
-    // Create synthetic data
-    let mut data: Vec<Vec<u8>> =Vec::new();
-    for _i in 0.. input_len{
-        let v = rand::thread_rng().gen_range(0..=input_len*input_len) as u8;
-        data.push(vec![v]);
+fn verify(options: &Options) -> Result<(), String> {
+    let path = options.path("proof")?;
+    let values_path = options.path("values")?;
+    let format: DataFormat = options.parsed("format", DataFormat::Lines)?;
+    let root = from_hex(options.required("root")?).ok_or("the root is not valid hex")?;
+    let proof = ProofFile::read(&path).map_err(io_error(&path))?;
+    let values = read_values(&values_path, format).map_err(io_error(&values_path))?;
+    if values.len() != proof.indices.len() {
+        return Err(format!("the proof is for {} values, got {}", proof.indices.len(), values.len()));
     }
 
-    // Create synthetic data kzg
-    let mut data_kzg: Vec<F> =Vec::new();
-    for _i in 0.. input_len{
-        let v = rand::thread_rng().gen_range(0..=input_len*input_len) as u8;
-        data_kzg.push(F::from(v));
+    let valid = match proof.body {
+        ProofBody::Pointproofs(tree_proof) => {
+            let root = Commitment::deserialize(&mut root.as_slice(), true).map_err(|error| error.to_string())?;
+            let (_, verifier_params) = pointproofs_params(options, proof.width)?;
+            VerkleTree_point::verify_proof(&root, &tree_proof, &proof.indices, &values, proof.width, proof.depth, &verifier_params)
+        }
+        ProofBody::Kzg(tree_proof) => {
+            let root = G1Affine::deserialize_compressed(root.as_slice()).map_err(|error| error.to_string())?;
+            let setup = kzg_setup(options, proof.width)?;
+            VerkleTree_kzg::verify(root, tree_proof, proof.width, proof.indices, proof.depth, values_to_field(&values), &setup)
+        }
+    };
+    if !valid {
+        return Err("the proof is not valid".to_string());
     }
+    println!("valid");
+    Ok(())
+}
 
-    let data: Vec<Vec<u8>> = get_receiver_data(input_len); 
-    println!("got data");
+fn update(options: &Options) -> Result<(), String> {
+    let path = options.path("tree")?;
+    let output = options.get("output").map_or(path.clone(), PathBuf::from);
+    let mut file = TreeFile::read(&path).map_err(io_error(&path))?;
+
+    let mut updates: Vec<(usize, Vec<u8>)> = Vec::new();
+    if let Some(updates_path) = options.get("updates") {
+        let updates_path = Path::new(updates_path);
+        let lines = read_values(updates_path, DataFormat::Lines).map_err(io_error(updates_path))?;
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            updates.push(parse_update(&String::from_utf8_lossy(line), ',')?);
+        }
+    }
+    for set in options.all("set") {
+        updates.push(parse_update(set, '=')?);
+    }
+    if updates.is_empty() {
+        return Err("no updates given, use --set or --updates".to_string());
+    }
+    if let Some((index, _)) = updates.iter().find(|(index, _)| *index >= file.values.len()) {
+        return Err(format!("index {} is out of range, the tree has {} values", index, file.values.len()));
+    }
 
-    for i in 0 .. 10 {
-        example_pointproofs(width, input_len, &data);
-        example_kzg(width, input_len, &data_kzg);
+    file.root = match Tree::load(&file, options)? {
+        Tree::Point(mut tree, verifier_params) => {
+            let mut batch = tree.batch();
+            for (index, value) in updates.iter() {
+                batch.insert(*index, value.clone()).map_err(|error| format!("{:?}", error))?;
+            }
+            batch.commit().map_err(|error| format!("{:?}", error))?;
+            Tree::Point(tree, verifier_params).root()?
+        }
+        Tree::Kzg(_) => {
+            // The KZG tree has no incremental updates, it is rebuilt
+            let mut values = file.values.clone();
+            for (index, value) in updates.iter() {
+                values[*index] = value.clone();
+            }
+            Tree::build(Backend::Kzg, &values, file.width, options)?.root()?
+        }
+    };
+    for (index, value) in updates {
+        file.values[index] = value;
     }
+    file.write(&output).map_err(io_error(&output))?;
+    println!("{}", to_hex(&file.root));
+    Ok(())
+}
+
+fn parse_update(update: &str, separator: char) -> Result<(usize, Vec<u8>), String> {
+    let (index, value) = update.split_once(separator).ok_or_else(|| format!("invalid update {}, expected index{}value", update, separator))?;
+    let index = index.trim().parse().map_err(|_| format!("invalid index {}", index))?;
+    Ok((index, value.as_bytes().to_vec()))
+}
+
+#[cfg(test)]
+mod main_test;
 
-}
\ No newline at end of file
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, options) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), Options::parse(rest)),
+        None => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let options = match options {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = match command {
+        "build" => build(&options),
+        "root" => root(&options),
+        "prove" => prove(&options),
+        "update" => update(&options),
+        "verify" => verify(&options),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("unknown command {}", command)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
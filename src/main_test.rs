@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    use verkle_tree::file_io::{to_hex, Backend, ProofBody, ProofFile, TreeFile};
+
+    use crate::{build, prove, update, verify, Options};
+
+    // A fresh directory per test, the tests run in parallel
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("verkle-cli-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn options(args: &[&str]) -> Options {
+        Options::parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>()).unwrap()
+    }
+
+    fn path(dir: &PathBuf, name: &str) -> String {
+        dir.join(name).to_str().unwrap().to_string()
+    }
+
+    fn write_lines(dir: &PathBuf, name: &str, lines: &[String]) -> String {
+        let file = path(dir, name);
+        fs::write(&file, lines.join("\n")).unwrap();
+        file
+    }
+
+    fn values(len: usize) -> Vec<String> {
+        (0..len).map(|i| format!("value {} of the tree", i)).collect()
+    }
+
+    // build -> save -> load -> prove -> verify, then the same with a wrong value and a wrong proof
+    fn round_trip(backend: &str, name: &str) {
+        let dir = test_dir(name);
+        let data = values(64);
+        let input = write_lines(&dir, "data.txt", &data);
+        let tree = path(&dir, "data.tree");
+        build(&options(&["--input", &input, "--output", &tree, "--backend", backend])).unwrap();
+
+        let file = TreeFile::read(&dir.join("data.tree")).unwrap();
+        assert_eq!(file.backend, backend.parse::<Backend>().unwrap());
+        let root = to_hex(&file.root);
+
+        let proof = path(&dir, "data.proof");
+        prove(&options(&["--tree", &tree, "--indices", "3,17,63", "--output", &proof])).unwrap();
+        let claimed = write_lines(&dir, "claimed.txt", &[data[3].clone(), data[17].clone(), data[63].clone()]);
+        verify(&options(&["--proof", &proof, "--root", &root, "--values", &claimed])).unwrap();
+
+        let wrong = write_lines(&dir, "wrong.txt", &[data[3].clone(), data[18].clone(), data[63].clone()]);
+        assert!(verify(&options(&["--proof", &proof, "--root", &root, "--values", &wrong])).is_err());
+
+        // the proof of another opened node in place of the last one
+        let mut tampered = ProofFile::read(&dir.join("data.proof")).unwrap();
+        match &mut tampered.body {
+            ProofBody::Pointproofs(tree_proof) => {
+                let last = tree_proof.proofs.len() - 1;
+                tree_proof.proofs[last].proof = tree_proof.proofs[last - 1].proof.clone();
+            }
+            ProofBody::Kzg(tree_proof) => {
+                let opened: Vec<usize> = (0..tree_proof.len()).filter(|i| tree_proof[*i].is_some()).collect();
+                let other = tree_proof[opened[opened.len() - 2]].as_ref().unwrap().proof;
+                tree_proof[opened[opened.len() - 1]].as_mut().unwrap().proof = other;
+            }
+        }
+        let tampered_path = path(&dir, "tampered.proof");
+        tampered.write(&dir.join("tampered.proof")).unwrap();
+        assert!(verify(&options(&["--proof", &tampered_path, "--root", &root, "--values", &claimed])).is_err());
+
+        // an update rebuilds the tree from the file, the new root proves the new value
+        update(&options(&["--tree", &tree, "--set", "17=new value"])).unwrap();
+        let root = to_hex(&TreeFile::read(&dir.join("data.tree")).unwrap().root);
+        prove(&options(&["--tree", &tree, "--indices", "17", "--output", &proof])).unwrap();
+        let updated = write_lines(&dir, "updated.txt", &["new value".to_string()]);
+        verify(&options(&["--proof", &proof, "--root", &root, "--values", &updated])).unwrap();
+        let old = write_lines(&dir, "old.txt", &[data[17].clone()]);
+        assert!(verify(&options(&["--proof", &proof, "--root", &root, "--values", &old])).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pointproofs_round_trip() {
+        round_trip("pointproofs", "pointproofs");
+    }
+
+    #[test]
+    fn test_kzg_round_trip() {
+        round_trip("kzg", "kzg");
+    }
+
+    #[test]
+    fn test_kzg_other_setup() {
+        let dir = test_dir("kzg-setup");
+        let data = values(16);
+        let input = write_lines(&dir, "data.txt", &data);
+        let tree = path(&dir, "data.tree");
+        build(&options(&["--input", &input, "--output", &tree, "--backend", "kzg"])).unwrap();
+        let root = to_hex(&TreeFile::read(&dir.join("data.tree")).unwrap().root);
+        let proof = path(&dir, "data.proof");
+        prove(&options(&["--tree", &tree, "--indices", "5", "--output", &proof])).unwrap();
+        let claimed = write_lines(&dir, "claimed.txt", &[data[5].clone()]);
+
+        // the proof does not verify against another setup, and the tree does not load with it
+        let setup = path(&dir, "other.setup");
+        fs::write(&setup, verkle_tree::KzgSetup::new(4).to_bytes()).unwrap();
+        assert!(verify(&options(&["--proof", &proof, "--root", &root, "--values", &claimed, "--params", &setup])).is_err());
+        assert!(prove(&options(&["--tree", &tree, "--indices", "5", "--output", &proof, "--params", &setup])).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::marker::PhantomData;
+
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+/* A Merkle tree with the same API as the Verkle trees, for comparisons and as a fallback without a trusted setup.
+    A node hashes its `width` children, width 2 is the usual binary tree. The hash function is a type parameter,
+    SHA-256 by default: `let tree: MerkleTree = MerkleTree::new(...)` or `MerkleTree::<Sha512>::new(...)`.
+    Leaves and inner nodes are hashed with a different prefix byte, so a leaf can not be passed off as a node */
+pub struct MerkleTree<D: Digest = Sha256> {
+    // The root is the first layer, the leaf hashes the last one
+    nodes: Vec<Vec<Vec<u8>>>,
+    width: usize,
+    hash: PhantomData<D>,
+}
+
+// The hashes of the children that can not be computed from the proven leaves, bottom up and left to right
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleProof {
+    pub hashes: Vec<Vec<u8>>,
+}
+
+impl MerkleProof {
+    pub fn size_in_bytes(&self) -> usize {
+        self.hashes.iter().map(|hash| hash.len()).sum()
+    }
+}
+
+impl<D: Digest> MerkleTree<D> {
+    // Initialize a new tree, the number of values has to be a power of the width
+    pub fn new(datas: &[Vec<u8>], width: usize) -> Result<Self, MerkleTreeError> {
+        if datas.is_empty() || width < 2 {
+            return Err(MerkleTreeError::BuildError);
+        }
+        let mut leaves = 1;
+        while leaves < datas.len() {
+            leaves *= width;
+        }
+        if leaves != datas.len() {
+            return Err(MerkleTreeError::BuildError);
+        }
+
+        let mut layer: Vec<Vec<u8>> = datas.par_iter().map(|data| Self::hash_leaf(data)).collect();
+        let mut nodes: Vec<Vec<Vec<u8>>> = vec![layer.clone()];
+        while layer.len() > 1 {
+            layer = layer.par_chunks(width).map(Self::hash_node).collect();
+            nodes.push(layer.clone());
+        }
+        // to get the root as first index.
+        nodes.reverse();
+        Ok(MerkleTree {
+            nodes,
+            width,
+            hash: PhantomData,
+        })
+    }
+
+    fn hash_leaf(value: &[u8]) -> Vec<u8> {
+        let mut hasher = D::new();
+        hasher.update([0u8]);
+        hasher.update(value);
+        hasher.finalize().to_vec()
+    }
+
+    fn hash_node(children: &[Vec<u8>]) -> Vec<u8> {
+        let mut hasher = D::new();
+        hasher.update([1u8]);
+        for child in children {
+            hasher.update(child);
+        }
+        hasher.finalize().to_vec()
+    }
+
+    // The number of layers, a tree of one leaf has depth 1 like the Verkle trees
+    pub fn depth(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn root_commitment(&self) -> Option<Vec<u8>> {
+        self.nodes.first().and_then(|layer| layer.first()).cloned()
+    }
+
+    // Replaces the value at `index` and rehashes its path
+    pub fn update(&mut self, index: usize, value: &[u8]) -> Result<(), MerkleTreeError> {
+        let depth = self.depth();
+        if index >= self.nodes[depth - 1].len() {
+            return Err(MerkleTreeError::InvalidIndex);
+        }
+        let mut position = index;
+        self.nodes[depth - 1][position] = Self::hash_leaf(value);
+        for layer in (0..depth - 1).rev() {
+            let first = position - position % self.width;
+            self.nodes[layer][position / self.width] = Self::hash_node(&self.nodes[layer + 1][first..first + self.width]);
+            position /= self.width;
+        }
+        Ok(())
+    }
+
+    /* A proof for several indices at once. Hashes that are shared by the paths, or that the verifier
+        computes from the proven values, are only included once or not at all. Duplicate indices are allowed */
+    pub fn proof(&self, indices: &[usize]) -> Result<MerkleProof, MerkleTreeError> {
+        let depth = self.depth();
+        if indices.is_empty() || indices.iter().any(|index| *index >= self.nodes[depth - 1].len()) {
+            return Err(MerkleTreeError::InvalidIndex);
+        }
+        let mut known: BTreeSet<usize> = indices.iter().cloned().collect();
+        let mut hashes: Vec<Vec<u8>> = Vec::new();
+        for layer in (1..depth).rev() {
+            let parents: BTreeSet<usize> = known.iter().map(|position| position / self.width).collect();
+            for parent in parents.iter() {
+                for child in parent * self.width..(parent + 1) * self.width {
+                    if !known.contains(&child) {
+                        hashes.push(self.nodes[layer][child].clone());
+                    }
+                }
+            }
+            known = parents;
+        }
+        Ok(MerkleProof { hashes })
+    }
+
+    // Verifies a proof from proof(indices) against the root, values[i] is the claimed value at indices[i]
+    pub fn verify(root: &[u8], proof: &MerkleProof, indices: &[usize], values: &[Vec<u8>], width: usize, depth: usize) -> bool {
+        if indices.is_empty() || indices.len() != values.len() || width < 2 || depth == 0 {
+            return false;
+        }
+        let leaves = match width.checked_pow((depth - 1) as u32) {
+            Some(leaves) => leaves,
+            None => return false,
+        };
+        let mut known: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+        for (index, value) in indices.iter().zip(values.iter()) {
+            if *index >= leaves {
+                return false;
+            }
+            let hash = Self::hash_leaf(value);
+            if *known.entry(*index).or_insert_with(|| hash.clone()) != hash {
+                return false;
+            }
+        }
+
+        let mut hashes = proof.hashes.iter();
+        for _ in 1..depth {
+            let parents: BTreeSet<usize> = known.keys().map(|position| position / width).collect();
+            let mut parent_hashes: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+            for parent in parents {
+                let mut children: Vec<Vec<u8>> = Vec::with_capacity(width);
+                for child in parent * width..(parent + 1) * width {
+                    match known.get(&child).or_else(|| hashes.next()) {
+                        Some(hash) => children.push(hash.clone()),
+                        None => return false,
+                    }
+                }
+                parent_hashes.insert(parent, Self::hash_node(&children));
+            }
+            known = parent_hashes;
+        }
+        hashes.next().is_none() && known.get(&0).map(|hash| hash.as_slice()) == Some(root)
+    }
+}
+
+#[derive(Debug)]
+pub enum MerkleTreeError {
+    BuildError,
+    InvalidIndex,
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+
+    use crate::MerkleTree;
+    use sha2::{Sha256, Sha512};
+
+    fn random_data(len: usize) -> Vec<Vec<u8>> {
+        (0..len).map(|i| format!("this is message number {}", i).into_bytes()).collect()
+    }
+
+    #[test]
+    fn test_build() {
+        let datas = random_data(64);
+        for width in [2, 4, 8, 64] {
+            let tree: MerkleTree = MerkleTree::new(&datas, width).unwrap();
+            assert_eq!(width.pow((tree.depth() - 1) as u32), datas.len());
+            assert_eq!(tree.root_commitment().unwrap().len(), 32);
+        }
+        assert!(MerkleTree::<Sha256>::new(&datas, 3).is_err());
+        assert!(MerkleTree::<Sha256>::new(&datas[..48], 4).is_err());
+        assert!(MerkleTree::<Sha256>::new(&[], 4).is_err());
+
+        let tree = MerkleTree::<Sha512>::new(&datas, 4).unwrap();
+        assert_eq!(tree.root_commitment().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn test_proof() {
+        let width = 4;
+        let datas = random_data(64);
+        let tree: MerkleTree = MerkleTree::new(&datas, width).unwrap();
+        let root = tree.root_commitment().unwrap();
+        let depth = tree.depth();
+
+        let single = tree.proof(&[17]).unwrap();
+        assert_eq!(single.hashes.len(), (width - 1) * (depth - 1));
+        assert!(<MerkleTree>::verify(&root, &single, &[17], &datas[17..18], width, depth));
+
+        // siblings of the proven leaves and shared hashes are not repeated
+        let indices = [0, 1, 2, 3, 17, 17, 63];
+        let values: Vec<Vec<u8>> = indices.iter().map(|i| datas[*i].clone()).collect();
+        let multi = tree.proof(&indices).unwrap();
+        assert!(multi.hashes.len() < 3 * single.hashes.len());
+        assert!(<MerkleTree>::verify(&root, &multi, &indices, &values, width, depth));
+
+        let mut wrong_values = values.clone();
+        wrong_values[4] = b"not the value".to_vec();
+        assert!(!<MerkleTree>::verify(&root, &multi, &indices, &wrong_values, width, depth));
+        assert!(!<MerkleTree>::verify(&root, &multi, &indices[..4], &values[..4], width, depth));
+        assert!(!<MerkleTree>::verify(&root, &multi, &indices, &values, width, depth + 1));
+        assert!(!MerkleTree::<Sha512>::verify(&root, &multi, &indices, &values, width, depth));
+        assert!(tree.proof(&[64]).is_err());
+    }
+
+    #[test]
+    fn test_update() {
+        let width = 2;
+        let mut datas = random_data(32);
+        let mut tree: MerkleTree = MerkleTree::new(&datas, width).unwrap();
+        tree.update(5, b"new value 5").unwrap();
+        datas[5] = b"new value 5".to_vec();
+        let rebuilt: MerkleTree = MerkleTree::new(&datas, width).unwrap();
+        assert_eq!(tree.root_commitment(), rebuilt.root_commitment());
+        assert!(tree.update(32, b"out of range").is_err());
+    }
+}
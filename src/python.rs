@@ -0,0 +1,250 @@
+use std::borrow::Cow;
+use std::fs::File;
+
+use ark_bls12_381::G1Affine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use pairing_plus::serdes::SerDes;
+use pointproofs::pairings::param;
+use pointproofs::pairings::{Commitment, ProverParams as PointproofsProverParams, VerifierParams as PointproofsVerifierParams};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::file_io::{values_to_field, ProofBody, ProofFile};
+use crate::verkle_tree::{KzgSetup, VerkleTree as KzgVerkleTree};
+use crate::verkle_tree_point::{check_shape, VerkleTree as PointVerkleTree};
+
+/* Python bindings, built with `maturin develop --features python` (see pyproject.toml).
+    Roots and proofs cross the boundary as bytes: roots are serialized commitments,
+    proofs use the proof file format of the command line tool */
+
+fn value_error<E: std::fmt::Debug>(error: E) -> PyErr {
+    PyValueError::new_err(format!("{:?}", error))
+}
+
+#[pyclass(name = "ProverParams", module = "verkle_tree")]
+#[derive(Clone)]
+struct PyProverParams {
+    inner: PointproofsProverParams,
+}
+
+#[pymethods]
+impl PyProverParams {
+    fn to_bytes(&self) -> PyResult<Cow<'static, [u8]>> {
+        let mut bytes: Vec<u8> = Vec::new();
+        self.inner.serialize(&mut bytes, true).map_err(value_error)?;
+        Ok(Cow::Owned(bytes))
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        let inner = PointproofsProverParams::deserialize(&mut &bytes[..], true).map_err(value_error)?;
+        Ok(PyProverParams { inner })
+    }
+}
+
+#[pyclass(name = "VerifierParams", module = "verkle_tree")]
+#[derive(Clone)]
+struct PyVerifierParams {
+    inner: PointproofsVerifierParams,
+}
+
+#[pymethods]
+impl PyVerifierParams {
+    fn to_bytes(&self) -> PyResult<Cow<'static, [u8]>> {
+        let mut bytes: Vec<u8> = Vec::new();
+        self.inner.serialize(&mut bytes, true).map_err(value_error)?;
+        Ok(Cow::Owned(bytes))
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        let inner = PointproofsVerifierParams::deserialize(&mut &bytes[..], true).map_err(value_error)?;
+        Ok(PyVerifierParams { inner })
+    }
+}
+
+// Parameters for vectors of n values, n is the width of the tree
+#[pyfunction]
+#[pyo3(signature = (seed, n, ciphersuite = 0))]
+fn paramgen_from_seed(seed: &[u8], n: usize, ciphersuite: u8) -> PyResult<(PyProverParams, PyVerifierParams)> {
+    let (prover, verifier) = param::paramgen_from_seed(seed, ciphersuite, n).map_err(value_error)?;
+    Ok((PyProverParams { inner: prover }, PyVerifierParams { inner: verifier }))
+}
+
+// Parameters from a file written by pointproofs-paramgen
+#[pyfunction]
+fn read_param(path: &str) -> PyResult<(PyProverParams, PyVerifierParams)> {
+    let mut file = File::open(path).map_err(|error| PyIOError::new_err(error.to_string()))?;
+    let (prover, verifier) = param::read_param(&mut file).map_err(value_error)?;
+    Ok((PyProverParams { inner: prover }, PyVerifierParams { inner: verifier }))
+}
+
+// The setup of a KZG tree, the verifier needs the one the tree was built with
+#[pyclass(name = "KzgSetup", module = "verkle_tree")]
+#[derive(Clone)]
+struct PyKzgSetup {
+    inner: KzgSetup,
+}
+
+#[pymethods]
+impl PyKzgSetup {
+    // From a random tau, or from a seed which is only fine for testing
+    #[new]
+    #[pyo3(signature = (width, seed = None))]
+    fn new(width: usize, seed: Option<&[u8]>) -> PyResult<Self> {
+        if width < 2 {
+            return Err(PyValueError::new_err("the width has to be at least 2"));
+        }
+        let inner = match seed {
+            Some(seed) => KzgSetup::from_seed(seed, width),
+            None => KzgSetup::new(width),
+        };
+        Ok(PyKzgSetup { inner })
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.inner.width()
+    }
+
+    fn to_bytes(&self) -> Cow<'static, [u8]> {
+        Cow::Owned(self.inner.to_bytes())
+    }
+
+    #[staticmethod]
+    fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        let inner = KzgSetup::from_bytes(bytes).map_err(value_error)?;
+        Ok(PyKzgSetup { inner })
+    }
+}
+
+fn proof_from_bytes(bytes: &[u8], indices: &[usize], width: usize, depth: usize) -> PyResult<Option<ProofBody>> {
+    let proof = ProofFile::from_reader(&mut &bytes[..]).map_err(|error| PyValueError::new_err(error.to_string()))?;
+    // a proof for other indices or another shape does not prove the claimed values
+    if proof.indices != indices || proof.width != width || proof.depth != depth {
+        return Ok(None);
+    }
+    Ok(Some(proof.body))
+}
+
+#[pyclass(name = "PointproofsTree", module = "verkle_tree")]
+struct PyPointproofsTree {
+    inner: PointVerkleTree,
+}
+
+#[pymethods]
+impl PyPointproofsTree {
+    // The number of values has to be a power of the width, the parameters are for `width` values
+    #[new]
+    fn new(values: Vec<Vec<u8>>, width: usize, prover_params: &PyProverParams) -> PyResult<Self> {
+        check_shape(values.len(), width).map_err(PyValueError::new_err)?;
+        let inner = PointVerkleTree::new(&values, width, prover_params.inner.clone()).map_err(value_error)?;
+        Ok(PyPointproofsTree { inner })
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.inner.width()
+    }
+
+    #[getter]
+    fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+
+    fn root(&self) -> PyResult<Cow<'static, [u8]>> {
+        let root = self.inner.root_commitment().ok_or_else(|| PyValueError::new_err("empty tree"))?;
+        Ok(Cow::Owned(PointVerkleTree::map_commitment_to_vec_u8(&root)))
+    }
+
+    fn prove(&self, indices: Vec<usize>) -> PyResult<Cow<'static, [u8]>> {
+        let proof = self.inner.open(&indices).map_err(value_error)?;
+        let file = ProofFile {
+            width: self.inner.width(),
+            depth: self.inner.depth(),
+            indices,
+            body: ProofBody::Pointproofs(proof),
+        };
+        Ok(Cow::Owned(file.to_bytes().map_err(|error| PyValueError::new_err(error.to_string()))?))
+    }
+
+    #[staticmethod]
+    fn verify(root: &[u8], proof: &[u8], indices: Vec<usize>, values: Vec<Vec<u8>>, width: usize, depth: usize, verifier_params: &PyVerifierParams) -> PyResult<bool> {
+        let root = Commitment::deserialize(&mut &root[..], true).map_err(value_error)?;
+        match proof_from_bytes(proof, &indices, width, depth)? {
+            Some(ProofBody::Pointproofs(proof)) => Ok(PointVerkleTree::verify_proof(&root, &proof, &indices, &values, width, depth, &verifier_params.inner)),
+            _ => Ok(false),
+        }
+    }
+}
+
+#[pyclass(name = "KzgTree", module = "verkle_tree")]
+struct PyKzgTree {
+    inner: KzgVerkleTree,
+    values: Vec<Vec<u8>>,
+    width: usize,
+}
+
+#[pymethods]
+impl PyKzgTree {
+    // The values are read as little endian numbers modulo the field order, the setup is for `width` values
+    #[new]
+    fn new(values: Vec<Vec<u8>>, width: usize, setup: &PyKzgSetup) -> PyResult<Self> {
+        check_shape(values.len(), width).map_err(PyValueError::new_err)?;
+        let inner = KzgVerkleTree::new_with_setup(&values_to_field(&values), width, setup.inner.clone()).map_err(value_error)?;
+        Ok(PyKzgTree { inner, values, width })
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[getter]
+    fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+
+    fn root(&self) -> PyResult<Cow<'static, [u8]>> {
+        let root = self.inner.root_commitment().ok_or_else(|| PyValueError::new_err("empty tree"))?;
+        let mut bytes: Vec<u8> = Vec::new();
+        root.serialize_compressed(&mut bytes).map_err(value_error)?;
+        Ok(Cow::Owned(bytes))
+    }
+
+    fn prove(&self, indices: Vec<usize>) -> PyResult<Cow<'static, [u8]>> {
+        if indices.is_empty() || indices.iter().any(|index| *index >= self.values.len()) {
+            return Err(PyValueError::new_err("index out of range"));
+        }
+        let proof = self.inner.proof(indices.clone(), &values_to_field(&self.values));
+        let file = ProofFile {
+            width: self.width,
+            depth: self.inner.depth(),
+            indices,
+            body: ProofBody::Kzg(proof),
+        };
+        Ok(Cow::Owned(file.to_bytes().map_err(|error| PyValueError::new_err(error.to_string()))?))
+    }
+
+    #[staticmethod]
+    fn verify(root: &[u8], proof: &[u8], indices: Vec<usize>, values: Vec<Vec<u8>>, width: usize, depth: usize, setup: &PyKzgSetup) -> PyResult<bool> {
+        let root = G1Affine::deserialize_compressed(root).map_err(value_error)?;
+        match proof_from_bytes(proof, &indices, width, depth)? {
+            Some(ProofBody::Kzg(proof)) => Ok(KzgVerkleTree::verify(root, proof, width, indices, depth, values_to_field(&values), &setup.inner)),
+            _ => Ok(false),
+        }
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "verkle_tree")]
+fn verkle_tree_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyProverParams>()?;
+    m.add_class::<PyVerifierParams>()?;
+    m.add_class::<PyKzgSetup>()?;
+    m.add_class::<PyPointproofsTree>()?;
+    m.add_class::<PyKzgTree>()?;
+    m.add_function(wrap_pyfunction!(paramgen_from_seed, m)?)?;
+    m.add_function(wrap_pyfunction!(read_param, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use ark_bls12_381::{Fr as F, G1Affine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::de::{Error as DeError, SeqAccess, Visitor};
+use serde::ser::{Error as SerError, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::file_io::{from_hex, to_hex};
+use crate::verkle_tree::ProofNode as KzgProofNode;
+
+/* serde for the tree proofs, enabled with the `serde` feature. Like the pointproofs types,
+    byte strings are hex in human-readable formats and raw bytes in binary ones.
+    A KZG proof node is its compressed ark encoding, which is validated when it is read */
+
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&to_hex(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string or a byte string")
+    }
+
+    fn visit_str<E: DeError>(self, v: &str) -> Result<Vec<u8>, E> {
+        from_hex(v).ok_or_else(|| E::custom("invalid hex string"))
+    }
+
+    fn visit_bytes<E: DeError>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: DeError>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor).map(Bytes)
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor).map(Bytes)
+        }
+    }
+}
+
+// For `#[serde(with = ...)]` on a Vec<u8>
+pub(crate) mod bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        Bytes(value.to_vec()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        Bytes::deserialize(deserializer).map(|bytes| bytes.0)
+    }
+}
+
+// For `#[serde(with = ...)]` on a Vec<Vec<u8>>
+pub(crate) mod byte_vecs {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(values: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(values.len()))?;
+        for value in values {
+            seq.serialize_element(&Bytes(value.clone()))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<Bytes>::deserialize(deserializer).map(|values| values.into_iter().map(|bytes| bytes.0).collect())
+    }
+}
+
+impl Serialize for KzgProofNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut buf: Vec<u8> = Vec::new();
+        (self.commitment, self.proof, &self.point)
+            .serialize_compressed(&mut buf)
+            .map_err(S::Error::custom)?;
+        Bytes(buf).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KzgProofNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf = Bytes::deserialize(deserializer)?.0;
+        let mut reader = buf.as_slice();
+        let (commitment, proof, point) = <(G1Affine, G1Affine, Vec<(F, F)>)>::deserialize_compressed(&mut reader).map_err(D::Error::custom)?;
+        if !reader.is_empty() {
+            return Err(D::Error::custom("trailing bytes"));
+        }
+        Ok(KzgProofNode { commitment, proof, point })
+    }
+}
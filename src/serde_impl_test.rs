@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+
+    use crate::{paramgen_from_seed, Commitment, ProofNode, VerkleProof_point, VerkleTree, VerkleTree_point};
+    use ark_bls12_381::Fr as F;
+
+    fn random_data(len: usize) -> Vec<Vec<u8>> {
+        (0..len).map(|i| format!("this is message number {}", i).into_bytes()).collect()
+    }
+
+    #[test]
+    fn test_serde_point_proof() {
+        let width = 4;
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        let datas = random_data(width.pow(3));
+        let tree = VerkleTree_point::new(&datas, width, prover_params).unwrap();
+        let root = tree.root_commitment().unwrap();
+        let indices = vec![0, 3, 17, 63];
+        let values: Vec<Vec<u8>> = indices.iter().map(|i| datas[*i].clone()).collect();
+        let proof = tree.open(&indices).unwrap();
+
+        // JSON has hex strings for the commitments, proofs and values
+        let json = serde_json::to_string(&(&root, &proof)).unwrap();
+        assert!(json.contains(&crate::file_io::to_hex(&datas[17])));
+        let (root_recover, proof_recover): (Commitment, VerkleProof_point) = serde_json::from_str(&json).unwrap();
+        assert_eq!(root_recover, root);
+        assert!(VerkleTree_point::verify_proof(&root_recover, &proof_recover, &indices, &values, width, 3, &verifier_params));
+
+        let mut cbor: Vec<u8> = Vec::new();
+        ciborium::ser::into_writer(&proof, &mut cbor).unwrap();
+        assert!(cbor.len() < json.len());
+        let proof_recover: VerkleProof_point = ciborium::de::from_reader(&cbor[..]).unwrap();
+        assert!(VerkleTree_point::verify_proof(&root, &proof_recover, &indices, &values, width, 3, &verifier_params));
+    }
+
+    #[test]
+    fn test_serde_kzg_proof() {
+        let width = 4;
+        let datas: Vec<F> = (0..width.pow(2)).map(|i| F::from(3 * i as u64 + 11)).collect();
+        let tree = VerkleTree::new(&datas, width).unwrap();
+        let proof = tree.proof(vec![2, 9], &datas);
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let proof_recover: Vec<Option<ProofNode>> = serde_json::from_str(&json).unwrap();
+        let mut cbor: Vec<u8> = Vec::new();
+        ciborium::ser::into_writer(&proof, &mut cbor).unwrap();
+        let proof_cbor: Vec<Option<ProofNode>> = ciborium::de::from_reader(&cbor[..]).unwrap();
+        for recovered in [proof_recover, proof_cbor] {
+            assert_eq!(recovered.len(), proof.len());
+            for (node, node_recover) in proof.iter().zip(recovered.iter()) {
+                assert_eq!(node.is_some(), node_recover.is_some());
+                if let (Some(node), Some(node_recover)) = (node, node_recover) {
+                    assert_eq!(node.commitment, node_recover.commitment);
+                    assert_eq!(node.proof, node_recover.proof);
+                    assert_eq!(node.point, node_recover.point);
+                }
+            }
+        }
+
+        // the compressed points are validated, here both the infinity and the sign flag of the commitment are set
+        let mut invalid = json.clone();
+        let start = invalid.find('"').unwrap() + 1;
+        invalid.replace_range(start + 94..start + 96, "ff");
+        assert!(serde_json::from_str::<Vec<Option<ProofNode>>>(&invalid).is_err());
+        assert!(serde_json::from_str::<Vec<Option<ProofNode>>>("[\"00\"]").is_err());
+    }
+}
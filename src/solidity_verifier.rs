@@ -0,0 +1,466 @@
+use ark_bls12_381::{Fq, Fr as F, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, One, PrimeField};
+use sha3::{Digest, Keccak256};
+
+use crate::file_io::to_hex;
+use crate::verkle_tree::{opened_nodes, KzgSetup, ProofNode, VerkleTree};
+
+/* Verification of KZG tree proofs (VerkleTree::proof) on chain, with the EIP-2537 BLS12-381 precompiles.
+    verifier_contract emits a Solidity contract for one tree width and setup, verifier_calldata the input
+    of its `verify(bytes,uint256,uint256[],uint256[],bytes)` function for a proof.
+    KzgVerifierKey::verify is the same check in Rust.
+
+    Every proven node opens its polynomial p at the points (x_i, y_i) with one multi-point proof pi:
+        e(pi, [Z(tau)]_2) = e(C - [I(tau)]_1, g2)
+    where Z is the vanishing polynomial of the x_i and I the polynomial interpolating the points.
+    The calldata carries the coefficients of I and Z, the contract only checks them at the x_i */
+
+// The setup of the tree: [tau^i]_1 for i < width and [tau^i]_2 for i <= width
+#[derive(Debug, Clone)]
+pub struct KzgVerifierKey {
+    pub g1_powers: Vec<G1Affine>,
+    pub g2_powers: Vec<G2Affine>,
+}
+
+const SELECTOR: &str = "verify(bytes,uint256,uint256[],uint256[],bytes)";
+
+// Layer, position, commitment, proof and the number of points, see the contract
+const NODE_HEADER: usize = 352;
+
+#[derive(Debug)]
+pub enum VerifierError {
+    InvalidKey,
+    InvalidProof,
+}
+
+impl From<&KzgSetup> for KzgVerifierKey {
+    fn from(setup: &KzgSetup) -> Self {
+        KzgVerifierKey {
+            g1_powers: setup.g1_powers.clone(),
+            g2_powers: setup.g2_powers.clone(),
+        }
+    }
+}
+
+impl KzgVerifierKey {
+    // The key for the setup the tree was built with
+    pub fn from_tree(tree: &VerkleTree) -> Self {
+        Self::from(tree.setup())
+    }
+
+    fn check(&self, width: usize) -> Result<(), VerifierError> {
+        if width < 2 || self.g1_powers.len() != width || self.g2_powers.len() != width + 1 {
+            return Err(VerifierError::InvalidKey);
+        }
+        Ok(())
+    }
+
+    /* Same rules as the contract: the first proven node is the root, every other proven node is opened in its parent
+        at its own commitment, every claimed value is opened in its leaf node, and all openings are valid */
+    pub fn verify(&self, root: &G1Affine, proofs: &[Option<ProofNode>], indices: &[usize], values: &[F], width: usize, depth: usize) -> bool {
+        let setup = KzgSetup {
+            g1_powers: self.g1_powers.clone(),
+            g2_powers: self.g2_powers.clone(),
+        };
+        VerkleTree::verify_proofs(root, proofs, indices, values, width, depth, &setup)
+    }
+}
+
+/* Encodings of EIP-2537: a base field element is 64 bytes big endian (16 zero bytes first),
+    G1 is x || y, G2 is x.c0 || x.c1 || y.c0 || y.c1 and the point at infinity is all zeros */
+fn encode_fq(value: &Fq, out: &mut Vec<u8>) {
+    out.extend_from_slice(&[0u8; 16]);
+    out.extend_from_slice(&value.into_bigint().to_bytes_be());
+}
+
+fn encode_g1(point: &G1Affine, out: &mut Vec<u8>) {
+    if point.is_zero() {
+        out.extend_from_slice(&[0u8; 128]);
+        return;
+    }
+    encode_fq(&point.x, out);
+    encode_fq(&point.y, out);
+}
+
+fn encode_g2(point: &G2Affine, out: &mut Vec<u8>) {
+    if point.is_zero() {
+        out.extend_from_slice(&[0u8; 256]);
+        return;
+    }
+    encode_fq(&point.x.c0, out);
+    encode_fq(&point.x.c1, out);
+    encode_fq(&point.y.c0, out);
+    encode_fq(&point.y.c1, out);
+}
+
+fn encode_fr(value: &F, out: &mut Vec<u8>) {
+    out.extend_from_slice(&value.into_bigint().to_bytes_be());
+}
+
+fn encode_word(value: usize, out: &mut Vec<u8>) {
+    out.extend_from_slice(&[0u8; 24]);
+    out.extend_from_slice(&(value as u64).to_be_bytes());
+}
+
+// The ABI encoded call of verify(root, depth, indices, values, proof) for a proof from VerkleTree::proof
+pub fn verifier_calldata(root: &G1Affine, proofs: &[Option<ProofNode>], indices: &[usize], values: &[F], width: usize, depth: usize) -> Result<Vec<u8>, VerifierError> {
+    if indices.len() != values.len() {
+        return Err(VerifierError::InvalidProof);
+    }
+    let mut proof: Vec<u8> = Vec::new();
+    for node in opened_nodes(proofs, width, depth).ok_or(VerifierError::InvalidProof)? {
+        encode_word(node.layer, &mut proof);
+        encode_word(node.position, &mut proof);
+        encode_g1(&node.node.commitment, &mut proof);
+        encode_g1(&node.node.proof, &mut proof);
+        encode_word(node.points.len(), &mut proof);
+        for (x, _) in node.points.iter() {
+            encode_fr(x, &mut proof);
+        }
+        for (_, y) in node.points.iter() {
+            encode_fr(y, &mut proof);
+        }
+        // I has degree < k, Z is monic of degree k and its leading coefficient is left out
+        for c in node.interpolation.iter().chain(node.vanishing[..node.points.len()].iter()) {
+            encode_fr(c, &mut proof);
+        }
+    }
+    let mut root_bytes: Vec<u8> = Vec::new();
+    encode_g1(root, &mut root_bytes);
+
+    // head: the offsets of the dynamic arguments and the depth, then the tails in order
+    let mut calldata: Vec<u8> = Keccak256::digest(SELECTOR.as_bytes())[..4].to_vec();
+    let root_offset = 5 * 32;
+    let indices_offset = root_offset + 32 + root_bytes.len();
+    let values_offset = indices_offset + 32 * (indices.len() + 1);
+    let proof_offset = values_offset + 32 * (values.len() + 1);
+    encode_word(root_offset, &mut calldata);
+    encode_word(depth, &mut calldata);
+    encode_word(indices_offset, &mut calldata);
+    encode_word(values_offset, &mut calldata);
+    encode_word(proof_offset, &mut calldata);
+    encode_word(root_bytes.len(), &mut calldata);
+    calldata.extend_from_slice(&root_bytes);
+    encode_word(indices.len(), &mut calldata);
+    for index in indices {
+        encode_word(*index, &mut calldata);
+    }
+    encode_word(values.len(), &mut calldata);
+    for value in values {
+        encode_fr(value, &mut calldata);
+    }
+    // every part of the proof is a multiple of 32 bytes, there is no padding
+    encode_word(proof.len(), &mut calldata);
+    calldata.extend_from_slice(&proof);
+    Ok(calldata)
+}
+
+// The Solidity source of a verifier for trees of `width` committed with the setup of `key`
+pub fn verifier_contract(key: &KzgVerifierKey, width: usize) -> Result<String, VerifierError> {
+    key.check(width)?;
+    let mut g1_powers: Vec<u8> = Vec::new();
+    for point in key.g1_powers.iter() {
+        encode_g1(point, &mut g1_powers);
+    }
+    let mut g2_powers: Vec<u8> = Vec::new();
+    for point in key.g2_powers.iter() {
+        encode_g2(point, &mut g2_powers);
+    }
+    let mut neg_g2: Vec<u8> = Vec::new();
+    encode_g2(&(-key.g2_powers[0].into_group()).into_affine(), &mut neg_g2);
+    let mut q: Vec<u8> = Vec::new();
+    encode_fq(&-Fq::one(), &mut q);
+    // encode_fq(q - 1) + 1, the modulus itself is not a field element
+    let last = q.len() - 1;
+    q[last] += 1;
+
+    Ok(CONTRACT_TEMPLATE
+        .replace("{WIDTH}", &width.to_string())
+        .replace("{NODE_HEADER}", &NODE_HEADER.to_string())
+        .replace("{Q}", &to_hex(&q))
+        .replace("{G1_POWERS}", &to_hex(&g1_powers))
+        .replace("{G2_POWERS}", &to_hex(&g2_powers))
+        .replace("{NEG_G2}", &to_hex(&neg_g2)))
+}
+
+const CONTRACT_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by verkle-tree, do not edit.
+pragma solidity ^0.8.20;
+
+/// @notice Verifies proofs of a KZG Verkle tree of width {WIDTH} over BLS12-381 (EIP-2537 precompiles).
+contract VerkleKzgVerifier {
+    uint256 internal constant WIDTH = {WIDTH};
+    uint256 internal constant R = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001;
+    uint256 internal constant NODE_HEADER = {NODE_HEADER};
+
+    address internal constant MODEXP = address(0x05);
+    address internal constant G1_MSM = address(0x0c);
+    address internal constant G2_MSM = address(0x0e);
+    address internal constant PAIRING = address(0x0f);
+
+    // base field modulus, 64 bytes
+    bytes internal constant Q = hex"{Q}";
+    // [tau^i]_1 for i < WIDTH, 128 bytes each
+    bytes internal constant G1_POWERS = hex"{G1_POWERS}";
+    // [tau^i]_2 for i <= WIDTH, 256 bytes each
+    bytes internal constant G2_POWERS = hex"{G2_POWERS}";
+    // -g2
+    bytes internal constant NEG_G2 = hex"{NEG_G2}";
+
+    struct Node {
+        uint256 layer;
+        uint256 position;
+        uint256 offset;
+        uint256 points;
+    }
+
+    /// @param root the root commitment (128 bytes)
+    /// @param depth the number of layers of the tree
+    /// @param indices the proven data indices
+    /// @param values the claimed values at the indices
+    /// @param proof the proven nodes sorted by (layer, position), each one is
+    ///     layer, position, commitment (128), proof (128), k, x[k], y[k], I[k], Z[k] (Z without its leading 1)
+    function verify(
+        bytes calldata root,
+        uint256 depth,
+        uint256[] calldata indices,
+        uint256[] calldata values,
+        bytes calldata proof
+    ) external view returns (bool) {
+        if (root.length != 128 || depth == 0 || indices.length == 0 || indices.length != values.length) {
+            return false;
+        }
+        (bool parsed, Node[] memory nodes) = parseNodes(proof, depth);
+        if (!parsed || nodes[0].layer != 0 || keccak256(proof[nodes[0].offset + 64:nodes[0].offset + 192]) != keccak256(root)) {
+            return false;
+        }
+        if (!checkLinks(proof, nodes)) {
+            return false;
+        }
+        for (uint256 i = 0; i < indices.length; i++) {
+            if (!claimHolds(proof, nodes, depth, indices[i], values[i])) {
+                return false;
+            }
+        }
+        for (uint256 i = 0; i < nodes.length; i++) {
+            if (!checkPolynomials(proof, nodes[i]) || !checkOpening(proof, nodes[i])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // Every node is opened in its parent at its own commitment
+    function checkLinks(bytes calldata proof, Node[] memory nodes) internal view returns (bool) {
+        for (uint256 i = 1; i < nodes.length; i++) {
+            (bool found, uint256 y) = valueAt(proof, nodes, nodes[i].layer - 1, nodes[i].position / WIDTH, nodes[i].position % WIDTH);
+            if (!found || !commitmentIs(proof[nodes[i].offset + 64:nodes[i].offset + 192], y)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    // The claimed value is opened in its leaf node
+    function claimHolds(bytes calldata proof, Node[] memory nodes, uint256 depth, uint256 index, uint256 value)
+        internal
+        pure
+        returns (bool)
+    {
+        (bool fits, uint256 leaves) = layerSize(depth);
+        if (!fits || index >= leaves) {
+            return false;
+        }
+        (bool found, uint256 y) = valueAt(proof, nodes, depth - 1, index / WIDTH, index % WIDTH);
+        return found && y == value;
+    }
+
+    function word(bytes calldata data, uint256 offset) internal pure returns (uint256) {
+        return uint256(bytes32(data[offset:offset + 32]));
+    }
+
+    function layerSize(uint256 layer) internal pure returns (bool, uint256) {
+        uint256 size = 1;
+        for (uint256 i = 0; i < layer; i++) {
+            if (size > type(uint256).max / WIDTH) {
+                return (false, 0);
+            }
+            size *= WIDTH;
+        }
+        return (true, size);
+    }
+
+    function parseNodes(bytes calldata proof, uint256 depth) internal pure returns (bool, Node[] memory) {
+        Node[] memory empty = new Node[](0);
+        uint256 count = 0;
+        uint256 offset = 0;
+        while (offset < proof.length) {
+            if (proof.length - offset < NODE_HEADER) {
+                return (false, empty);
+            }
+            uint256 k = word(proof, offset + 320);
+            if (k == 0 || k > WIDTH || proof.length - offset < NODE_HEADER + 128 * k) {
+                return (false, empty);
+            }
+            offset += NODE_HEADER + 128 * k;
+            count++;
+        }
+        if (count == 0) {
+            return (false, empty);
+        }
+        Node[] memory nodes = new Node[](count);
+        offset = 0;
+        for (uint256 i = 0; i < count; i++) {
+            nodes[i] = Node(word(proof, offset), word(proof, offset + 32), offset, word(proof, offset + 320));
+            (bool fits, uint256 size) = layerSize(nodes[i].layer);
+            if (nodes[i].layer >= depth || !fits || nodes[i].position >= size) {
+                return (false, empty);
+            }
+            if (i > 0) {
+                Node memory previous = nodes[i - 1];
+                if (previous.layer > nodes[i].layer || (previous.layer == nodes[i].layer && previous.position >= nodes[i].position)) {
+                    return (false, empty);
+                }
+            }
+            offset += NODE_HEADER + 128 * nodes[i].points;
+        }
+        return (true, nodes);
+    }
+
+    // The y of the point with x = slot in the node at (layer, position)
+    function valueAt(bytes calldata proof, Node[] memory nodes, uint256 layer, uint256 position, uint256 slot)
+        internal
+        pure
+        returns (bool, uint256)
+    {
+        for (uint256 i = 0; i < nodes.length; i++) {
+            if (nodes[i].layer == layer && nodes[i].position == position) {
+                uint256 xs = nodes[i].offset + NODE_HEADER;
+                uint256 k = nodes[i].points;
+                for (uint256 j = 0; j < k; j++) {
+                    if (word(proof, xs + 32 * j) == slot) {
+                        return (true, word(proof, xs + 32 * (k + j)));
+                    }
+                }
+                return (false, 0);
+            }
+        }
+        return (false, 0);
+    }
+
+    // Checks that ((x + y) mod q) mod r of the commitment is `value`, the field element the parent commits to
+    function commitmentIs(bytes calldata commitment, uint256 value) internal view returns (bool) {
+        uint256 xLo = word(commitment, 32);
+        uint256 low;
+        uint256 high;
+        unchecked {
+            low = xLo + word(commitment, 96);
+            high = word(commitment, 0) + word(commitment, 64) + (low < xLo ? 1 : 0);
+        }
+        (bool ok, bytes memory reduced) = MODEXP.staticcall(abi.encodePacked(uint256(64), uint256(1), uint256(64), high, low, uint8(1), Q));
+        if (!ok || reduced.length != 64) {
+            return false;
+        }
+        (ok, reduced) = MODEXP.staticcall(abi.encodePacked(uint256(64), uint256(1), uint256(32), reduced, uint8(1), R));
+        return ok && reduced.length == 32 && abi.decode(reduced, (uint256)) == value;
+    }
+
+    // Horner evaluation of the k coefficients at `at`, with an optional leading 1
+    function evaluate(bytes calldata proof, uint256 coefficients, uint256 k, uint256 at, bool monic)
+        internal
+        pure
+        returns (bool, uint256)
+    {
+        uint256 result = monic ? 1 : 0;
+        for (uint256 j = k; j > 0; j--) {
+            uint256 c = word(proof, coefficients + 32 * (j - 1));
+            if (c >= R) {
+                return (false, 0);
+            }
+            result = addmod(mulmod(result, at, R), c, R);
+        }
+        return (true, result);
+    }
+
+    // The points are distinct slots, I interpolates them and Z vanishes on them
+    function checkPolynomials(bytes calldata proof, Node memory node) internal pure returns (bool) {
+        uint256 k = node.points;
+        uint256 xs = node.offset + NODE_HEADER;
+        for (uint256 i = 0; i < k; i++) {
+            uint256 x = word(proof, xs + 32 * i);
+            uint256 y = word(proof, xs + 32 * (k + i));
+            if (x >= WIDTH || (i > 0 && x <= word(proof, xs + 32 * (i - 1))) || y >= R) {
+                return false;
+            }
+            (bool valid, uint256 value) = evaluate(proof, xs + 64 * k, k, x, false);
+            if (!valid || value != y) {
+                return false;
+            }
+            (valid, value) = evaluate(proof, xs + 96 * k, k, x, true);
+            if (!valid || value != 0) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function copyInto(bytes memory target, uint256 at, bytes memory source, uint256 from, uint256 length) internal pure {
+        for (uint256 i = 0; i < length; i += 32) {
+            assembly {
+                mstore(add(add(target, 32), add(at, i)), mload(add(add(source, 32), add(from, i))))
+            }
+        }
+    }
+
+    function putWord(bytes memory target, uint256 at, uint256 value) internal pure {
+        assembly {
+            mstore(add(add(target, 32), at), value)
+        }
+    }
+
+    // C - [I(tau)]_1
+    function shiftedCommitment(bytes calldata proof, Node memory node) internal view returns (bool, bytes memory) {
+        uint256 k = node.points;
+        uint256 coefficients = node.offset + NODE_HEADER + 64 * k;
+        bytes memory powers = G1_POWERS;
+        bytes memory input = new bytes(160 * (k + 1));
+        for (uint256 j = 0; j < k; j++) {
+            copyInto(input, 160 * j, powers, 128 * j, 128);
+            putWord(input, 160 * j + 128, (R - word(proof, coefficients + 32 * j)) % R);
+        }
+        copyInto(input, 160 * k, proof[node.offset + 64:node.offset + 192], 0, 128);
+        putWord(input, 160 * k + 128, 1);
+        (bool ok, bytes memory output) = G1_MSM.staticcall(input);
+        return (ok && output.length == 128, output);
+    }
+
+    // [Z(tau)]_2
+    function vanishingAtTau(bytes calldata proof, Node memory node) internal view returns (bool, bytes memory) {
+        uint256 k = node.points;
+        uint256 coefficients = node.offset + NODE_HEADER + 96 * k;
+        bytes memory powers = G2_POWERS;
+        bytes memory input = new bytes(288 * (k + 1));
+        for (uint256 j = 0; j < k; j++) {
+            copyInto(input, 288 * j, powers, 256 * j, 256);
+            putWord(input, 288 * j + 256, word(proof, coefficients + 32 * j));
+        }
+        copyInto(input, 288 * k, powers, 256 * k, 256);
+        putWord(input, 288 * k + 256, 1);
+        (bool ok, bytes memory output) = G2_MSM.staticcall(input);
+        return (ok && output.length == 256, output);
+    }
+
+    // e(C - [I(tau)]_1, -g2) * e(pi, [Z(tau)]_2) == 1
+    function checkOpening(bytes calldata proof, Node memory node) internal view returns (bool) {
+        (bool shifted, bytes memory commitment) = shiftedCommitment(proof, node);
+        (bool vanishing, bytes memory zTau) = vanishingAtTau(proof, node);
+        if (!shifted || !vanishing) {
+            return false;
+        }
+        bytes memory input = bytes.concat(commitment, NEG_G2, proof[node.offset + 192:node.offset + 320], zTau);
+        (bool ok, bytes memory output) = PAIRING.staticcall(input);
+        return ok && output.length == 32 && abi.decode(output, (uint256)) == 1;
+    }
+}
+"#;
@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod tests {
+
+    use std::fs;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    use ark_bls12_381::{Fr as F, G1Affine};
+    use ark_ec::AffineRepr;
+    use revm::db::{CacheDB, EmptyDB};
+    use revm::primitives::{Address, ExecutionResult, Output, SpecId, TxKind};
+    use revm::Evm;
+    use sha3::{Digest, Keccak256};
+
+    use crate::file_io::{from_hex, to_hex};
+    use crate::{verifier_calldata, verifier_contract, KzgSetup, KzgVerifierKey, ProofNode, VerkleTree};
+
+    const WIDTH: usize = 4;
+    const DEPTH: usize = 3;
+
+    fn datas() -> Vec<F> {
+        (0..WIDTH.pow(DEPTH as u32)).map(|i| F::from(3 * i as u64 + 11)).collect()
+    }
+
+    fn build_tree(datas: &[F], setup: &KzgSetup) -> VerkleTree {
+        VerkleTree::new_with_setup(&datas.to_vec(), WIDTH, setup.clone()).unwrap()
+    }
+
+    // The committed bytecode is compiled from the contract for this setup, see test_regenerate_contract_bytecode
+    const SEED: &[u8] = b"solidity verifier test setup";
+    const BYTECODE_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/solidity_verifier_test_w4.hex");
+
+    fn fixed_setup() -> KzgSetup {
+        KzgSetup::from_seed(SEED, WIDTH)
+    }
+
+    fn source_hash(source: &str) -> String {
+        to_hex(&Keccak256::digest(source.as_bytes()))
+    }
+
+    fn solc(source: &str) -> Vec<u8> {
+        let mut child = Command::new("solc")
+            .args(["--bin", "--optimize", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("solc has to be on the PATH to regenerate the bytecode");
+        child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "solc failed to compile the verifier");
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let hex = stdout.lines().skip_while(|line| !line.starts_with("Binary")).nth(1).expect("no bytecode in the solc output");
+        from_hex(hex).unwrap()
+    }
+
+    // Deploys the verifier and returns what verify() answers for each calldata, a revert counts as a rejection
+    fn run_in_evm(bytecode: Vec<u8>, calls: &[Vec<u8>]) -> Vec<bool> {
+        let mut evm = Evm::builder()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .with_spec_id(SpecId::PRAGUE)
+            .modify_tx_env(|tx| {
+                tx.caller = Address::with_last_byte(1);
+                tx.transact_to = TxKind::Create;
+                tx.data = bytecode.into();
+                tx.gas_limit = 30_000_000;
+            })
+            .build();
+        let address = match evm.transact_commit().unwrap() {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => address,
+            result => panic!("deployment failed: {:?}", result),
+        };
+        calls
+            .iter()
+            .map(|calldata| {
+                evm.tx_mut().transact_to = TxKind::Call(address);
+                evm.tx_mut().data = calldata.clone().into();
+                match evm.transact().unwrap().result {
+                    ExecutionResult::Success {
+                        output: Output::Call(output),
+                        ..
+                    } => output.len() == 32 && output[31] == 1 && output[..31].iter().all(|byte| *byte == 0),
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+
+    // Valid and tampered proofs of a VerkleTree, with the values they claim
+    fn cases(setup: &KzgSetup) -> (G1Affine, Vec<(Vec<Option<ProofNode>>, Vec<usize>, Vec<F>)>) {
+        let datas = datas();
+        let tree = build_tree(&datas, setup);
+        let indices = vec![5, 6, 40, 63];
+        let values: Vec<F> = indices.iter().map(|index| datas[*index]).collect();
+        let proofs = tree.proof(indices.clone(), &datas);
+        let mut cases = vec![(proofs.clone(), indices.clone(), values.clone())];
+
+        let mut wrong_value = values.clone();
+        wrong_value[2] += F::from(1u64);
+        cases.push((proofs.clone(), indices.clone(), wrong_value));
+
+        let mut wrong_index = indices.clone();
+        wrong_index[0] = 4;
+        cases.push((proofs.clone(), wrong_index, values.clone()));
+
+        // a proof of another node
+        let mut wrong_proof = proofs.clone();
+        let last = wrong_proof.iter().rposition(|node| node.is_some()).unwrap();
+        let first_leaf = wrong_proof.iter().skip(1 + WIDTH).position(|node| node.is_some()).unwrap() + 1 + WIDTH;
+        let other = wrong_proof[first_leaf].as_ref().unwrap().proof;
+        wrong_proof[last].as_mut().unwrap().proof = other;
+        cases.push((wrong_proof, indices.clone(), values.clone()));
+
+        // a leaf of another tree, it is not linked to the root
+        let other_datas: Vec<F> = datas.iter().map(|data| *data + F::from(1u64)).collect();
+        let other_proofs = build_tree(&other_datas, setup).proof(indices.clone(), &other_datas);
+        let mut unlinked = proofs.clone();
+        unlinked[last] = other_proofs[last].clone();
+        let mut unlinked_values = values.clone();
+        unlinked_values[3] += F::from(1u64);
+        cases.push((unlinked, indices.clone(), unlinked_values));
+
+        (tree.root_commitment().unwrap(), cases)
+    }
+
+    #[test]
+    fn test_reference_verifier() {
+        let setup = KzgSetup::new(WIDTH);
+        let key = KzgVerifierKey::from_tree(&build_tree(&datas(), &setup));
+        let (root, cases) = cases(&setup);
+        let results: Vec<bool> = cases
+            .iter()
+            .map(|(proofs, indices, values)| key.verify(&root, proofs, indices, values, WIDTH, DEPTH))
+            .collect();
+        assert_eq!(results, vec![true, false, false, false, false]);
+
+        // the tree verifies with the same check
+        let results: Vec<bool> = cases
+            .iter()
+            .map(|(proofs, indices, values)| VerkleTree::verify(root, proofs.clone(), WIDTH, indices.clone(), DEPTH, values.clone(), &setup))
+            .collect();
+        assert_eq!(results, vec![true, false, false, false, false]);
+
+        let (proofs, indices, values) = &cases[0];
+        let other_root = build_tree(&datas().into_iter().rev().collect::<Vec<F>>(), &setup).root_commitment().unwrap();
+        assert!(!key.verify(&other_root, proofs, indices, values, WIDTH, DEPTH));
+        assert!(!key.verify(&root, proofs, indices, values, WIDTH, DEPTH + 1));
+        // a key of another setup
+        assert!(!KzgVerifierKey::from(&KzgSetup::new(WIDTH)).verify(&root, proofs, indices, values, WIDTH, DEPTH));
+        assert!(verifier_contract(&key, WIDTH + 1).is_err());
+    }
+
+    // A commitment at infinity has no field element, the proof is rejected instead of a panic
+    #[test]
+    fn test_commitment_at_infinity() {
+        let setup = KzgSetup::new(WIDTH);
+        let (root, cases) = cases(&setup);
+        let (mut proofs, indices, values) = cases[0].clone();
+        let last = proofs.iter().rposition(|node| node.is_some()).unwrap();
+        proofs[last].as_mut().unwrap().commitment = G1Affine::zero();
+        assert!(!KzgVerifierKey::from(&setup).verify(&root, &proofs, &indices, &values, WIDTH, DEPTH));
+        assert!(!VerkleTree::verify(root, proofs, WIDTH, indices, DEPTH, values, &setup));
+
+        // a leaf of all zero values commits to infinity, the tree can not link it
+        assert!(VerkleTree::new_with_setup(&vec![F::from(0u64); WIDTH * WIDTH], WIDTH, setup).is_err());
+    }
+
+    #[test]
+    fn test_calldata() {
+        let (root, cases) = cases(&KzgSetup::new(WIDTH));
+        let (proofs, indices, values) = &cases[0];
+        let calldata = verifier_calldata(&root, proofs, indices, values, WIDTH, DEPTH).unwrap();
+        // selector, then five head words
+        assert_eq!((calldata.len() - 4) % 32, 0);
+        assert_eq!(calldata[4 + 32 * 2 - 1] as usize, DEPTH);
+        assert!(verifier_calldata(&root, proofs, indices, &values[1..], WIDTH, DEPTH).is_err());
+    }
+
+    // Runs the committed bytecode of the contract for the fixed setup, it has to be compiled from the current source
+    #[test]
+    fn test_contract_in_evm() {
+        let setup = fixed_setup();
+        let source = verifier_contract(&KzgVerifierKey::from(&setup), WIDTH).unwrap();
+        let committed = fs::read_to_string(BYTECODE_FILE)
+            .expect("the verifier bytecode is missing, run cargo test test_regenerate_contract_bytecode -- --ignored with solc");
+        let mut lines = committed.lines();
+        assert_eq!(
+            lines.next(),
+            Some(source_hash(&source).as_str()),
+            "the contract source changed, run cargo test test_regenerate_contract_bytecode -- --ignored with solc"
+        );
+        let bytecode = from_hex(lines.next().expect("no bytecode in the file")).expect("the bytecode is not hex");
+        let (root, cases) = cases(&setup);
+        let calls: Vec<Vec<u8>> = cases
+            .iter()
+            .map(|(proofs, indices, values)| verifier_calldata(&root, proofs, indices, values, WIDTH, DEPTH).unwrap())
+            .collect();
+        assert_eq!(run_in_evm(bytecode, &calls), vec![true, false, false, false, false]);
+    }
+
+    // Compiles the contract for the fixed setup with solc and writes the bytecode that test_contract_in_evm runs
+    #[test]
+    #[ignore = "needs solc on the PATH, regenerates the committed bytecode"]
+    fn test_regenerate_contract_bytecode() {
+        let setup = fixed_setup();
+        let source = verifier_contract(&KzgVerifierKey::from(&setup), WIDTH).unwrap();
+        let bytecode = solc(&source);
+        fs::write(BYTECODE_FILE, format!("{}\n{}\n", source_hash(&source), to_hex(&bytecode))).unwrap();
+    }
+}
@@ -1,13 +1,17 @@
-use std::{collections::HashSet, vec};
+use std::{collections::{BTreeMap, HashSet}, vec};
 
-use ark_bls12_381::{Fr as F, G1Affine};
-use ark_ec:: AffineRepr;
-use ark_poly::univariate::DensePolynomial;
+use ark_bls12_381::{Bls12_381, Fr as F, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_poly::univariate::{DenseOrSparsePolynomial, DensePolynomial};
+use ark_poly::DenseUVPolynomial;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::UniformRand;
 use kzg_commitment::KZGCommitment;
 
-use ark_ff::PrimeField;
-use kzg_commitment::ProofError;
+use ark_ff::{Field, One, PrimeField, Zero};
 use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
 
 use pointproofs::pairings::Commitment;
 use rayon::prelude::*;
@@ -15,7 +19,15 @@ use rayon::prelude::*;
 pub struct VerkleTree {
     nodes: Vec<Vec<VerkleNode>>,
     width: usize,
-    kzg: KZGCommitment,
+    setup: KzgSetup,
+}
+
+/* The setup of the tree: [tau^i]_1 for i < width and [tau^i]_2 for i <= width.
+    Commitments and proofs only check against the setup they were made with, so the verifier needs the same one */
+#[derive(Debug, Clone, PartialEq)]
+pub struct KzgSetup {
+    pub g1_powers: Vec<G1Affine>,
+    pub g2_powers: Vec<G2Affine>,
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +38,7 @@ struct VerkleNode {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VerkleProof {
     pub proofs: Vec<ProofNode>,
 }
@@ -37,26 +50,132 @@ pub struct ProofNode {
     pub point: Vec<(F, F)>,
 }
 
+// One proven node: its position in the tree, the points sorted by x and the polynomials I and Z of the opening
+pub(crate) struct OpenedNode<'a> {
+    pub layer: usize,
+    pub position: usize,
+    pub node: &'a ProofNode,
+    pub points: Vec<(F, F)>,
+    pub interpolation: Vec<F>,
+    pub vanishing: Vec<F>,
+}
+
+impl KzgSetup {
+    // A setup from a random tau that is dropped afterwards
+    pub fn new(width: usize) -> Self {
+        Self::from_tau(F::rand(&mut rand::thread_rng()), width)
+    }
+
+    // Only for testing, anyone who knows the seed knows tau and can open a commitment to any value
+    pub fn from_seed(seed: &[u8], width: usize) -> Self {
+        Self::from_tau(F::from_le_bytes_mod_order(&Sha256::digest(seed)), width)
+    }
+
+    pub(crate) fn from_tau(tau: F, width: usize) -> Self {
+        let g1_powers: Vec<G1Projective> = (0..width).map(|i| G1Projective::generator() * tau.pow([i as u64])).collect();
+        let g2_powers: Vec<G2Projective> = (0..=width).map(|i| G2Projective::generator() * tau.pow([i as u64])).collect();
+        KzgSetup {
+            g1_powers: G1Projective::normalize_batch(&g1_powers),
+            g2_powers: G2Projective::normalize_batch(&g2_powers),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.g1_powers.len()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        self.g1_powers.serialize_compressed(&mut bytes).expect("writing to a vector does not fail");
+        self.g2_powers.serialize_compressed(&mut bytes).expect("writing to a vector does not fail");
+        bytes
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, VerkleTreeError> {
+        let g1_powers = Vec::<G1Affine>::deserialize_compressed(&mut bytes).map_err(|_| VerkleTreeError::InvalidSetup)?;
+        let g2_powers = Vec::<G2Affine>::deserialize_compressed(&mut bytes).map_err(|_| VerkleTreeError::InvalidSetup)?;
+        if !bytes.is_empty() || g1_powers.len() < 2 || g2_powers.len() != g1_powers.len() + 1 {
+            return Err(VerkleTreeError::InvalidSetup);
+        }
+        Ok(KzgSetup { g1_powers, g2_powers })
+    }
+
+    fn commit(&self, polynomial: &DensePolynomial<F>) -> Result<G1Affine, VerkleTreeError> {
+        let coeffs = &polynomial.coeffs;
+        if coeffs.len() > self.g1_powers.len() {
+            return Err(VerkleTreeError::BuildError);
+        }
+        let commitment: G1Projective = G1Projective::msm(&self.g1_powers[..coeffs.len()], coeffs).map_err(|_| VerkleTreeError::BuildError)?;
+        Ok(commitment.into_affine())
+    }
+
+    // The multi-point proof [q(tau)]_1 with q = (p - I) / Z, where I interpolates the points and Z vanishes on them
+    fn open(&self, polynomial: &DensePolynomial<F>, points: &[(F, F)]) -> Result<G1Affine, VerkleTreeError> {
+        let mut distinct: Vec<(F, F)> = Vec::new();
+        for point in points {
+            match distinct.iter().find(|(x, _)| *x == point.0) {
+                Some((_, y)) if *y != point.1 => return Err(VerkleTreeError::ProofGenerateError),
+                Some(_) => {}
+                None => distinct.push(*point),
+            }
+        }
+        let numerator = polynomial - &interpolate(&distinct);
+        let (quotient, remainder) = DenseOrSparsePolynomial::from(&numerator)
+            .divide_with_q_and_r(&DenseOrSparsePolynomial::from(vanishing(&distinct)))
+            .ok_or(VerkleTreeError::ProofGenerateError)?;
+        // The polynomial does not go through the points
+        if !remainder.is_zero() {
+            return Err(VerkleTreeError::ProofGenerateError);
+        }
+        self.commit(&quotient).map_err(|_| VerkleTreeError::ProofGenerateError)
+    }
+
+    // e(pi, [Z(tau)]_2) = e(C - [I(tau)]_1, g2), the opening of the node at its points
+    fn check_opening(&self, node: &OpenedNode) -> bool {
+        if node.interpolation.len() > self.g1_powers.len() || node.vanishing.len() > self.g2_powers.len() {
+            return false;
+        }
+        let interpolation: G1Projective = match G1Projective::msm(&self.g1_powers[..node.interpolation.len()], &node.interpolation) {
+            Ok(interpolation) => interpolation,
+            Err(_) => return false,
+        };
+        let vanishing: G2Projective = match G2Projective::msm(&self.g2_powers[..node.vanishing.len()], &node.vanishing) {
+            Ok(vanishing) => vanishing,
+            Err(_) => return false,
+        };
+        let lhs = Bls12_381::pairing(node.node.proof, vanishing);
+        let rhs = Bls12_381::pairing(node.node.commitment.into_group() - interpolation, self.g2_powers[0]);
+        lhs == rhs
+    }
+}
+
 impl VerkleTree {
+    // The tree gets a fresh setup, see setup() for what the verifier needs
     pub fn new(datas: &Vec<F>, width: usize) -> Result<Self, VerkleTreeError> {
-        let kzg = KZGCommitment::new(width);
-        Self::build_tree(kzg, datas, width)
+        Self::new_with_setup(datas, width, KzgSetup::new(width))
+    }
+
+    pub fn new_with_setup(datas: &Vec<F>, width: usize, setup: KzgSetup) -> Result<Self, VerkleTreeError> {
+        if setup.width() != width {
+            return Err(VerkleTreeError::InvalidSetup);
+        }
+        Self::build_tree(setup, datas, width)
     }
 
-    fn build_tree(kzg: KZGCommitment, datas: &Vec<F>, width: usize) -> Result<VerkleTree, VerkleTreeError> {
+    fn build_tree(setup: KzgSetup, datas: &Vec<F>, width: usize) -> Result<VerkleTree, VerkleTreeError> {
         if datas.is_empty() {
           return Err(VerkleTreeError::BuildError);
         }
         assert!(datas.len().is_multiple_of(width), "Please give a tree that is compeletly filled, i.e. log_{{width}}(data) is a natural number otherwise security can not be guaranteed");
         // We build the tree layer per layer
         //println!("Start making the first layer with no children");
-        let mut layer = Self::create_leaf_nodes(&kzg, datas, width);
+        let mut layer = Self::create_leaf_nodes(&setup, datas, width)?;
         // println!("first layer {:?}", layer);
         //println!("first layer is constructed");
         let mut tree: Vec<Vec<VerkleNode>> = Vec::new();
         tree.push(layer.clone());
         while layer.len() > 1 {
-            layer = Self::build_layer(&kzg, &layer, width);
+            layer = Self::build_layer(&setup, &layer, width)?;
             tree.push(layer.clone());
             //println!("next layer is constructed");
         }
@@ -70,40 +189,47 @@ impl VerkleTree {
         Ok(VerkleTree {
             nodes: tree,
             width,
-            kzg,
+            setup,
         })
     }
+
+    // The setup the tree commits with, verification needs it
+    pub fn setup(&self) -> &KzgSetup {
+        &self.setup
+    }
     
-    fn create_leaf_nodes(kzg: &KZGCommitment, datas: &[F], width: usize) -> Vec<VerkleNode> {
+    fn create_leaf_nodes(setup: &KzgSetup, datas: &[F], width: usize) -> Result<Vec<VerkleNode>, VerkleTreeError> {
         datas
             .par_chunks(width)
-            .map(|chunk| {
+            .map(|chunk| -> Result<VerkleNode, VerkleTreeError> {
                 let polynomial = KZGCommitment::vector_to_polynomial(&chunk.to_vec());
-                let commitment = kzg.commit_polynomial(&polynomial);
-                VerkleNode {
+                let commitment = setup.commit(&polynomial)?;
+                Ok(VerkleNode {
                     commitment,
                     polynomial,
                     children: false,
-                }
+                })
             })
             .collect()
     }
 
-    fn build_layer (kzg: &KZGCommitment, nodes: &[VerkleNode], width: usize, ) -> Vec<VerkleNode>{
+    fn build_layer (setup: &KzgSetup, nodes: &[VerkleNode], width: usize, ) -> Result<Vec<VerkleNode>, VerkleTreeError>{
         nodes
         .par_chunks(width)
-            .map(|chunk| {
+            .map(|chunk| -> Result<VerkleNode, VerkleTreeError> {
+                // A chunk of all zero values commits to the point at infinity, which has no field element
                 let vector_commitment_mapping = chunk
                     .par_iter()
                     .map(|node| Self::map_commitment_to_field(&node.commitment))
-                    .collect();
+                    .collect::<Option<Vec<F>>>()
+                    .ok_or(VerkleTreeError::BuildError)?;
                 let polynomial = KZGCommitment::vector_to_polynomial(&vector_commitment_mapping);
-                let commitment = kzg.commit_polynomial(&polynomial);
-                VerkleNode {
+                let commitment = setup.commit(&polynomial)?;
+                Ok(VerkleNode {
                     commitment,
                     polynomial,
                     children: true,
-                }
+                })
             })
             .collect()
     }
@@ -117,7 +243,7 @@ impl VerkleTree {
         let index_for_proof = Self::create_index_for_proof(index, width, self.depth());
         //println!("index proof {:?}", index_for_proof);
         //println!("done creating indices");
-        let tree: Vec<Vec<VerkleNode>> = self.nodes.clone();
+        let tree = &self.nodes;
 
         let flattened_tree: Vec<(usize, usize, &VerkleNode)> = tree.iter()
             .enumerate()
@@ -138,7 +264,8 @@ impl VerkleTree {
             else if node.children {
                 for ind in indices {
                     let com = &tree[layer_index+1][*ind].commitment;
-                    let child_commitment = Self::map_commitment_to_field(&com);
+                    // The tree was built, so none of its commitments is the point at infinity
+                    let child_commitment = Self::map_commitment_to_field(&com).expect("the tree has no commitment at infinity");
                     let index = *ind % width;
                     //println!("index {}", ind);
                     points.push((F::from((index) as u32),child_commitment));
@@ -238,8 +365,8 @@ impl VerkleTree {
 
     fn find_proof_node (&self, node: &VerkleNode, points:Vec<(F,F)>) ->  Result<ProofNode, VerkleTreeError>  {
 
-        let proof: Result<G1Affine, ProofError> = self.kzg.generate_proof(&node.polynomial, &points);
-        
+        let proof = self.setup.open(&node.polynomial, &points);
+
         match proof {
             Ok(proof) => {
                 let proof_node = ProofNode {
@@ -253,60 +380,63 @@ impl VerkleTree {
         }
     }
 
-    // This function computes batch proofs, is also works if the NONE values are already deleted.
-    pub fn verify (root: G1Affine, mut tree_proofs: Vec<Option<ProofNode>>, width: usize, indices: Vec<usize>, depth: usize, data: Vec<F>) -> bool {
-        assert!(tree_proofs[0].is_some());
+    /* Checks the proofs against the setup of the tree: the first proven node is the root, every other proven node is opened
+        in its parent and the claimed values in their leaves. The proofs list every node of the tree, as proof() returns them */
+    pub fn verify (root: G1Affine, tree_proofs: Vec<Option<ProofNode>>, width: usize, indices: Vec<usize>, depth: usize, data: Vec<F>, setup: &KzgSetup) -> bool {
+        Self::verify_proofs(&root, &tree_proofs, &indices, &data, width, depth, setup)
+    }
 
-        // Check if the root is correct
-        if root != tree_proofs[0].as_ref().unwrap().commitment {
-            println!("Root commitment is not correct");
+    pub(crate) fn verify_proofs(root: &G1Affine, proofs: &[Option<ProofNode>], indices: &[usize], values: &[F], width: usize, depth: usize, setup: &KzgSetup) -> bool {
+        if width < 2 || setup.g1_powers.len() != width || setup.g2_powers.len() != width + 1 {
             return false;
         }
+        if indices.is_empty() || indices.len() != values.len() {
+            return false;
+        }
+        let nodes = match opened_nodes(proofs, width, depth) {
+            Some(nodes) => nodes,
+            None => return false,
+        };
+        let positions: BTreeMap<(usize, usize), &OpenedNode> = nodes.iter().map(|node| ((node.layer, node.position), node)).collect();
+        let value_at = |layer: usize, position: usize, slot: usize| {
+            positions
+                .get(&(layer, position))
+                .and_then(|node| node.points.iter().find(|(x, _)| *x == F::from(slot as u64)))
+                .map(|(_, y)| *y)
+        };
 
-        // Check if the proofs are of the correct size, also works if NONE values were already deleted
-        tree_proofs.retain(|node| node.is_some());
-        // The expected length
-        let check_vector: Vec<Vec<Vec<usize>>> = Self::create_index_for_proof(indices, width, depth);
-        let length = check_vector.iter()
-            .flat_map(|layer| layer.iter()) 
-            .filter(|node_vec| {
-                !node_vec.is_empty()})
-            .count();
-        if tree_proofs.len() != length {
-            println!("The tree proofs vector is not of the correct length");
-            println!(" lengt {}, {}", length, tree_proofs.len());
+        if nodes[0].layer != 0 || nodes[0].node.commitment != *root {
             return false;
         }
-        // To find the commitment value easier, we dont save the ProofNodes but the commitments in the next vector
-        let mut commitments_vector: Vec<F> = tree_proofs.iter().map(|proof_node|
-            {
-                let node = proof_node.as_ref().unwrap();
-                Self::map_commitment_to_field(&node.commitment)
-            }
-        ).collect();
-        data.iter().for_each(|d| commitments_vector.push(*d));
-        
-        let kzg = KZGCommitment::new(width + 1);
-        tree_proofs.par_iter().all(|proof_node| {
-            if let Some(node) = proof_node{
-                let b1 = kzg.verify_proof(&node.commitment, &node.point, &node.proof);
-                // For simplicity we check if there is a commitment that matches
-                let b2 = node.point.par_iter().all(|point| {
-                    commitments_vector.iter().any(|n| *n == point.1)
-                });
-                b1 & b2
+        for node in nodes.iter().skip(1) {
+            // A commitment at infinity has no field element, the proof is invalid
+            let commitment = match Self::map_commitment_to_field(&node.node.commitment) {
+                Some(commitment) => commitment,
+                None => return false,
+            };
+            if value_at(node.layer - 1, node.position / width, node.position % width) != Some(commitment) {
+                return false;
             }
-            else {
-                true
+        }
+        let leaves = match width.checked_pow(depth as u32) {
+            Some(leaves) => leaves,
+            None => return false,
+        };
+        for (index, value) in indices.iter().zip(values.iter()) {
+            if *index >= leaves || value_at(depth - 1, index / width, index % width) != Some(*value) {
+                return false;
             }
-        });
-        true
+        }
+
+        nodes.par_iter().all(|node| setup.check_opening(node))
     }
 
-    fn map_commitment_to_field(g1_point: &G1Affine) -> F {
-        let fq_value = g1_point.x().expect("its the x value") + g1_point.y().expect("its the y value");
+    // None for the point at infinity, it has no coordinates
+    pub(crate) fn map_commitment_to_field(g1_point: &G1Affine) -> Option<F> {
+        let (x, y) = g1_point.xy()?;
+        let fq_value = *x + y;
         let fq_bigint: BigUint = fq_value.into_bigint().into();
-        F::from_le_bytes_mod_order(&fq_bigint.to_bytes_le())
+        Some(F::from_le_bytes_mod_order(&fq_bigint.to_bytes_le()))
     }
 
     pub fn depth(&self) -> usize {
@@ -326,9 +456,78 @@ impl VerkleTree {
     }
 }
 
+// The proven nodes with their (layer, position), the proofs of VerkleTree::proof list every node of the tree
+pub(crate) fn opened_nodes(proofs: &[Option<ProofNode>], width: usize, depth: usize) -> Option<Vec<OpenedNode<'_>>> {
+    let mut nodes: Vec<OpenedNode> = Vec::new();
+    let mut flat_index = 0;
+    let mut layer_size = 1;
+    for layer in 0..depth {
+        for position in 0..layer_size {
+            if let Some(Some(node)) = proofs.get(flat_index + position) {
+                nodes.push(open_node(layer, position, node, width)?);
+            }
+        }
+        flat_index += layer_size;
+        layer_size = layer_size.checked_mul(width)?;
+    }
+    if nodes.is_empty() || proofs.len() > flat_index {
+        return None;
+    }
+    Some(nodes)
+}
+
+fn open_node(layer: usize, position: usize, node: &ProofNode, width: usize) -> Option<OpenedNode<'_>> {
+    // The tree may repeat a point or list them in any order, the verifiers need them sorted and distinct
+    let mut sorted: BTreeMap<usize, F> = BTreeMap::new();
+    for (x, y) in node.point.iter() {
+        let slot = (0..width).find(|slot| F::from(*slot as u64) == *x)?;
+        if *sorted.entry(slot).or_insert(*y) != *y {
+            return None;
+        }
+    }
+    let points: Vec<(F, F)> = sorted.into_iter().map(|(slot, y)| (F::from(slot as u64), y)).collect();
+    let mut interpolation = interpolate(&points).coeffs;
+    interpolation.resize(points.len(), F::zero());
+    let vanishing = vanishing(&points).coeffs;
+    Some(OpenedNode {
+        layer,
+        position,
+        node,
+        points,
+        interpolation,
+        vanishing,
+    })
+}
+
+fn vanishing(points: &[(F, F)]) -> DensePolynomial<F> {
+    points.iter().fold(DensePolynomial::from_coefficients_vec(vec![F::one()]), |acc, (x, _)| {
+        &acc * &DensePolynomial::from_coefficients_vec(vec![-*x, F::one()])
+    })
+}
+
+// Lagrange interpolation, the points have distinct x
+fn interpolate(points: &[(F, F)]) -> DensePolynomial<F> {
+    let mut result = DensePolynomial::from_coefficients_vec(vec![]);
+    for (i, (x_i, y_i)) in points.iter().enumerate() {
+        let mut basis = DensePolynomial::from_coefficients_vec(vec![F::one()]);
+        let mut denominator = F::one();
+        for (j, (x_j, _)) in points.iter().enumerate() {
+            if i != j {
+                basis = &basis * &DensePolynomial::from_coefficients_vec(vec![-*x_j, F::one()]);
+                denominator *= *x_i - x_j;
+            }
+        }
+        let scale = *y_i * denominator.inverse().unwrap();
+        let scaled = DensePolynomial::from_coefficients_vec(basis.coeffs.iter().map(|c| *c * scale).collect());
+        result = &result + &scaled;
+    }
+    result
+}
+
 #[derive(Debug)]
 pub enum VerkleTreeError {
     BuildError,
     ProofGenerateError,
     EmptyTree,
+    InvalidSetup,
 }
@@ -1,6 +1,6 @@
 use std::alloc::Layout;
 use std::time::Instant;
-use std::{collections::HashSet, vec};
+use std::{collections::{BTreeMap, BTreeSet}, vec};
 
 use pairing_plus::serdes::SerDes;
 
@@ -18,19 +18,71 @@ pub struct VerkleTree {
 #[derive(Debug, Clone)]
 struct VerkleNode {
     commitment: Commitment,
+    // The committed vector: data for the leaves, serialized child commitments otherwise
+    values: Vec<Vec<u8>>,
+    // The opening of every slot, only kept when the tree is built with a proof cache
+    proofs: Option<Vec<Proof>>,
     children: bool,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VerkleProof {
     pub proofs: Vec<ProofNode>,
 }
 
+/* Openings of every slot of the nodes on all paths touched by a set of updates, root first and layer by layer,
+    once before and once after the updates */
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitionProof {
+    pub old: VerkleProof,
+    pub new: VerkleProof,
+}
+
+/* The changes of one WriteBatch::commit: the leaf writes, and the old and new commitment of every node they changed.
+    VerkleTree::update_proof derives the changed slots of the nodes on the paths of a proof from it */
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TreeUpdate {
+    pub writes: Vec<LeafWrite>,
+    commitments: Vec<NodeCommitments>,
+}
+
+// A write to data index `index` that changed its value
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeafWrite {
+    pub index: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::bytes"))]
+    pub value_before: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::bytes"))]
+    pub value_after: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct NodeCommitments {
+    layer: usize,
+    node: usize,
+    before: Commitment,
+    after: Commitment,
+}
+
+// The new commitment of a node and its changed slots as (slot, value before, value after)
+struct NodeChange<'a> {
+    before: &'a Commitment,
+    after: &'a Commitment,
+    slots: Vec<(usize, Vec<u8>, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProofNode {
     pub commitment: Commitment, 
     pub proof: Proof, 
     pub indices : Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impl::byte_vecs"))]
     pub values: Vec<Vec<u8>> , //(index:usize, value: vec<vec<u8>>)
 }
 
@@ -41,6 +93,24 @@ impl VerkleTree {
         Self::build_tree(prover_params, datas, width)
     }
 
+    /* Initialize a new tree that also keeps the opening of every slot of every node.
+        Proofs are then aggregated from the cache instead of computed with an MSM of size width per node,
+        at the cost of width proofs of memory per node and slower updates */
+    pub fn new_with_proof_cache(datas: &[Vec<u8>], width: usize, prover_params: ProverParams) -> Result<Self, VerkleTreeError> {
+        let mut tree = Self::build_tree(prover_params, datas, width)?;
+        let slots: Vec<usize> = (0..width).collect();
+        let pp = &tree.pp;
+        tree.nodes.par_iter_mut().flatten().try_for_each(|node| {
+            node.proofs = Some(Proof::batch_new(pp, &node.values, &slots).map_err(|_| VerkleTreeError::BuildError)?);
+            Ok::<(), VerkleTreeError>(())
+        })?;
+        Ok(tree)
+    }
+
+    pub fn has_proof_cache(&self) -> bool {
+        self.nodes.first().is_some_and(|layer| layer[0].proofs.is_some())
+    }
+
     fn build_tree(prover_params: ProverParams, datas: &[Vec<u8>], width: usize) -> Result<VerkleTree, VerkleTreeError> {
         if datas.is_empty() {
           return Err(VerkleTreeError::BuildError);
@@ -81,7 +151,8 @@ impl VerkleTree {
                 let commitment: Commitment = Commitment::new(prover_params, &values).unwrap();
                 VerkleNode {
                     commitment,
-                    //values,
+                    values,
+                    proofs: None,
                     children: false,
                     //index: 0,
                 }
@@ -100,7 +171,8 @@ impl VerkleTree {
                 let commitment: Commitment = Commitment::new(prover_params, &values).unwrap();
                 VerkleNode {
                     commitment,
-                    //values,
+                    values,
+                    proofs: None,
                     children: true,
                     //index: 0,
                 }
@@ -110,178 +182,446 @@ impl VerkleTree {
 /* The next functions are to generate proofs for several indices simultaeusly  */
 
     /*  This function returns a long vector which reads the nodes from top to bottom left to right
-        Each index contains either a proof of some children, or a None value
+        Each index contains either a proof of some children, or a None value.
+        Every node is opened at its own values like in open (with the proof cache if there is one),
+        `data` only has to have the shape of the tree
     */
     pub fn proof (&self, index: Vec<usize>, data: &[Vec<u8>]) -> Vec<Option<ProofNode>> {
         assert!(data.len().is_multiple_of(self.width), "Please give a tree that is compeletly filled, i.e. log_{{width}}(data) is a natural number");
         assert!(!index.is_empty(), "Please give a non empty index");
-        let width = self.width;
-        // The following line creates a vector, on each index is a vector which incidates which children nodes need to be proven
-        //println!("create index for proof");
-        let index_for_proof = Self::create_index_for_proof(index, width, self.depth());
-        //println!("done creating indices");
-        let tree = self.nodes.clone();
+        // For each layer the nodes on the paths of the indices and the slots that need to be proven in them
+        let touched = Self::touched_slots(&index, self.width, self.depth()).expect("the index is outside of the tree");
 
         /* We observe that we can compute each node in parallel, so first we flatten the tree structure
             While, perserving the layer and node index */
-        let flattened_tree: Vec<(usize, usize, &VerkleNode)> = tree.iter()
+        let flattened_tree: Vec<(usize, usize)> = self.nodes.iter()
             .enumerate()
-            .flat_map(|(layer_index,layer)|{
-                layer.iter().enumerate().map(move |(node_index, node)| (layer_index, node_index, node))
-            }).collect();
-        
-        flattened_tree.par_iter().map(|(layer_index, node_index, node)|{
-            let mut values:Vec<Vec<u8>> = Vec::new();
-            let indices: &Vec<usize> = &index_for_proof[*layer_index][*node_index];
-            let mut proof_indices: Vec<usize> = Vec::new();
-                for index in indices{
-                    proof_indices.push(index % width);
+            .flat_map(|(layer_index, layer)| (0..layer.len()).map(move |node_index| (layer_index, node_index)))
+            .collect();
+
+        flattened_tree.par_iter().map(|(layer_index, node_index)| {
+            touched[*layer_index].get(node_index).map(|slots| {
+                self.open_node(*layer_index, *node_index, slots).expect("failed to generate proof for node")
+            })
+        }).collect()
+    }
+
+    // Verifies a proof from proof(), the None values may already be deleted. The same checks as verify_proof
+    pub fn verify (root: Commitment, tree_proofs: Vec<Option<ProofNode>>, width: usize, indices: Vec<usize>, depth: usize, data: Vec<Vec<u8>>, verifier_params: VerifierParams) -> bool {
+        let proof = VerkleProof {
+            proofs: tree_proofs.into_iter().flatten().collect(),
+        };
+        Self::verify_proof(&root, &proof, &indices, &data, width, depth, &verifier_params)
+    }
+
+    pub fn map_commitment_to_vec_u8(com: &Commitment) -> Vec<u8> {
+        let mut old_commitment_bytes: Vec<u8> = vec![];
+        com.serialize(&mut old_commitment_bytes, true).unwrap();
+        old_commitment_bytes
+        //Vec::new()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /* The layers are built from reversed chunks (see create_leaf_nodes and build_layer),
+        so the position of a node is not simply index / width. The next two functions keep that mapping in one place */
+
+    // Returns the leaf node holding data index `index` and the slot of the value in that node
+    fn leaf_position(index: usize, width: usize, depth: usize) -> (usize, usize) {
+        let leaves = width.pow((depth - 1) as u32);
+        (leaves - 1 - index / width, index % width)
+    }
+
+    // Returns the parent of node `node_index` in layer `layer` (the root is layer 0) and the slot of the child in the parent
+    fn parent_position(node_index: usize, width: usize, layer: usize) -> (usize, usize) {
+        let parents = width.pow((layer - 1) as u32);
+        (parents - 1 - node_index / width, node_index % width)
+    }
+
+    // Start staging writes, nothing is applied to the tree before WriteBatch::commit
+    pub fn batch(&mut self) -> WriteBatch<'_> {
+        WriteBatch {
+            tree: self,
+            staged: BTreeMap::new(),
+        }
+    }
+
+    /* Computes the new nodes for a set of writes without touching the tree.
+        Returns (layer, node index, new node) for every node on the affected paths */
+    fn recompute_paths(&self, writes: &BTreeMap<usize, Vec<u8>>) -> Result<Vec<(usize, usize, VerkleNode)>, VerkleTreeError> {
+        let width = self.width;
+        let depth = self.depth();
+        // For each node of the current layer the slots that change and their new values
+        let mut changes: BTreeMap<usize, BTreeMap<usize, Vec<u8>>> = BTreeMap::new();
+        for (index, value) in writes {
+            let (node, slot) = Self::leaf_position(*index, width, depth);
+            changes.entry(node).or_default().insert(slot, value.clone());
+        }
+
+        let mut new_nodes: Vec<(usize, usize, VerkleNode)> = Vec::new();
+        for layer in (0..depth).rev() {
+            let changed: Vec<(usize, VerkleNode)> = changes
+                .par_iter()
+                .map(|(node_index, slots)| {
+                    let node = &self.nodes[layer][*node_index];
+                    Ok((*node_index, self.update_node(node, slots)?))
+                })
+                .collect::<Result<Vec<_>, VerkleTreeError>>()?;
+
+            // The new commitments are the changed values of the layer above
+            let mut parent_changes: BTreeMap<usize, BTreeMap<usize, Vec<u8>>> = BTreeMap::new();
+            if layer > 0 {
+                for (node_index, node) in changed.iter() {
+                    let (parent, slot) = Self::parent_position(*node_index, width, layer);
+                    parent_changes
+                        .entry(parent)
+                        .or_default()
+                        .insert(slot, Self::map_commitment_to_vec_u8(&node.commitment));
                 }
-            if indices.is_empty() {
-                None
             }
-            else if node.children {
-                for i in 0 .. width{
-                    let com = &tree[layer_index+1][i].commitment;
-                    let child_commitment = Self::map_commitment_to_vec_u8(&com);
-                    values.push(child_commitment);
-                }
-                Some(self.proof_node(&node.commitment, values, &proof_indices).expect("failed to generate proof for node"))
+            new_nodes.extend(changed.into_iter().map(|(node_index, node)| (layer, node_index, node)));
+            changes = parent_changes;
+        }
+        Ok(new_nodes)
+    }
+
+    // Applies the slot changes to a copy of the node, all siblings go through one batch update
+    fn update_node(&self, node: &VerkleNode, slots: &BTreeMap<usize, Vec<u8>>) -> Result<VerkleNode, VerkleTreeError> {
+        let mut new_node = node.clone();
+        let mut changed_index: Vec<usize> = Vec::new();
+        let mut value_before: Vec<&[u8]> = Vec::new();
+        let mut value_after: Vec<&[u8]> = Vec::new();
+        for (slot, value) in slots {
+            if node.values[*slot] != *value {
+                changed_index.push(*slot);
+                value_before.push(&node.values[*slot]);
+                value_after.push(value);
             }
-            else {
-                // no children so values from the data
-                for i in 0 .. width{
-                    values.push(data[i].clone());
-                }
-                Some(self.proof_node(&node.commitment, values, &proof_indices).expect("failed to generate proof for node"))
+            new_node.values[*slot] = value.clone();
+        }
+        if changed_index.len() == self.width {
+            // batch_update does not accept a change of the full vector
+            new_node.commitment = Commitment::new(&self.pp, &new_node.values).map_err(|_| VerkleTreeError::UpdateError)?;
+            if new_node.proofs.is_some() {
+                let slots: Vec<usize> = (0..self.width).collect();
+                new_node.proofs = Some(Proof::batch_new(&self.pp, &new_node.values, &slots).map_err(|_| VerkleTreeError::UpdateError)?);
+            }
+        }
+        else {
+            new_node.commitment
+                .batch_update(&self.pp, &changed_index, &value_before, &value_after)
+                .map_err(|_| VerkleTreeError::UpdateError)?;
+            // Each cached opening moves by one exponentiation per changed slot
+            if let Some(proofs) = new_node.proofs.as_mut() {
+                for (proof_index, proof) in proofs.iter_mut().enumerate() {
+                    for ((changed, before), after) in changed_index.iter().zip(value_before.iter()).zip(value_after.iter()) {
+                        proof
+                            .update(&self.pp, proof_index, *changed, before, after)
+                            .map_err(|_| VerkleTreeError::UpdateError)?;
+                    }
                 }
-        }).collect()
-        
+            }
+        }
+        Ok(new_node)
     }
 
-    fn proof_node(&self, commit:&Commitment, values: Vec<Vec<u8>>, indices: &Vec<usize>) -> Result<ProofNode, VerkleTreeError>{
-        let proof = Proof::batch_new_aggregated(&self.pp, commit, &values, indices);
-        match proof {
-            Ok(proof) => {
-                let proof_node = ProofNode {
-                    commitment: commit.clone(),
-                    proof,
-                    indices: indices.to_vec(),
-                    values,
-                };
-                Ok(proof_node)
+/* The next functions prove and verify that a set of writes moves the root from one commitment to another */
+
+    // For each layer (root first) the touched nodes and the slots that are opened in them
+    fn touched_slots(indices: &[usize], width: usize, depth: usize) -> Result<Vec<BTreeMap<usize, BTreeSet<usize>>>, VerkleTreeError> {
+        let mut layers: Vec<BTreeMap<usize, BTreeSet<usize>>> = vec![BTreeMap::new(); depth];
+        for index in indices {
+            if *index >= width.pow(depth as u32) {
+                return Err(VerkleTreeError::InvalidIndex);
             }
-            Err(_) => Err(VerkleTreeError::ProofGenerateError),
-        }
-    }
-
-    fn create_index_for_proof(index: Vec<usize>, width: usize, depth: usize) -> Vec<Vec<Vec<usize>>> {
-
-        /* For widht = 3, depth=2 index = [1,2,6] This creates:
-            [[[0, 2]],
-                [[1, 2], [], [6]]]
-            The first vector is the root, this node needs to prove children on position 0 adn 2 of the next layer.
-            The second vector is the second layer, need to prove node 1, 2 and 6 of the next layer in this case the data set
-            */
-        let mut tree_path: Vec<Vec<Vec<usize>>>  = Vec::new();
-        let mut indexes = index.clone();
-        for level in 2.. (depth+1){
-            let data_level_above = width.pow((depth+1-level) as u32);
-            // This creates for each parent node an empty vector
-            let mut level_above: Vec<Vec<usize>> = vec![vec![]; data_level_above];
-            // This is a hash set to only insert if the node is "new"
-            let mut new_indices: HashSet<usize> = HashSet::new();
-            /* The loop adds the index of the child that needs to be proven in the vector of the parent node
-                Also the loop creates a vector for the indices of the parent node for the next layer*/
-            for i in 0.. indexes.len(){
-                level_above[indexes[i] / width].push(indexes[i]);
-                new_indices.insert(indexes[i]/ width);
+            let (mut node, slot) = Self::leaf_position(*index, width, depth);
+            layers[depth - 1].entry(node).or_default().insert(slot);
+            for layer in (1..depth).rev() {
+                let (parent, slot) = Self::parent_position(node, width, layer);
+                layers[layer - 1].entry(parent).or_default().insert(slot);
+                node = parent;
             }
-            tree_path.push(level_above);
-            indexes = new_indices.into_iter().collect();
-                
-        }
-        // We need to add the root manually
-        if depth > 1 {
-            let mut node_root: Vec<usize> = Vec::new();
-            for i in 0 .. width{
-                let child  = &tree_path[tree_path.len()-1][i];
-                if !child.is_empty(){
-                    node_root.push(i);
-                }
+        }
+        Ok(layers)
+    }
+
+    // Opens the given slots of a node, the proof node carries the opened values only
+    fn open_node(&self, layer: usize, node_index: usize, slots: &BTreeSet<usize>) -> Result<ProofNode, VerkleTreeError> {
+        self.open_slots(&self.nodes[layer][node_index], slots)
+    }
+
+    fn open_slots(&self, node: &VerkleNode, slots: &BTreeSet<usize>) -> Result<ProofNode, VerkleTreeError> {
+        let indices: Vec<usize> = slots.iter().cloned().collect();
+        let values: Vec<Vec<u8>> = indices.iter().map(|slot| node.values[*slot].clone()).collect();
+        let proof = match node.proofs.as_ref() {
+            Some(proofs) => {
+                let cached: Vec<Proof> = indices.iter().map(|slot| proofs[*slot].clone()).collect();
+                Proof::same_commit_aggregate(&node.commitment, &cached, &indices, &values, self.width)
             }
-            tree_path.push( vec![node_root]);
+            None => Proof::batch_new_aggregated(&self.pp, &node.commitment, &node.values, &indices),
         }
-        else {
-            // If the tree has 1 layer, the "root" is just the commitment of all indices
-            tree_path = vec![vec![index]];
+        .map_err(|_| VerkleTreeError::ProofGenerateError)?;
+        Ok(ProofNode {
+            commitment: node.commitment.clone(),
+            proof,
+            values,
+            indices,
+        })
+    }
+
+    // Opens the current values on the paths of the given indices, root first and layer by layer
+    pub fn open(&self, indices: &[usize]) -> Result<VerkleProof, VerkleTreeError> {
+        if indices.is_empty() {
+            return Err(VerkleTreeError::ProofGenerateError);
         }
-        tree_path.reverse();
-        tree_path
+        let touched = Self::touched_slots(indices, self.width, self.depth())?;
+        let flattened: Vec<(usize, usize, &BTreeSet<usize>)> = touched.iter()
+            .enumerate()
+            .flat_map(|(layer, nodes)| nodes.iter().map(move |(node_index, slots)| (layer, *node_index, slots)))
+            .collect();
+        let proofs = flattened
+            .par_iter()
+            .map(|(layer, node_index, slots)| self.open_node(*layer, *node_index, slots))
+            .collect::<Result<Vec<ProofNode>, VerkleTreeError>>()?;
+        Ok(VerkleProof { proofs })
     }
-    
 
-    // This function computes batch proofs, is also works if the NONE values are already deleted.
-    pub fn verify (root: Commitment, mut tree_proofs: Vec<Option<ProofNode>>, width: usize, indices: Vec<usize>, depth: usize, data: Vec<Vec<u8>>, verifier_params: VerifierParams) -> bool {
-        //tln!("start checking parameters");
-        assert!(tree_proofs[0].is_some());
+    /* Opens every slot of the nodes on the paths of all updated indices, before and after the updates.
+        This does not apply the updates, the new nodes are computed like in WriteBatch::commit */
+    pub fn prove_transition(&self, updates: &[(usize, Vec<u8>)]) -> Result<TransitionProof, VerkleTreeError> {
+        if updates.is_empty() {
+            return Err(VerkleTreeError::ProofGenerateError);
+        }
+        let mut writes: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+        for (index, value) in updates {
+            if *index >= self.width.pow(self.depth() as u32) {
+                return Err(VerkleTreeError::InvalidIndex);
+            }
+            writes.insert(*index, value.clone());
+        }
+        // The paths of the writes, root first and layer by layer like open
+        let new_nodes: BTreeMap<(usize, usize), VerkleNode> = self
+            .recompute_paths(&writes)?
+            .into_iter()
+            .map(|(layer, node_index, node)| ((layer, node_index), node))
+            .collect();
+        let all_slots: BTreeSet<usize> = (0..self.width).collect();
+        let old = new_nodes
+            .par_iter()
+            .map(|((layer, node_index), _)| self.open_node(*layer, *node_index, &all_slots))
+            .collect::<Result<Vec<ProofNode>, VerkleTreeError>>()?;
+        let new = new_nodes
+            .par_iter()
+            .map(|(_, node)| self.open_slots(node, &all_slots))
+            .collect::<Result<Vec<ProofNode>, VerkleTreeError>>()?;
+        Ok(TransitionProof {
+            old: VerkleProof { proofs: old },
+            new: VerkleProof { proofs: new },
+        })
+    }
 
-        // Check if the root is correct
-        if root != tree_proofs[0].as_ref().unwrap().commitment {
-            println!("Root commitment is not correct");
-            return false;
+    /* Matches the proof nodes with the paths of `indices` (in the order of open), then checks the root,
+        that each opened slot of an inner node holds the commitment of the child below it, and all openings.
+        Returns the proof nodes by (layer, node index) */
+    fn check_paths<'a>(root: &Commitment, indices: &[usize], proof: &'a VerkleProof, width: usize, depth: usize, verifier_params: &VerifierParams) -> Option<BTreeMap<(usize, usize), &'a ProofNode>> {
+        if indices.is_empty() || depth == 0 {
+            return None;
         }
+        let touched = Self::touched_slots(indices, width, depth).ok()?;
 
-        tree_proofs.retain(|node| node.is_some());
-        // The expected length
-        let check_vector: Vec<Vec<Vec<usize>>> = Self::create_index_for_proof(indices, width, depth);
-        // To check the expected length we filter on non empty vectors. 
-        let length = check_vector.iter()
-            .flat_map(|layer| layer.iter()) 
-            .filter(|node_vec| {
-                !node_vec.is_empty()})
-            .count();
-        if tree_proofs.len() != length {
-            println!("The tree proofs vector is not of the correct length");
-            return false;
+        let proofs = &proof.proofs;
+        let mut positions: BTreeMap<(usize, usize), &ProofNode> = BTreeMap::new();
+        let mut counter = 0;
+        for (layer, nodes) in touched.iter().enumerate() {
+            for (node_index, slots) in nodes {
+                match proofs.get(counter) {
+                    Some(node) if node.indices.iter().eq(slots.iter()) && node.values.len() == node.indices.len() => {
+                        positions.insert((layer, *node_index), node);
+                    }
+                    _ => return None,
+                }
+                counter += 1;
+            }
         }
-        //println!("start making vector");
-        // To find the commitment value easier, we dont save the ProofNodes but the commitments in the next vector
-        let mut commitments_vector: Vec<Vec<u8>> = tree_proofs.iter().map(|proof_node|
-            {
-                let node = proof_node.as_ref().unwrap();
-                Self::map_commitment_to_vec_u8(&node.commitment)
+        if counter != proofs.len() || proofs[0].commitment != *root {
+            return None;
+        }
+
+        for ((layer, node_index), node) in positions.iter() {
+            if *layer == 0 {
+                continue;
             }
-        ).collect();
-        data.iter().for_each(|d| commitments_vector.push(d.to_vec()));
-        
-        //println!("start verify");
-        tree_proofs.par_iter().all(|proof_node| {
-            if let Some(node) = proof_node{
-                let b1 = Proof::same_commit_batch_verify(&node.proof, &verifier_params, &node.commitment, &node.indices, &node.values);
-                // For simplicity we check if there is a commitment that matches
-                let b2 = node.values.par_iter().all(|point| {
-                    commitments_vector.par_iter().any(|n| n == point)
-                });
-                b1 & b2
+            let (parent, slot) = Self::parent_position(*node_index, width, *layer);
+            let parent_node = positions.get(&(layer - 1, parent))?;
+            let position = parent_node.indices.iter().position(|i| *i == slot).unwrap();
+            if parent_node.values[position] != Self::map_commitment_to_vec_u8(&node.commitment) {
+                return None;
+            }
+        }
+
+        let openings_valid = positions.par_iter().all(|(_, node)| {
+            Proof::same_commit_batch_verify(&node.proof, verifier_params, &node.commitment, &node.indices, &node.values)
+        });
+        if !openings_valid {
+            return None;
+        }
+        Some(positions)
+    }
+
+    // Verifies a proof from open(indices) against the root, values[i] is the claimed value at indices[i]
+    pub fn verify_proof(root: &Commitment, proof: &VerkleProof, indices: &[usize], values: &[Vec<u8>], width: usize, depth: usize, verifier_params: &VerifierParams) -> bool {
+        if indices.len() != values.len() {
+            return false;
+        }
+        let positions = match Self::check_paths(root, indices, proof, width, depth, verifier_params) {
+            Some(positions) => positions,
+            None => return false,
+        };
+        indices.iter().zip(values.iter()).all(|(index, value)| {
+            let (node, slot) = Self::leaf_position(*index, width, depth);
+            let leaf = positions[&(depth - 1, node)];
+            let position = leaf.indices.iter().position(|i| *i == slot).unwrap();
+            leaf.values[position] == *value
+        })
+    }
+
+    /* Matches proof nodes that open every slot with the given (layer, node index) positions, then checks the root,
+        that each inner node holds the commitments of its children on the paths, and all openings */
+    fn check_full_paths<'a>(root: &Commitment, proof: &'a VerkleProof, positions: &[(usize, usize)], width: usize, verifier_params: &VerifierParams) -> Option<BTreeMap<(usize, usize), &'a ProofNode>> {
+        let proofs = &proof.proofs;
+        if proofs.is_empty() || proofs.len() != positions.len() || proofs[0].commitment != *root {
+            return None;
+        }
+        let all_slots: Vec<usize> = (0..width).collect();
+        let nodes: BTreeMap<(usize, usize), &ProofNode> = positions.iter().cloned().zip(proofs.iter()).collect();
+        if nodes.values().any(|node| node.indices != all_slots || node.values.len() != width) {
+            return None;
+        }
+        for ((layer, node_index), node) in nodes.iter() {
+            if *layer == 0 {
+                continue;
             }
-            else {
-                true
+            let (parent, slot) = Self::parent_position(*node_index, width, *layer);
+            if nodes.get(&(layer - 1, parent))?.values[slot] != Self::map_commitment_to_vec_u8(&node.commitment) {
+                return None;
             }
+        }
+        let openings_valid = nodes.par_iter().all(|(_, node)| {
+            Proof::same_commit_batch_verify(&node.proof, verifier_params, &node.commitment, &node.indices, &node.values)
         });
-        //println!("done verify");
+        if !openings_valid {
+            return None;
+        }
+        Some(nodes)
+    }
+
+    /* Checks that the updates move old_root to new_root with the verifier parameters only. The updates are
+        (index, new value), later entries for an index win. Both sides of the proof have to open every slot of the
+        touched nodes against their root, and each new node has to hold the old values except for the written values
+        in the leaves and the new child commitments in the inner nodes, so nothing else changed */
+    pub fn verify_transition(old_root: &Commitment, new_root: &Commitment, updates: &[(usize, Vec<u8>)], transition: &TransitionProof, width: usize, depth: usize, verifier_params: &VerifierParams) -> bool {
+        if updates.is_empty() || width < 2 || depth == 0 {
+            return false;
+        }
+        let mut writes: BTreeMap<usize, &Vec<u8>> = BTreeMap::new();
+        for (index, value) in updates {
+            writes.insert(*index, value);
+        }
+        let indices: Vec<usize> = writes.keys().cloned().collect();
+        let touched = match Self::touched_slots(&indices, width, depth) {
+            Ok(touched) => touched,
+            Err(_) => return false,
+        };
+        let positions: Vec<(usize, usize)> = touched.iter()
+            .enumerate()
+            .flat_map(|(layer, nodes)| nodes.keys().map(move |node_index| (layer, *node_index)))
+            .collect();
+        let old_nodes = match Self::check_full_paths(old_root, &transition.old, &positions, width, verifier_params) {
+            Some(nodes) => nodes,
+            None => return false,
+        };
+        let new_nodes = match Self::check_full_paths(new_root, &transition.new, &positions, width, verifier_params) {
+            Some(nodes) => nodes,
+            None => return false,
+        };
+
+        // The changed slots of each node bottom up, the new commitments of a layer are the changes of the layer above
+        let mut changes: BTreeMap<(usize, usize), BTreeMap<usize, Vec<u8>>> = BTreeMap::new();
+        for (index, value) in writes {
+            let (node, slot) = Self::leaf_position(index, width, depth);
+            changes.entry((depth - 1, node)).or_default().insert(slot, value.clone());
+        }
+        for (layer, node_index) in positions.iter().rev() {
+            let old_node = old_nodes[&(*layer, *node_index)];
+            let new_node = new_nodes[&(*layer, *node_index)];
+            let changed = changes.remove(&(*layer, *node_index)).unwrap_or_default();
+            for slot in 0..width {
+                let expected = changed.get(&slot).unwrap_or(&old_node.values[slot]);
+                if new_node.values[slot] != *expected {
+                    return false;
+                }
+            }
+            if *layer > 0 {
+                let (parent, slot) = Self::parent_position(*node_index, width, *layer);
+                changes.entry((layer - 1, parent)).or_default().insert(slot, Self::map_commitment_to_vec_u8(&new_node.commitment));
+            }
+        }
         true
     }
 
-    pub fn map_commitment_to_vec_u8(com: &Commitment) -> Vec<u8> {
-        let mut old_commitment_bytes: Vec<u8> = vec![];
-        com.serialize(&mut old_commitment_bytes, true).unwrap();
-        old_commitment_bytes
-        //Vec::new()
+    /* Refreshes a proof from open(indices) with the TreeUpdates of later commits (in the order of the commits),
+        so the holder of a proof does not need the tree. Every opening on the paths moves with one multi-exponentiation
+        per commit and every commitment is replaced by the new one of the commit.
+        Aggregated openings (more than one slot in a node) can not be refreshed this way, because the aggregation
+        depends on the old commitment. Those nodes are only accepted if no commit changes them, keep one proof per index
+        to refresh them all */
+    pub fn update_proof(proof: &VerkleProof, indices: &[usize], updates: &[TreeUpdate], width: usize, depth: usize, prover_params: &ProverParams) -> Result<VerkleProof, VerkleTreeError> {
+        let touched = Self::touched_slots(indices, width, depth)?;
+        let positions: Vec<(usize, usize)> = touched.iter()
+            .enumerate()
+            .flat_map(|(layer, nodes)| nodes.keys().map(move |node_index| (layer, *node_index)))
+            .collect();
+        if positions.len() != proof.proofs.len() {
+            return Err(VerkleTreeError::UpdateError);
+        }
+
+        let mut proofs = proof.proofs.clone();
+        for update in updates {
+            let changes = update.node_changes(width, depth)?;
+            proofs = positions
+                .par_iter()
+                .zip(proofs.into_par_iter())
+                .map(|(position, node)| match changes.get(position) {
+                    Some(change) => Self::update_proof_node(node, change, prover_params),
+                    None => Ok(node),
+                })
+                .collect::<Result<Vec<ProofNode>, VerkleTreeError>>()?;
+        }
+        Ok(VerkleProof { proofs })
     }
 
-    pub fn depth(&self) -> usize {
-        self.nodes.len()
+    fn update_proof_node(mut node: ProofNode, change: &NodeChange, prover_params: &ProverParams) -> Result<ProofNode, VerkleTreeError> {
+        if node.commitment != *change.before || node.indices.len() != 1 {
+            return Err(VerkleTreeError::UpdateError);
+        }
+        let slot = node.indices[0];
+        let changed_index: Vec<usize> = change.slots.iter().map(|(changed, _, _)| *changed).collect();
+        let value_before: Vec<&Vec<u8>> = change.slots.iter().map(|(_, before, _)| before).collect();
+        let value_after: Vec<&Vec<u8>> = change.slots.iter().map(|(_, _, after)| after).collect();
+        node.proof
+            .batch_update(prover_params, slot, &changed_index, &value_before, &value_after)
+            .map_err(|_| VerkleTreeError::UpdateError)?;
+        node.commitment = change.after.clone();
+        if let Some((_, _, after)) = change.slots.iter().find(|(changed, _, _)| *changed == slot) {
+            node.values[0] = after.clone();
+        }
+        Ok(node)
     }
 
     pub fn root_commitment(&self) -> Option<Commitment>{
@@ -297,9 +637,156 @@ impl VerkleTree {
     }
 }
 
+/* A set of staged writes. The tree is only changed by commit, in one step after all new commitments are computed,
+    so the tree shows either the old root or the new one. Dropping the batch or calling rollback discards the writes */
+pub struct WriteBatch<'a> {
+    tree: &'a mut VerkleTree,
+    staged: BTreeMap<usize, Vec<u8>>,
+}
+
+impl WriteBatch<'_> {
+    // Stage a write of `value` to data index `index`, a later write to the same index replaces it
+    pub fn insert(&mut self, index: usize, value: Vec<u8>) -> Result<(), VerkleTreeError> {
+        if index >= self.tree.width.pow(self.tree.depth() as u32) {
+            return Err(VerkleTreeError::InvalidIndex);
+        }
+        self.staged.insert(index, value);
+        Ok(())
+    }
+
+    // Stage a write that only succeeds if the current value (staged or in the tree) is `value_before`
+    pub fn update(&mut self, index: usize, value_before: &[u8], value_after: Vec<u8>) -> Result<(), VerkleTreeError> {
+        if index >= self.tree.width.pow(self.tree.depth() as u32) {
+            return Err(VerkleTreeError::InvalidIndex);
+        }
+        let current = match self.staged.get(&index) {
+            Some(value) => value.as_slice(),
+            None => {
+                let (node, slot) = VerkleTree::leaf_position(index, self.tree.width, self.tree.depth());
+                self.tree.nodes[self.tree.depth() - 1][node].values[slot].as_slice()
+            }
+        };
+        if current != value_before {
+            return Err(VerkleTreeError::UpdateError);
+        }
+        self.staged.insert(index, value_after);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.staged.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /* Recompute the affected paths once and swap the new nodes in. On an error the tree is left as it was.
+        Returns the changes for VerkleTree::update_proof */
+    pub fn commit(self) -> Result<TreeUpdate, VerkleTreeError> {
+        let mut update = TreeUpdate {
+            writes: Vec::new(),
+            commitments: Vec::new(),
+        };
+        if self.staged.is_empty() {
+            return Ok(update);
+        }
+        let new_nodes = self.tree.recompute_paths(&self.staged)?;
+        let (width, depth) = (self.tree.width, self.tree.depth());
+        for (index, value) in self.staged.iter() {
+            let (node, slot) = VerkleTree::leaf_position(*index, width, depth);
+            let before = &self.tree.nodes[depth - 1][node].values[slot];
+            if before != value {
+                update.writes.push(LeafWrite {
+                    index: *index,
+                    value_before: before.clone(),
+                    value_after: value.clone(),
+                });
+            }
+        }
+        for (layer, node_index, node) in new_nodes {
+            let old_node = &self.tree.nodes[layer][node_index];
+            if old_node.commitment != node.commitment {
+                update.commitments.push(NodeCommitments {
+                    layer,
+                    node: node_index,
+                    before: old_node.commitment.clone(),
+                    after: node.commitment.clone(),
+                });
+            }
+            self.tree.nodes[layer][node_index] = node;
+        }
+        Ok(update)
+    }
+
+    // Discard all staged writes
+    pub fn rollback(self) {}
+}
+
+impl TreeUpdate {
+    /* The changed nodes with their changed slots: the leaf writes in the leaves, and the old and new commitment
+        of a changed node in the slot of its parent. The update may come from elsewhere, so positions are checked */
+    fn node_changes(&self, width: usize, depth: usize) -> Result<BTreeMap<(usize, usize), NodeChange<'_>>, VerkleTreeError> {
+        let layer_size = |layer: usize| width.checked_pow(layer as u32).ok_or(VerkleTreeError::UpdateError);
+        let mut changes: BTreeMap<(usize, usize), NodeChange> = BTreeMap::new();
+        for commitments in self.commitments.iter() {
+            if commitments.layer >= depth || commitments.node >= layer_size(commitments.layer)? {
+                return Err(VerkleTreeError::UpdateError);
+            }
+            let change = NodeChange {
+                before: &commitments.before,
+                after: &commitments.after,
+                slots: Vec::new(),
+            };
+            if changes.insert((commitments.layer, commitments.node), change).is_some() {
+                return Err(VerkleTreeError::UpdateError);
+            }
+        }
+        for write in self.writes.iter() {
+            if write.index >= layer_size(depth)? {
+                return Err(VerkleTreeError::UpdateError);
+            }
+            let (node, slot) = VerkleTree::leaf_position(write.index, width, depth);
+            changes
+                .get_mut(&(depth - 1, node))
+                .ok_or(VerkleTreeError::UpdateError)?
+                .slots
+                .push((slot, write.value_before.clone(), write.value_after.clone()));
+        }
+        for commitments in self.commitments.iter().filter(|commitments| commitments.layer > 0) {
+            let (parent, slot) = VerkleTree::parent_position(commitments.node, width, commitments.layer);
+            let before = VerkleTree::map_commitment_to_vec_u8(&commitments.before);
+            let after = VerkleTree::map_commitment_to_vec_u8(&commitments.after);
+            changes
+                .get_mut(&(commitments.layer - 1, parent))
+                .ok_or(VerkleTreeError::UpdateError)?
+                .slots
+                .push((slot, before, after));
+        }
+        Ok(changes)
+    }
+}
+
 #[derive(Debug)]
 pub enum VerkleTreeError {
     BuildError,
     ProofGenerateError,
     EmptyTree,
+    InvalidIndex,
+    UpdateError,
+}
+
+/// Checking that len values fill a tree of the given width, i.e. len is a power of the width
+pub fn check_shape(len: usize, width: usize) -> Result<(), String> {
+    if width < 2 {
+        return Err("the width has to be at least 2".to_string());
+    }
+    let mut size = width;
+    while size < len {
+        size = size.saturating_mul(width);
+    }
+    if size != len {
+        return Err(format!("got {} values, the number of values has to be a power of the width {}", len, width));
+    }
+    Ok(())
 }
@@ -0,0 +1,258 @@
+#[cfg(test)]
+mod tests {
+
+    use crate::{paramgen_from_seed, VerkleProof_point as VerkleProof, VerkleTree_point as VerkleTree};
+    use pointproofs::pairings::Proof;
+
+    fn build_verkle_tree(width: usize, depth: u32) -> (VerkleTree, Vec<Vec<u8>>) {
+        let (prover_params, _) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        let datas = random_data(width.pow(depth));
+        let tree = VerkleTree::new(&datas, width, prover_params).unwrap();
+        (tree, datas)
+    }
+
+    fn random_data(len: usize) -> Vec<Vec<u8>> {
+        (0..len).map(|i| format!("this is message number {}", i).into_bytes()).collect()
+    }
+
+    fn rebuild(width: usize, datas: &[Vec<u8>]) -> VerkleTree {
+        let (prover_params, _) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        VerkleTree::new(datas, width, prover_params).unwrap()
+    }
+
+    #[test]
+    fn test_batch_commit() {
+        let width = 4;
+        let (mut tree, mut datas) = build_verkle_tree(width, 3);
+
+        let mut batch = tree.batch();
+        batch.insert(1, b"new value 1".to_vec()).unwrap();
+        batch.insert(2, b"new value 2".to_vec()).unwrap();
+        batch.insert(63, b"new value 63".to_vec()).unwrap();
+        batch.update(2, b"new value 2", b"newer value 2".to_vec()).unwrap();
+        batch.commit().unwrap();
+
+        datas[1] = b"new value 1".to_vec();
+        datas[2] = b"newer value 2".to_vec();
+        datas[63] = b"new value 63".to_vec();
+        assert_eq!(tree.root_commitment(), rebuild(width, &datas).root_commitment());
+    }
+
+    #[test]
+    fn test_batch_full_node() {
+        let width = 4;
+        let (mut tree, mut datas) = build_verkle_tree(width, 2);
+
+        let mut batch = tree.batch();
+        for i in 0..width {
+            batch.insert(i, format!("new value {}", i).into_bytes()).unwrap();
+            datas[i] = format!("new value {}", i).into_bytes();
+        }
+        batch.commit().unwrap();
+        assert_eq!(tree.root_commitment(), rebuild(width, &datas).root_commitment());
+    }
+
+    #[test]
+    fn test_batch_rollback() {
+        let width = 4;
+        let (mut tree, datas) = build_verkle_tree(width, 2);
+        let root = tree.root_commitment();
+
+        let mut batch = tree.batch();
+        batch.insert(3, b"new value".to_vec()).unwrap();
+        batch.rollback();
+        assert_eq!(tree.root_commitment(), root);
+
+        let mut batch = tree.batch();
+        batch.insert(3, b"new value".to_vec()).unwrap();
+        drop(batch);
+        assert_eq!(tree.root_commitment(), root);
+
+        let mut batch = tree.batch();
+        assert!(batch.insert(16, b"new value".to_vec()).is_err());
+        assert!(batch.update(3, b"not the old value", b"new value".to_vec()).is_err());
+        assert!(batch.update(3, &datas[3], b"new value".to_vec()).is_ok());
+    }
+
+    #[test]
+    fn test_transition_proof() {
+        let width = 4;
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        let datas = random_data(width.pow(3));
+        let mut tree = VerkleTree::new(&datas, width, prover_params).unwrap();
+        let old_root = tree.root_commitment().unwrap();
+        let depth = tree.depth();
+
+        let updates = vec![
+            (0, b"new value 0".to_vec()),
+            (5, b"new value 5".to_vec()),
+            (6, b"new value 6".to_vec()),
+            (60, b"new value 60".to_vec()),
+        ];
+        let transition = tree.prove_transition(&updates).unwrap();
+        // the same writes and one more in a node on the paths
+        let mut sneaky_updates = updates.clone();
+        sneaky_updates.push((7, b"sneaky value 7".to_vec()));
+        let sneaky = tree.prove_transition(&sneaky_updates).unwrap();
+
+        let mut batch = tree.batch();
+        for (index, value) in updates.iter() {
+            batch.insert(*index, value.clone()).unwrap();
+        }
+        batch.commit().unwrap();
+        let new_root = tree.root_commitment().unwrap();
+
+        assert!(VerkleTree::verify_transition(&old_root, &new_root, &updates, &transition, width, depth, &verifier_params));
+        // the roots are not interchangeable
+        assert!(!VerkleTree::verify_transition(&new_root, &new_root, &updates, &transition, width, depth, &verifier_params));
+        assert!(!VerkleTree::verify_transition(&old_root, &old_root, &updates, &transition, width, depth, &verifier_params));
+        // a different write set does not lead to the new root
+        let mut other_updates = updates.clone();
+        other_updates[1].1 = b"another value".to_vec();
+        assert!(!VerkleTree::verify_transition(&old_root, &new_root, &other_updates, &transition, width, depth, &verifier_params));
+        // the proof has to cover exactly the touched paths
+        assert!(!VerkleTree::verify_transition(&old_root, &new_root, &updates[..2], &transition, width, depth, &verifier_params));
+        // a tampered old value is rejected
+        let mut tampered = transition.clone();
+        let last = tampered.old.proofs.len() - 1;
+        tampered.old.proofs[last].values[0] = b"not the old value".to_vec();
+        assert!(!VerkleTree::verify_transition(&old_root, &new_root, &updates, &tampered, width, depth, &verifier_params));
+        // a change of a slot that is not written is caught, every slot of the touched nodes is opened
+        let sneaky_root = sneaky.new.proofs[0].commitment.clone();
+        assert!(VerkleTree::verify_transition(&old_root, &sneaky_root, &sneaky_updates, &sneaky, width, depth, &verifier_params));
+        assert!(!VerkleTree::verify_transition(&old_root, &sneaky_root, &updates, &sneaky, width, depth, &verifier_params));
+    }
+
+    #[test]
+    fn test_update_proof() {
+        let width = 4;
+        let (prover_params, _) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        let (mut tree, _) = build_verkle_tree(width, 3);
+        let depth = tree.depth();
+        let proof_5 = tree.open(&[5]).unwrap();
+        let proof_42 = tree.open(&[42]).unwrap();
+        let aggregated = tree.open(&[1, 42]).unwrap();
+
+        // changes the value of index 5 itself, a sibling of it and a leaf in another subtree
+        let mut batch = tree.batch();
+        batch.insert(5, b"new value 5".to_vec()).unwrap();
+        batch.insert(6, b"new value 6".to_vec()).unwrap();
+        batch.insert(60, b"new value 60".to_vec()).unwrap();
+        let first = batch.commit().unwrap();
+        assert_eq!(first.writes.iter().map(|write| write.index).collect::<Vec<usize>>(), vec![5, 6, 60]);
+        // then every value of the node of index 5
+        let mut batch = tree.batch();
+        for i in 4..8 {
+            batch.insert(i, format!("newer value {}", i).into_bytes()).unwrap();
+        }
+        let updates = vec![first, batch.commit().unwrap()];
+
+        for (index, proof) in [(5, proof_5), (42, proof_42)] {
+            let refreshed = VerkleTree::update_proof(&proof, &[index], &updates, width, depth, &prover_params).unwrap();
+            let expected = tree.open(&[index]).unwrap();
+            assert_eq!(refreshed.proofs.len(), expected.proofs.len());
+            for (refreshed, expected) in refreshed.proofs.iter().zip(expected.proofs.iter()) {
+                assert_eq!(refreshed.commitment, expected.commitment);
+                assert_eq!(refreshed.proof, expected.proof);
+                assert_eq!(refreshed.indices, expected.indices);
+                assert_eq!(refreshed.values, expected.values);
+            }
+        }
+        assert!(VerkleTree::update_proof(&aggregated, &[1, 42], &updates, width, depth, &prover_params).is_err());
+        // the commits have to be applied in order
+        let reversed: Vec<_> = updates.iter().rev().cloned().collect();
+        assert!(VerkleTree::update_proof(&tree.open(&[5]).unwrap(), &[5], &reversed, width, depth, &prover_params).is_err());
+    }
+
+    #[test]
+    fn test_proof_cache() {
+        let width = 4;
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        let datas = random_data(width.pow(3));
+        let mut plain = VerkleTree::new(&datas, width, prover_params.clone()).unwrap();
+        let mut cached = VerkleTree::new_with_proof_cache(&datas, width, prover_params).unwrap();
+        assert!(!plain.has_proof_cache());
+        assert!(cached.has_proof_cache());
+
+        let assert_same_openings = |plain: &VerkleTree, cached: &VerkleTree, indices: &[usize]| {
+            let expected = plain.open(indices).unwrap();
+            let proof = cached.open(indices).unwrap();
+            assert_eq!(proof.proofs.len(), expected.proofs.len());
+            for (node, expected) in proof.proofs.iter().zip(expected.proofs.iter()) {
+                assert_eq!(node.commitment, expected.commitment);
+                assert_eq!(node.proof, expected.proof);
+                assert!(Proof::same_commit_batch_verify(&node.proof, &verifier_params, &node.commitment, &node.indices, &node.values));
+            }
+        };
+        assert_same_openings(&plain, &cached, &[0, 1, 17, 63]);
+
+        // proof() aggregates the cached proofs as well, every node is opened at its own values
+        let root = cached.root_commitment().unwrap();
+        for indices in [vec![0, 2, 3], vec![5, 17, 42]] {
+            let expected = plain.proof(indices.clone(), &datas);
+            let proof = cached.proof(indices.clone(), &datas);
+            assert_eq!(proof.len(), expected.len());
+            for (node, expected) in proof.iter().zip(expected.iter()) {
+                match (node, expected) {
+                    (Some(node), Some(expected)) => {
+                        assert_eq!(node.commitment, expected.commitment);
+                        assert_eq!(node.proof, expected.proof);
+                    }
+                    (None, None) => {}
+                    _ => panic!("the cached proof opens other nodes"),
+                }
+            }
+            let values: Vec<Vec<u8>> = indices.iter().map(|i| datas[*i].clone()).collect();
+            let opened = VerkleProof {
+                proofs: proof.into_iter().flatten().collect(),
+            };
+            assert!(VerkleTree::verify_proof(&root, &opened, &indices, &values, width, cached.depth(), &verifier_params));
+        }
+
+        // partial and full node updates refresh the cached openings
+        for tree in [&mut plain, &mut cached] {
+            let mut batch = tree.batch();
+            batch.insert(1, b"new value 1".to_vec()).unwrap();
+            for i in 40..44 {
+                batch.insert(i, format!("new value {}", i).into_bytes()).unwrap();
+            }
+            batch.commit().unwrap();
+        }
+        assert_eq!(plain.root_commitment(), cached.root_commitment());
+        assert_same_openings(&plain, &cached, &[0, 1, 17, 42, 63]);
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        let width = 4;
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is our Favourite very very long Seed", 0, width).unwrap();
+        let datas = random_data(width.pow(3));
+        let tree = VerkleTree::new(&datas, width, prover_params).unwrap();
+        let root = tree.root_commitment().unwrap();
+        let depth = tree.depth();
+
+        let indices = vec![0, 3, 17, 63];
+        let values: Vec<Vec<u8>> = indices.iter().map(|i| datas[*i].clone()).collect();
+        let proof = tree.open(&indices).unwrap();
+        assert!(VerkleTree::verify_proof(&root, &proof, &indices, &values, width, depth, &verifier_params));
+
+        let mut wrong_values = values.clone();
+        wrong_values[2] = b"not the value".to_vec();
+        assert!(!VerkleTree::verify_proof(&root, &proof, &indices, &wrong_values, width, depth, &verifier_params));
+        assert!(!VerkleTree::verify_proof(&root, &proof, &indices[..3], &values[..3], width, depth, &verifier_params));
+        let other_root = tree.open(&[5]).unwrap().proofs[1].commitment.clone();
+        assert!(!VerkleTree::verify_proof(&other_root, &proof, &indices, &values, width, depth, &verifier_params));
+
+        // verify checks the proofs of proof() the same way
+        let tree_proof = tree.proof(indices.clone(), &datas);
+        assert!(VerkleTree::verify(root.clone(), tree_proof.clone(), width, indices.clone(), depth, values.clone(), verifier_params.clone()));
+        assert!(!VerkleTree::verify(root.clone(), tree_proof.clone(), width, indices.clone(), depth, wrong_values, verifier_params.clone()));
+        assert!(!VerkleTree::verify(other_root, tree_proof, width, indices, depth, values, verifier_params));
+    }
+}